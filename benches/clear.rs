@@ -0,0 +1,49 @@
+//! Compare the strategies for clearing the pixel buffer.
+//!
+//! `Pixels::clear` fills the buffer one `u32` word at a time, instead of copying one 4-byte pixel
+//! at a time. Run with `cargo bench --bench clear`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pixels::{Error, PixelsBuilder};
+use std::hint::black_box;
+
+const COLOR: [u8; 4] = [0x12, 0x34, 0x56, 0xff];
+
+fn clear(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clear");
+
+    for (width, height) in [(320, 240), (1920, 1080), (3840, 2160)] {
+        let builder = PixelsBuilder::new_offscreen(width, height, width, height)
+            .request_adapter_options(pixels::wgpu::RequestAdapterOptions {
+                force_fallback_adapter: true,
+                ..pixels::wgpu::RequestAdapterOptions::default()
+            });
+        let mut pixels = match builder.build() {
+            Ok(pixels) => pixels,
+            Err(Error::AdapterNotFound) => {
+                eprintln!("Skipping benchmark: no fallback adapter available");
+                return;
+            }
+            Err(err) => panic!("Unable to create offscreen pixel buffer: {err}"),
+        };
+        let size = format!("{width}x{height}");
+
+        // The manual loop shown in the `Pixels::render` docs before `Pixels::clear` existed
+        group.bench_function(BenchmarkId::new("byte loop", &size), |b| {
+            b.iter(|| {
+                for pixel in black_box(pixels.frame_mut()).chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&COLOR);
+                }
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("Pixels::clear", &size), |b| {
+            b.iter(|| pixels.clear(black_box(COLOR)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, clear);
+criterion_main!(benches);
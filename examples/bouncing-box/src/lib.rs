@@ -0,0 +1,80 @@
+//! The bouncing box shared by the [`minimal-winit`] and [`softbuffer`] examples.
+//!
+//! Both examples draw the same frames with this code, so the only difference between them is how
+//! the frame gets to the window.
+//!
+//! [`minimal-winit`]: https://github.com/parasyte/pixels/tree/master/examples/minimal-winit
+//! [`softbuffer`]: https://github.com/parasyte/pixels/tree/master/examples/softbuffer
+
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+/// The width of the frame, in pixels.
+pub const WIDTH: u32 = 320;
+/// The height of the frame, in pixels.
+pub const HEIGHT: u32 = 240;
+
+const BOX_SIZE: i16 = 64;
+
+/// Representation of the application state. In this example, a box will bounce around the screen.
+#[derive(Debug)]
+pub struct World {
+    box_x: i16,
+    box_y: i16,
+    velocity_x: i16,
+    velocity_y: i16,
+}
+
+impl World {
+    /// Create a new `World` instance that can draw a moving box.
+    pub fn new() -> Self {
+        Self {
+            box_x: 24,
+            box_y: 16,
+            velocity_x: 1,
+            velocity_y: 1,
+        }
+    }
+
+    /// Update the `World` internal state; bounce the box around the screen.
+    pub fn update(&mut self) {
+        if self.box_x <= 0 || self.box_x + BOX_SIZE > WIDTH as i16 {
+            self.velocity_x *= -1;
+        }
+        if self.box_y <= 0 || self.box_y + BOX_SIZE > HEIGHT as i16 {
+            self.velocity_y *= -1;
+        }
+
+        self.box_x += self.velocity_x;
+        self.box_y += self.velocity_y;
+    }
+
+    /// Draw the `World` state to a frame of `WIDTH` by `HEIGHT` RGBA pixels.
+    ///
+    /// This matches the default texture format of `pixels`: `wgpu::TextureFormat::Rgba8UnormSrgb`
+    pub fn draw(&self, frame: &mut [u8]) {
+        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
+            let x = (i % WIDTH as usize) as i16;
+            let y = (i / WIDTH as usize) as i16;
+
+            let inside_the_box = x >= self.box_x
+                && x < self.box_x + BOX_SIZE
+                && y >= self.box_y
+                && y < self.box_y + BOX_SIZE;
+
+            let rgba = if inside_the_box {
+                [0x5e, 0x48, 0xe8, 0xff]
+            } else {
+                [0x48, 0xb2, 0xe8, 0xff]
+            };
+
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
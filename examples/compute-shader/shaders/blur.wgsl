@@ -0,0 +1,69 @@
+// Separable gaussian blur, applied in-place to the pixel buffer texture.
+//
+// The pixel buffer uses the `Rgba8Unorm` texture format and contains sRGB colors. The horizontal
+// pass decodes them and writes linear colors to an intermediate texture. The vertical pass writes
+// linear colors back to the pixel buffer, which the scaling renderer samples without decoding.
+
+const WEIGHTS = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+// Horizontal pass bindings
+
+@group(0) @binding(0) var h_input: texture_2d<f32>;
+@group(0) @binding(1) var h_output: texture_storage_2d<rgba16float, write>;
+
+// Vertical pass bindings
+
+@group(0) @binding(2) var v_input: texture_2d<f32>;
+@group(0) @binding(3) var v_output: texture_storage_2d<rgba8unorm, write>;
+
+fn srgb_to_linear(color: vec4<f32>) -> vec4<f32> {
+    let low = color.rgb / 12.92;
+    let high = pow((color.rgb + 0.055) / 1.055, vec3<f32>(2.4));
+
+    return vec4<f32>(select(high, low, color.rgb <= vec3<f32>(0.04045)), color.a);
+}
+
+// Load a texel, clamping the position to the edges of the texture.
+fn load(tex: texture_2d<f32>, pos: vec2<i32>) -> vec4<f32> {
+    let max_pos = vec2<i32>(textureDimensions(tex)) - 1;
+
+    return textureLoad(tex, clamp(pos, vec2<i32>(0), max_pos), 0);
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_horizontal(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(h_input);
+    if id.x >= size.x || id.y >= size.y {
+        return;
+    }
+
+    let pos = vec2<i32>(id.xy);
+    var weights = WEIGHTS;
+    var color = srgb_to_linear(load(h_input, pos)) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = vec2<i32>(i, 0);
+        let left = srgb_to_linear(load(h_input, pos - offset));
+        let right = srgb_to_linear(load(h_input, pos + offset));
+        color += (left + right) * weights[i];
+    }
+
+    textureStore(h_output, pos, color);
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_vertical(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = textureDimensions(v_input);
+    if id.x >= size.x || id.y >= size.y {
+        return;
+    }
+
+    let pos = vec2<i32>(id.xy);
+    var weights = WEIGHTS;
+    var color = load(v_input, pos) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = vec2<i32>(0, i);
+        color += (load(v_input, pos - offset) + load(v_input, pos + offset)) * weights[i];
+    }
+
+    textureStore(v_output, pos, color);
+}
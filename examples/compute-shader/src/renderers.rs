@@ -0,0 +1,118 @@
+use pixels::wgpu;
+
+/// Blurs the pixel buffer texture in-place with a compute shader.
+pub(crate) struct BlurRenderer {
+    horizontal_pipeline: wgpu::ComputePipeline,
+    horizontal_bind_group: wgpu::BindGroup,
+    vertical_pipeline: wgpu::ComputePipeline,
+    vertical_bind_group: wgpu::BindGroup,
+    workgroups: (u32, u32),
+}
+
+impl BlurRenderer {
+    /// The workgroup size declared in the shader.
+    const WORKGROUP_SIZE: u32 = 8;
+
+    pub(crate) fn new(pixels: &pixels::Pixels) -> Self {
+        let device = pixels.device();
+        let shader = wgpu::include_wgsl!("../shaders/blur.wgsl");
+        let module = device.create_shader_module(shader);
+
+        // The pixel buffer texture is both the input and the output of the blur
+        // This requires `TextureUsages::STORAGE_BINDING`, see `PixelsBuilder::texture_usage`
+        let texture = pixels.texture();
+        let texture_view = pixels.texture_view();
+
+        // Create an intermediate texture for the result of the horizontal pass
+        let intermediate_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("BlurRenderer intermediate texture"),
+            size: texture.size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        });
+        let intermediate_view =
+            intermediate_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Create pipelines; the bind group layouts are derived from the shader
+        let horizontal_pipeline =
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("BlurRenderer horizontal pipeline"),
+                layout: None,
+                module: &module,
+                entry_point: "cs_horizontal",
+            });
+        let vertical_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("BlurRenderer vertical pipeline"),
+            layout: None,
+            module: &module,
+            entry_point: "cs_vertical",
+        });
+
+        // Create bind groups
+        let horizontal_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("BlurRenderer horizontal bind group"),
+            layout: &horizontal_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&intermediate_view),
+                },
+            ],
+        });
+        let vertical_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("BlurRenderer vertical bind group"),
+            layout: &vertical_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&intermediate_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+            ],
+        });
+
+        // Each invocation handles one pixel
+        let workgroups = (
+            texture.width().div_ceil(Self::WORKGROUP_SIZE),
+            texture.height().div_ceil(Self::WORKGROUP_SIZE),
+        );
+
+        Self {
+            horizontal_pipeline,
+            horizontal_bind_group,
+            vertical_pipeline,
+            vertical_bind_group,
+            workgroups,
+        }
+    }
+
+    /// Record the blur passes.
+    ///
+    /// This must be called after the pixel buffer has been uploaded, and before the scaling
+    /// renderer draws it; i.e. at the start of the `Pixels::render_with` closure.
+    pub(crate) fn dispatch(&self, encoder: &mut wgpu::CommandEncoder) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("BlurRenderer compute pass"),
+            timestamp_writes: None,
+        });
+
+        cpass.set_pipeline(&self.horizontal_pipeline);
+        cpass.set_bind_group(0, &self.horizontal_bind_group, &[]);
+        cpass.dispatch_workgroups(self.workgroups.0, self.workgroups.1, 1);
+
+        cpass.set_pipeline(&self.vertical_pipeline);
+        cpass.set_bind_group(0, &self.vertical_bind_group, &[]);
+        cpass.dispatch_workgroups(self.workgroups.0, self.workgroups.1, 1);
+    }
+}
@@ -65,7 +65,7 @@ fn main() -> Result<(), Error> {
                 noise_renderer.update(&context.queue, time);
                 time += 0.01;
 
-                noise_renderer.render(encoder, render_target, context.scaling_renderer.clip_rect());
+                noise_renderer.render(encoder, render_target, context.clip_rect());
 
                 Ok(())
             });
@@ -109,7 +109,8 @@ fn main() -> Result<(), Error> {
                     }
 
                     // Resize the world
-                    let LogicalSize { width, height } = size.to_logical(scale_factor);
+                    let (width, height) =
+                        Pixels::suggested_buffer_size((size.width, size.height), scale_factor);
                     world.resize(width, height);
                     if let Err(err) = pixels.resize_buffer(width, height) {
                         log_error("pixels.resize_buffer", err);
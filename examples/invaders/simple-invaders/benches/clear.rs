@@ -0,0 +1,39 @@
+//! Compare the strategies for clearing the screen to opaque black.
+//!
+//! The `clear` helper in `simple-invaders` writes one whole pixel at a time, instead of checking
+//! the index of every byte. Run with `cargo bench --bench clear`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use simple_invaders::{HEIGHT, WIDTH};
+use std::hint::black_box;
+
+fn clear(c: &mut Criterion) {
+    let mut group = c.benchmark_group("clear");
+
+    // Allocate as `u32` to guarantee alignment for the word fill
+    let mut words = vec![0_u32; WIDTH * HEIGHT];
+
+    // The original strategy, which decides the value of each byte from its index
+    group.bench_function("indexed bytes", |b| {
+        b.iter(|| {
+            let screen: &mut [u8] = bytemuck::cast_slice_mut(black_box(&mut words));
+            for (i, byte) in screen.iter_mut().enumerate() {
+                *byte = if i % 4 == 3 { 255 } else { 0 };
+            }
+        })
+    });
+
+    group.bench_function("whole pixels", |b| {
+        b.iter(|| simple_invaders::clear(bytemuck::cast_slice_mut(black_box(&mut words))))
+    });
+
+    // Filling whole words, like `Pixels::clear`
+    group.bench_function("u32 fill", |b| {
+        b.iter(|| black_box(&mut words).fill(u32::from_ne_bytes([0, 0, 0, 255])))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, clear);
+criterion_main!(benches);
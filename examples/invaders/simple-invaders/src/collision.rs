@@ -3,12 +3,29 @@
 use crate::geo::{Point, Rect};
 use crate::{Bullet, Invaders, Laser, Player, Shield, COLS, GRID, ROWS};
 use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
 
 /// Store information about collisions (for debug mode).
 #[derive(Debug, Default)]
 pub(crate) struct Collision {
     pub(crate) bullet_details: BTreeSet<BulletDetail>,
     pub(crate) laser_details: BTreeSet<LaserDetail>,
+    pub(crate) stats: CollisionStats,
+}
+
+/// Counters for profiling the collision detection in each update (for debug mode).
+///
+/// Checks without a separate broad phase, like lasers against players, count each hit in both
+/// phases.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct CollisionStats {
+    /// The number of collision checks, e.g. one bullet against the invader fleet.
+    pub(crate) tested: usize,
+    /// The number of checks that passed the bounding box test of the broad phase.
+    pub(crate) broad_phase: usize,
+    /// The number of checks that found a collision in the narrow phase.
+    pub(crate) narrow_phase: usize,
 }
 
 /// Information regarding collisions between bullets and invaders, lasers, or shields.
@@ -22,13 +39,13 @@ pub(crate) enum BulletDetail {
     Laser,
 }
 
-/// Information regarding collisions between lasers and shields or the player.
+/// Information regarding collisions between lasers and shields or the players.
 #[derive(Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub(crate) enum LaserDetail {
     /// A shield index.
     Shield(usize),
-    /// Collided with the player.
-    Player,
+    /// A player index.
+    Player(usize),
 }
 
 impl Collision {
@@ -36,69 +53,110 @@ impl Collision {
     pub(crate) fn clear(&mut self) {
         self.bullet_details.clear();
         self.laser_details.clear();
+        self.stats = CollisionStats::default();
     }
 
     /// Handle collisions between bullets and invaders.
+    ///
+    /// The bullet has moved up by `distance` pixels since the last check. The whole path that it
+    /// swept is tested, so a fast bullet cannot pass through an invader between two checks.
+    ///
+    /// # Returns
+    ///
+    /// The score for the destroyed invader, if any.
     pub(crate) fn bullet_to_invader(
         &mut self,
         bullet: &mut Option<Bullet>,
         invaders: &mut Invaders,
-    ) -> bool {
+        distance: usize,
+    ) -> Option<u32> {
+        self.stats.tested += 1;
+
         // Broad phase collision detection
-        let (top, right, bottom, left) = invaders.get_bounds();
-        let invaders_rect = Rect::new(&Point::new(left, top), &Point::new(right, bottom));
-        let bullet_rect = {
-            let bullet = bullet.as_ref().unwrap();
-            Rect::from_drawable(&bullet.pos, &bullet.sprite)
-        };
+        let invaders_rect = invaders.bounds_rect();
+        let bullet_rect = bullet_path(bullet.as_ref().unwrap(), distance);
         if bullet_rect.intersects(&invaders_rect) {
-            // Narrow phase collision detection
-            let corners = [
-                (bullet_rect.p1.x, bullet_rect.p1.y),
-                (bullet_rect.p1.x, bullet_rect.p2.y),
-                (bullet_rect.p2.x, bullet_rect.p1.y),
-                (bullet_rect.p2.x, bullet_rect.p2.y),
-            ];
-
-            for (x, y) in corners.iter() {
-                let col = x.saturating_sub(left) / GRID.x + invaders.bounds.left_col;
-                let row = y.saturating_sub(top) / GRID.y + invaders.bounds.top_row;
-
-                if col < COLS && row < ROWS && invaders.grid[row][col].is_some() {
-                    let detail = BulletDetail::Invader(col, row);
-                    self.bullet_details.insert(detail);
+            self.stats.broad_phase += 1;
+
+            // Narrow phase collision detection, with every grid cell that the bullet path touches
+            let origin = invaders_rect.p1;
+            let cell = |p: Point| {
+                // Points above or to the left of the fleet are in its first row or column
+                let offset = p.saturating_sub(origin);
+                let col = offset.x / GRID.x + invaders.bounds.left_col;
+                let row = offset.y / GRID.y + invaders.bounds.top_row;
+
+                (col.min(COLS - 1), row.min(ROWS - 1))
+            };
+            let (left, top) = cell(bullet_rect.p1);
+            let (right, bottom) = cell(bullet_rect.p2);
+
+            // Only this bullet's candidates are tested; the details may hold invaders that another
+            // player's bullet destroyed in the same update
+            let mut candidates = Vec::new();
+            for row in top..=bottom {
+                for col in left..=right {
+                    if invaders.grid[row][col].is_some() {
+                        self.bullet_details.insert(BulletDetail::Invader(col, row));
+                        candidates.push((col, row));
+                    }
                 }
             }
 
-            // If any collision candidate is a hit, kill the bullet and invader
-            for detail in self.bullet_details.iter() {
-                if let BulletDetail::Invader(x, y) = *detail {
+            // If any collision candidate is a hit, kill the bullet and the lowest invader, which
+            // the bullet reached first
+            let hit = candidates
+                .into_iter()
+                .filter(|&(x, y)| {
                     let invader = invaders.grid[y][x].as_ref().unwrap();
-                    let invader_rect = Rect::from_drawable(&invader.pos, &invader.sprite);
-                    if bullet_rect.intersects(&invader_rect) {
-                        // TODO: Explosion! Score!
-                        invaders.grid[y][x] = None;
+                    bullet_rect.intersects(&Rect::from_drawable(&invader.pos, &invader.sprite))
+                })
+                .max_by_key(|&(x, y)| (y, Reverse(x)));
 
-                        // Destroy bullet
-                        *bullet = None;
+            if let Some((x, y)) = hit {
+                self.stats.narrow_phase += 1;
 
-                        return true;
-                    }
-                }
+                // TODO: Explosion!
+                let score = invaders.grid[y][x].as_ref().unwrap().score;
+                invaders.grid[y][x] = None;
+
+                // Destroy bullet
+                *bullet = None;
+
+                return Some(score);
             }
         }
 
-        false
+        None
     }
 
     /// Handle collisions between bullets and shields.
-    pub(crate) fn bullet_to_shield(&mut self, bullet: &mut Option<Bullet>, shields: &mut [Shield]) {
+    ///
+    /// The bullet has moved up by `distance` pixels since the last check, like
+    /// [`Collision::bullet_to_invader`].
+    ///
+    /// # Returns
+    ///
+    /// `true` when the bullet hit a shield.
+    pub(crate) fn bullet_to_shield(
+        &mut self,
+        bullet: &mut Option<Bullet>,
+        shields: &mut [Shield],
+        distance: usize,
+    ) -> bool {
+        let mut hit = false;
+
         if bullet.is_some() {
+            self.stats.tested += 1;
+
             let shield_rects = create_shield_rects(shields);
-            let bullet_rect = {
-                let bullet = bullet.as_ref().unwrap();
-                Rect::from_drawable(&bullet.pos, &bullet.sprite)
-            };
+            let bullet_rect = bullet_path(bullet.as_ref().unwrap(), distance);
+
+            // Skip the shields entirely when the bullet is nowhere near them
+            match union_rects(&shield_rects) {
+                Some(bounds) if bullet_rect.intersects(&bounds) => self.stats.broad_phase += 1,
+                _ => return false,
+            }
 
             for (i, shield_rect) in shield_rects.iter().enumerate() {
                 // broad phase collision detection
@@ -109,24 +167,38 @@ impl Collision {
                     // TODO: Explosion!
                     let detail = BulletDetail::Shield(i);
                     self.bullet_details.insert(detail);
+                    self.stats.narrow_phase += 1;
 
                     // Destroy bullet
                     *bullet = None;
+                    hit = true;
                 }
             }
         }
+
+        hit
     }
 
-    /// Handle collisions between lasers and the player.
-    pub(crate) fn laser_to_player(&mut self, laser: &Laser, player: &Player) -> bool {
+    /// Handle collisions between lasers and the live players.
+    ///
+    /// # Returns
+    ///
+    /// The index of the player that was hit, if any.
+    pub(crate) fn laser_to_player(&mut self, laser: &Laser, players: &[Player]) -> Option<usize> {
         let laser_rect = Rect::from_drawable(&laser.pos, &laser.sprite);
-        let player_rect = Rect::from_drawable(&player.pos, &player.sprite);
-        if laser_rect.intersects(&player_rect) {
-            self.laser_details.insert(LaserDetail::Player);
-            true
-        } else {
-            false
+        self.stats.tested += 1;
+
+        for (i, player) in players.iter().enumerate().filter(|(_, p)| p.alive) {
+            let player_rect = Rect::from_drawable(&player.pos, &player.sprite);
+            if laser_rect.intersects(&player_rect) {
+                self.laser_details.insert(LaserDetail::Player(i));
+                self.stats.broad_phase += 1;
+                self.stats.narrow_phase += 1;
+                return Some(i);
+            }
         }
+
+        None
     }
 
     /// Handle collisions between lasers and bullets.
@@ -134,6 +206,7 @@ impl Collision {
         let mut destroy = false;
         if bullet.is_some() {
             let laser_rect = Rect::from_drawable(&laser.pos, &laser.sprite);
+            self.stats.tested += 1;
 
             if let Some(bullet) = &bullet {
                 let bullet_rect = Rect::from_drawable(&bullet.pos, &bullet.sprite);
@@ -141,6 +214,8 @@ impl Collision {
                     // TODO: Explosion!
                     let detail = BulletDetail::Laser;
                     self.bullet_details.insert(detail);
+                    self.stats.broad_phase += 1;
+                    self.stats.narrow_phase += 1;
 
                     // Destroy laser and bullet
                     destroy = true;
@@ -155,11 +230,25 @@ impl Collision {
         destroy
     }
 
+    /// Handle collisions between lasers and the bullets of all players.
+    pub(crate) fn laser_to_bullets(&mut self, laser: &Laser, players: &mut [Player]) -> bool {
+        players
+            .iter_mut()
+            .any(|player| self.laser_to_bullet(laser, &mut player.bullet))
+    }
+
     /// Handle collisions between lasers and shields.
     pub(crate) fn laser_to_shield(&mut self, laser: &Laser, shields: &mut [Shield]) -> bool {
         let laser_rect = Rect::from_drawable(&laser.pos, &laser.sprite);
         let shield_rects = create_shield_rects(shields);
         let mut destroy = false;
+        self.stats.tested += 1;
+
+        // Skip the shields entirely when the laser is nowhere near them
+        match union_rects(&shield_rects) {
+            Some(bounds) if laser_rect.intersects(&bounds) => self.stats.broad_phase += 1,
+            _ => return false,
+        }
 
         for (i, shield_rect) in shield_rects.iter().enumerate() {
             // broad phase collision detection
@@ -170,6 +259,7 @@ impl Collision {
                 // TODO: Explosion!
                 let detail = LaserDetail::Shield(i);
                 self.laser_details.insert(detail);
+                self.stats.narrow_phase += 1;
 
                 // Destroy laser
                 destroy = true;
@@ -180,11 +270,24 @@ impl Collision {
     }
 }
 
-fn create_shield_rects(shields: &[Shield]) -> [Rect; 4] {
-    [
-        Rect::from_drawable(&shields[0].pos, &shields[0].sprite),
-        Rect::from_drawable(&shields[1].pos, &shields[1].sprite),
-        Rect::from_drawable(&shields[2].pos, &shields[2].sprite),
-        Rect::from_drawable(&shields[3].pos, &shields[3].sprite),
-    ]
+fn create_shield_rects(shields: &[Shield]) -> Vec<Rect> {
+    shields
+        .iter()
+        .map(|shield| Rect::from_drawable(&shield.pos, &shield.sprite))
+        .collect()
+}
+
+/// Compute the rectangle that a bullet swept while moving up by `distance` pixels.
+fn bullet_path(bullet: &Bullet, distance: usize) -> Rect {
+    let start = bullet.pos + Point::new(0, distance);
+
+    Rect::from_drawable(&bullet.pos, &bullet.sprite)
+        .union(&Rect::from_drawable(&start, &bullet.sprite))
+}
+
+/// Compute the bounding box around all of the rectangles, or `None` when there are none.
+fn union_rects(rects: &[Rect]) -> Option<Rect> {
+    let (first, rest) = rects.split_first()?;
+
+    Some(rest.iter().fold(*first, |bounds, rect| bounds.union(rect)))
 }
@@ -1,5 +1,5 @@
 /// Player control inputs.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Controls {
     /// Move the player.
     pub direction: Direction,
@@ -7,8 +7,52 @@ pub struct Controls {
     pub fire: bool,
 }
 
+impl Controls {
+    /// Take a snapshot of the movement and fire inputs from any input source.
+    pub fn from_input(input: &impl InputSource) -> Self {
+        Self {
+            direction: input.direction(),
+            fire: input.fire(),
+        }
+    }
+}
+
+/// A source of inputs for one player, e.g. a keyboard or a gamepad.
+///
+/// Implement this for any input library to drive [`World::update`](crate::World::update).
+/// [`Controls`] is the simplest implementation, and it is what gets recorded for replays.
+pub trait InputSource {
+    /// The direction to move the player.
+    fn direction(&self) -> Direction;
+
+    /// Whether the cannon is being fired.
+    fn fire(&self) -> bool;
+
+    /// Whether pause was pressed since the last update. Pausing toggles between playing and
+    /// paused, see [`World::set_paused`](crate::World::set_paused).
+    ///
+    /// The default implementation never pauses.
+    fn pause(&self) -> bool {
+        false
+    }
+}
+
+impl InputSource for Controls {
+    fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    fn fire(&self) -> bool {
+        self.fire
+    }
+}
+
 /// The player can only move left or right, but can also be stationary.
-#[derive(Debug, Default)]
+///
+/// Moving up and down is ignored unless it is enabled with
+/// [`World::set_vertical_movement`](crate::World::set_vertical_movement).
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Direction {
     /// Do not move the player.
     #[default]
@@ -17,4 +61,35 @@ pub enum Direction {
     Left,
     /// Move to the right.
     Right,
+    /// Move up.
+    Up,
+    /// Move down.
+    Down,
+}
+
+impl Direction {
+    /// Select a direction from the state of the left and right inputs.
+    ///
+    /// Left takes precedence when both inputs are active.
+    pub fn from_inputs(left: bool, right: bool) -> Self {
+        if left {
+            Direction::Left
+        } else if right {
+            Direction::Right
+        } else {
+            Direction::Still
+        }
+    }
+
+    /// Select a direction from the state of the left, right, up, and down inputs.
+    ///
+    /// Horizontal movement takes precedence over vertical movement, and left and up take
+    /// precedence over right and down, respectively.
+    pub fn from_inputs_4way(left: bool, right: bool, up: bool, down: bool) -> Self {
+        match Self::from_inputs(left, right) {
+            Direction::Still if up => Direction::Up,
+            Direction::Still if down => Direction::Down,
+            direction => direction,
+        }
+    }
 }
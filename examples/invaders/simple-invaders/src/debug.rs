@@ -1,23 +1,83 @@
-use crate::collision::{BulletDetail, Collision, LaserDetail};
-use crate::geo::Point;
-use crate::sprites::{rect, Drawable};
-use crate::{Bullet, Invaders, Laser, Player, Shield, GRID};
+use crate::collision::{BulletDetail, Collision, CollisionStats, LaserDetail};
+use crate::geo::{Point, Rect};
+use crate::sprites::{line, rect, screen_height, Drawable};
+use crate::text::text;
+use crate::{Bullet, Invaders, Laser, Player, Shield, World, GRID};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
 
 // Colors
 const RED: [u8; 4] = [255, 0, 0, 255];
 const GREEN: [u8; 4] = [0, 255, 0, 255];
 const BLUE: [u8; 4] = [0, 0, 255, 255];
 const YELLOW: [u8; 4] = [255, 255, 0, 255];
+const GRAY: [u8; 4] = [128, 128, 128, 255];
+const CYAN: [u8; 4] = [0, 255, 255, 255];
+
+// Every few ticks of the grid are longer, like a ruler
+const MAJOR_TICKS: usize = 4;
+
+/// A custom debug overlay, see [`World::add_debug_draw`].
+///
+/// Closures that take the screen and the `World` implement this trait.
+///
+/// # Example
+///
+/// ```
+/// use simple_invaders::World;
+///
+/// let mut world = World::new((6_364_136_223_846_793_005, 1), true);
+///
+/// // Mark the top-left corner of each laser
+/// world.add_debug_draw(Box::new(|screen: &mut [u8], world: &World| {
+///     let (width, _) = world.size();
+///     for (x, y) in world.laser_positions() {
+///         let i = (y * width + x) * 4;
+///         screen[i..i + 4].copy_from_slice(&[0xff, 0x00, 0xff, 0xff]);
+///     }
+/// }));
+/// ```
+pub trait DebugDraw {
+    /// Draw the overlay on the screen, over the built-in debug visualizations.
+    ///
+    /// The `screen` is the buffer given to [`World::draw`], see [`World::size`].
+    fn draw(&mut self, screen: &mut [u8], world: &World);
+}
+
+impl<F> DebugDraw for F
+where
+    F: FnMut(&mut [u8], &World),
+{
+    fn draw(&mut self, screen: &mut [u8], world: &World) {
+        self(screen, world)
+    }
+}
+
+/// The overlays added with [`World::add_debug_draw`].
+#[derive(Default)]
+pub(crate) struct DebugOverlays(pub(crate) Vec<Box<dyn DebugDraw>>);
+
+impl fmt::Debug for DebugOverlays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DebugOverlays").field(&self.0.len()).finish()
+    }
+}
 
 /// Draw bounding boxes for the invader fleet and each invader.
-pub(crate) fn draw_invaders(screen: &mut [u8], invaders: &Invaders, collision: &Collision) {
-    // Draw invaders bounding box
+pub(crate) fn draw_invaders(
+    screen: &mut [u8],
+    width: usize,
+    invaders: &Invaders,
+    collision: &Collision,
+) {
+    // Draw invaders bounding box, just outside of the fleet so it does not cover the outlines of
+    // the grid cells on its edges
     {
-        let (top, right, bottom, left) = invaders.get_bounds();
-        let p1 = Point::new(left, top);
-        let p2 = Point::new(right, bottom);
+        let bounds = invaders.bounds_rect().inflate(1);
 
-        rect(screen, &p1, &p2, BLUE);
+        rect(screen, width, &bounds.p1, &bounds.p2, BLUE);
     }
 
     // Draw bounding boxes for each invader
@@ -35,57 +95,73 @@ pub(crate) fn draw_invaders(screen: &mut [u8], invaders: &Invaders, collision: &
                     GREEN
                 };
 
-                rect(screen, &p1, &p2, color);
+                rect(screen, width, &p1, &p2, color);
             } else if collision.bullet_details.contains(&detail) {
                 let x = x - invaders.bounds.left_col;
                 let y = y - invaders.bounds.top_row;
                 let p1 = invaders.bounds.pos + Point::new(x, y) * GRID;
-                let p2 = p1 + GRID;
 
-                rect(screen, &p1, &p2, RED);
+                // Inside of the grid cell, so neighboring candidates do not share an outline
+                let cell = Rect::new(&p1, &(p1 + GRID)).deflate(1);
+
+                rect(screen, width, &cell.p1, &cell.p2, RED);
             }
         }
     }
 }
 
 /// Draw bounding box for bullet.
-pub(crate) fn draw_bullet(screen: &mut [u8], bullet: Option<&Bullet>) {
+pub(crate) fn draw_bullet(screen: &mut [u8], width: usize, bullet: Option<&Bullet>) {
     if let Some(bullet) = bullet {
         let p1 = bullet.pos;
         let p2 = p1 + Point::new(bullet.sprite.width(), bullet.sprite.height());
 
-        rect(screen, &p1, &p2, GREEN);
+        rect(screen, width, &p1, &p2, GREEN);
     }
 }
 
 /// Draw bounding box for lasers.
-pub(crate) fn draw_lasers(screen: &mut [u8], lasers: &[Laser]) {
+pub(crate) fn draw_lasers(screen: &mut [u8], width: usize, lasers: &[Laser]) {
     for laser in lasers {
         let p1 = laser.pos;
         let p2 = p1 + Point::new(laser.sprite.width(), laser.sprite.height());
 
-        rect(screen, &p1, &p2, GREEN);
+        rect(screen, width, &p1, &p2, GREEN);
     }
 }
 
-/// Draw bounding box for player.
-pub(crate) fn draw_player(screen: &mut [u8], player: &Player, collision: &Collision) {
-    let p1 = player.pos;
-    let p2 = p1 + Point::new(player.sprite.width(), player.sprite.height());
-
-    // Select color based on collisions
-    let detail = LaserDetail::Player;
-    let color = if collision.laser_details.contains(&detail) {
-        RED
-    } else {
-        GREEN
-    };
+/// Draw bounding boxes for players.
+pub(crate) fn draw_players(
+    screen: &mut [u8],
+    width: usize,
+    players: &[Player],
+    collision: &Collision,
+) {
+    for (i, player) in players.iter().enumerate() {
+        let p1 = player.pos;
+        let p2 = p1 + Point::new(player.sprite.width(), player.sprite.height());
+
+        // Select color based on collisions; players destroyed in earlier frames are skipped
+        let detail = LaserDetail::Player(i);
+        let color = if collision.laser_details.contains(&detail) {
+            RED
+        } else if player.alive {
+            GREEN
+        } else {
+            continue;
+        };
 
-    rect(screen, &p1, &p2, color);
+        rect(screen, width, &p1, &p2, color);
+    }
 }
 
 /// Draw bounding boxes for shields.
-pub(crate) fn draw_shields(screen: &mut [u8], shields: &[Shield], collision: &Collision) {
+pub(crate) fn draw_shields(
+    screen: &mut [u8],
+    width: usize,
+    shields: &[Shield],
+    collision: &Collision,
+) {
     for (i, shield) in shields.iter().enumerate() {
         let p1 = shield.pos;
         let p2 = p1 + Point::new(shield.sprite.width(), shield.sprite.height());
@@ -101,6 +177,95 @@ pub(crate) fn draw_shields(screen: &mut [u8], shields: &[Shield], collision: &Co
             GREEN
         };
 
-        rect(screen, &p1, &p2, color);
+        rect(screen, width, &p1, &p2, color);
+    }
+}
+
+/// Draw the collision counters in the top-left corner of the screen.
+pub(crate) fn draw_stats(screen: &mut [u8], width: usize, stats: &CollisionStats) {
+    let msg = format!(
+        "TESTED:{} BROAD:{} NARROW:{}",
+        stats.tested, stats.broad_phase, stats.narrow_phase,
+    );
+
+    text(screen, width, &Point::new(2, 2), &msg, YELLOW);
+}
+
+/// Draw coordinate ticks every `spacing` pixels along the top and left edges of the screen, and a
+/// crosshair with the coordinates of the cursor pixel.
+pub(crate) fn draw_grid(screen: &mut [u8], width: usize, spacing: usize, cursor: Option<Point>) {
+    let height = screen_height(screen, width);
+    let tick_length = |i: usize| match i % MAJOR_TICKS {
+        0 => 4,
+        _ => 2,
+    };
+
+    for (i, x) in (0..width).step_by(spacing).enumerate() {
+        let p1 = Point::new(x, 0);
+        let p2 = Point::new(x, tick_length(i) - 1);
+        line(screen, width, &p1, &p2, GRAY);
+    }
+    for (i, y) in (0..height).step_by(spacing).enumerate() {
+        let p1 = Point::new(0, y);
+        let p2 = Point::new(tick_length(i) - 1, y);
+        line(screen, width, &p1, &p2, GRAY);
+    }
+
+    // The arms of the crosshair leave the cursor pixel itself uncovered, and they are cut short
+    // at the top and left edges
+    if let Some(cursor) = cursor {
+        let (x, y) = (cursor.x, cursor.y);
+        let arms = [
+            x.checked_sub(2)
+                .map(|end| (Point::new(x.saturating_sub(4), y), Point::new(end, y))),
+            Some((Point::new(x + 2, y), Point::new(x + 4, y))),
+            y.checked_sub(2)
+                .map(|end| (Point::new(x, y.saturating_sub(4)), Point::new(x, end))),
+            Some((Point::new(x, y + 2), Point::new(x, y + 4))),
+        ];
+        for (p1, p2) in arms.iter().flatten() {
+            line(screen, width, p1, p2, CYAN);
+        }
+
+        let msg = format!("{} {}", x, y);
+        text(screen, width, &Point::new(x + 6, y + 6), &msg, CYAN);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HEIGHT, WIDTH};
+    use alloc::vec;
+
+    #[test]
+    fn test_draw_grid() {
+        let mut screen = vec![0; WIDTH * HEIGHT * 4];
+        draw_grid(&mut screen, WIDTH, 8, Some(Point::new(100, 50)));
+
+        // Major ticks every 4th tick are longer
+        assert_eq!(pixel(&screen, 0, 3), GRAY);
+        assert_eq!(pixel(&screen, 8, 1), GRAY);
+        assert_eq!(pixel(&screen, 8, 2), [0; 4]);
+        assert_eq!(pixel(&screen, 32, 3), GRAY);
+        assert_eq!(pixel(&screen, 1, 16), GRAY);
+
+        // The crosshair surrounds the cursor pixel without covering it
+        assert_eq!(pixel(&screen, 100, 50), [0; 4]);
+        assert_eq!(pixel(&screen, 99, 50), [0; 4]);
+        assert_eq!(pixel(&screen, 96, 50), CYAN);
+        assert_eq!(pixel(&screen, 104, 50), CYAN);
+        assert_eq!(pixel(&screen, 100, 46), CYAN);
+        assert_eq!(pixel(&screen, 100, 54), CYAN);
+
+        // A cursor in the corner does not underflow
+        draw_grid(&mut screen, WIDTH, 8, Some(Point::new(0, 0)));
+        assert_eq!(pixel(&screen, 2, 0), CYAN);
+    }
+
+    fn pixel(screen: &[u8], x: usize, y: usize) -> &[u8] {
+        let i = x * 4 + y * WIDTH * 4;
+
+        &screen[i..i + 4]
     }
 }
@@ -0,0 +1,70 @@
+use core::time::Duration;
+
+/// Tuning knobs for the game difficulty.
+///
+/// Use one of the presets, or adjust the fields individually:
+///
+/// ```
+/// use simple_invaders::{Difficulty, World};
+///
+/// let difficulty = Difficulty {
+///     max_lasers: 10,
+///     ..Difficulty::hard()
+/// };
+/// let world = World::new_with_difficulty((6_364_136_223_846_793_005, 1), false, difficulty);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Difficulty {
+    /// Each step, an invader fires a laser with a `1:shoot_chance` chance. Must be nonzero.
+    pub shoot_chance: usize,
+    /// The maximum number of lasers on screen at once.
+    pub max_lasers: usize,
+    /// Laser velocity, in pixels per frame.
+    pub laser_speed: usize,
+    /// Bullet velocity, in pixels per time step.
+    pub bullet_speed: usize,
+    /// The minimum time between shots fired by each player.
+    pub fire_cooldown: Duration,
+}
+
+impl Difficulty {
+    /// Fewer and slower lasers, and faster bullets.
+    pub const fn easy() -> Self {
+        Self {
+            shoot_chance: 80,
+            max_lasers: 2,
+            laser_speed: 1,
+            bullet_speed: 2,
+            fire_cooldown: Duration::ZERO,
+        }
+    }
+
+    /// The classic difficulty.
+    pub const fn normal() -> Self {
+        Self {
+            shoot_chance: 50,
+            max_lasers: 3,
+            laser_speed: 2,
+            bullet_speed: 1,
+            fire_cooldown: Duration::ZERO,
+        }
+    }
+
+    /// More and faster lasers, and a cooldown between shots.
+    pub const fn hard() -> Self {
+        Self {
+            shoot_chance: 25,
+            max_lasers: 5,
+            laser_speed: 3,
+            bullet_speed: 1,
+            fire_cooldown: Duration::from_millis(400),
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
@@ -4,6 +4,7 @@ use crate::sprites::Drawable;
 
 /// A tiny position vector.
 #[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct Point {
     pub(crate) x: usize,
     pub(crate) y: usize,
@@ -21,6 +22,14 @@ impl Point {
     pub(crate) const fn new(x: usize, y: usize) -> Point {
         Point { x, y }
     }
+
+    /// Subtract another point, clamping each coordinate at zero instead of underflowing.
+    pub(crate) fn saturating_sub(self, other: Point) -> Point {
+        Point::new(
+            self.x.saturating_sub(other.x),
+            self.y.saturating_sub(other.y),
+        )
+    }
 }
 
 impl core::ops::Add for Point {
@@ -31,6 +40,14 @@ impl core::ops::Add for Point {
     }
 }
 
+impl core::ops::Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
 impl core::ops::Mul for Point {
     type Output = Self;
 
@@ -69,6 +86,51 @@ impl Rect {
         bottom1 > top2 && bottom2 > top1 && right1 > left2 && right2 > left1
     }
 
+    /// Compute the smallest rectangle containing both rectangles.
+    pub(crate) fn union(&self, other: &Rect) -> Rect {
+        let (top1, right1, bottom1, left1) = self.get_bounds();
+        let (top2, right2, bottom2, left2) = other.get_bounds();
+
+        let p1 = Point::new(left1.min(left2), top1.min(top2));
+        let p2 = Point::new(right1.max(right2), bottom1.max(bottom2));
+
+        Rect { p1, p2 }
+    }
+
+    /// Grow the rectangle by `margin` on every side.
+    ///
+    /// The top-left corner stops at the origin instead of underflowing.
+    pub(crate) fn inflate(&self, margin: usize) -> Rect {
+        let p1 = Point::new(
+            self.p1.x.saturating_sub(margin),
+            self.p1.y.saturating_sub(margin),
+        );
+        let p2 = Point::new(
+            self.p2.x.saturating_add(margin),
+            self.p2.y.saturating_add(margin),
+        );
+
+        Rect { p1, p2 }
+    }
+
+    /// Shrink the rectangle by `margin` on every side.
+    ///
+    /// A rectangle that is smaller than twice the margin collapses to an empty rectangle at its
+    /// center, instead of turning inside out.
+    pub(crate) fn deflate(&self, margin: usize) -> Rect {
+        let shrink = |min: usize, max: usize| {
+            let center = min + (max - min) / 2;
+            (
+                min.saturating_add(margin).min(center),
+                max.saturating_sub(margin).max(center),
+            )
+        };
+        let (left, right) = shrink(self.p1.x, self.p2.x);
+        let (top, bottom) = shrink(self.p1.y, self.p2.y);
+
+        Rect::new(&Point::new(left, top), &Point::new(right, bottom))
+    }
+
     /// Compute the bounding box for this rectangle.
     ///
     /// # Returns
@@ -83,6 +145,21 @@ impl Rect {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_point_sub() {
+        let p = Point::new(10, 20) - Point::new(3, 5);
+        assert_eq!((p.x, p.y), (7, 15));
+
+        let p = Point::new(10, 20).saturating_sub(Point::new(10, 20));
+        assert_eq!((p.x, p.y), (0, 0));
+
+        // Underflow in either coordinate only clamps that coordinate
+        let p = Point::new(10, 20).saturating_sub(Point::new(11, 5));
+        assert_eq!((p.x, p.y), (0, 15));
+        let p = Point::new(10, 20).saturating_sub(Point::new(3, 21));
+        assert_eq!((p.x, p.y), (7, 0));
+    }
+
     #[test]
     fn test_rect_intersect() {
         let rect_size = Point::new(10, 10);
@@ -124,4 +201,51 @@ mod tests {
         assert!(r1.intersects(&r2), "Should intersect");
         assert!(r2.intersects(&r1), "Should intersect");
     }
+
+    #[test]
+    fn test_rect_union() {
+        let bounds = |r: Rect| r.get_bounds();
+        let r1 = Rect::new(&Point::new(10, 10), &Point::new(20, 20));
+
+        // Disjoint rectangles span the gap between them
+        let r2 = Rect::new(&Point::new(30, 0), &Point::new(40, 5));
+        assert_eq!(bounds(r1.union(&r2)), (0, 40, 20, 10));
+        assert_eq!(bounds(r2.union(&r1)), (0, 40, 20, 10));
+
+        // Overlapping rectangles
+        let r2 = Rect::new(&Point::new(15, 5), &Point::new(25, 15));
+        assert_eq!(bounds(r1.union(&r2)), (5, 25, 20, 10));
+        assert_eq!(bounds(r2.union(&r1)), (5, 25, 20, 10));
+
+        // A contained rectangle does not change the bounds
+        let r2 = Rect::new(&Point::new(12, 12), &Point::new(18, 18));
+        assert_eq!(bounds(r1.union(&r2)), bounds(r1));
+        assert_eq!(bounds(r2.union(&r1)), bounds(r1));
+    }
+
+    #[test]
+    fn test_rect_inflate_deflate() {
+        let bounds = |r: Rect| r.get_bounds();
+        let r = Rect::new(&Point::new(10, 20), &Point::new(30, 26));
+
+        assert_eq!(bounds(r.inflate(2)), (18, 32, 28, 8));
+        assert_eq!(bounds(r.deflate(2)), (22, 28, 24, 12));
+        assert_eq!(bounds(r.inflate(0)), bounds(r));
+        assert_eq!(bounds(r.deflate(0)), bounds(r));
+
+        // Inflating stops at the origin, and at the maximum coordinate
+        assert_eq!(bounds(r.inflate(15)), (5, 45, 41, 0));
+        assert_eq!(bounds(r.inflate(20)), (0, 50, 46, 0));
+        assert_eq!(
+            bounds(r.inflate(usize::MAX)),
+            (0, usize::MAX, usize::MAX, 0)
+        );
+
+        // Deflating past the center collapses the rectangle without underflowing
+        assert_eq!(bounds(r.deflate(3)), (23, 27, 23, 13));
+        assert_eq!(bounds(r.deflate(4)), (23, 26, 23, 14));
+        assert_eq!(bounds(r.deflate(usize::MAX)), (23, 20, 23, 20));
+        let empty = Rect::new(&Point::new(0, 0), &Point::new(0, 0));
+        assert_eq!(bounds(empty.deflate(1)), (0, 0, 0, 0));
+    }
 }
@@ -9,30 +9,42 @@
 #![forbid(unsafe_code)]
 
 extern crate alloc;
+use alloc::boxed::Box;
 use alloc::vec::Vec;
 
 use crate::collision::Collision;
-pub use crate::controls::{Controls, Direction};
-use crate::geo::Point;
+pub use crate::controls::{Controls, Direction, InputSource};
+pub use crate::debug::DebugDraw;
+use crate::debug::DebugOverlays;
+pub use crate::difficulty::Difficulty;
+use crate::geo::{Point, Rect};
 use crate::loader::{load_assets, Assets};
 use crate::player::Player;
+pub use crate::save::SaveState;
 use crate::shield::Shield;
-use crate::sprites::{blit, Animation, Drawable, Frame, SpriteRef};
+pub use crate::shield::ShieldLayout;
+use crate::sprites::{
+    blend_rect, blit, line, screen_height, Animation, Drawable, Frame, SpriteRef,
+};
+use crate::text::{text, text_width, GLYPH_HEIGHT};
 use core::time::Duration;
 use randomize::PCG32;
 
 mod collision;
 mod controls;
 mod debug;
+mod difficulty;
 mod geo;
 mod loader;
 mod player;
+mod save;
 mod shield;
 mod sprites;
+mod text;
 
-/// The screen width is constant (units are in pixels)
+/// The default screen width (units are in pixels), see [`World::new_with_size`]
 pub const WIDTH: usize = 224;
-/// The screen height is constant (units are in pixels)
+/// The default screen height (units are in pixels), see [`World::new_with_size`]
 pub const HEIGHT: usize = 256;
 
 // Fixed time step (240 fps)
@@ -49,29 +61,88 @@ const COLS: usize = 11;
 
 // Player positioning
 const PLAYER_START: Point = Point::new(80, 216);
+const PLAYER_SPACING: usize = 64;
+/// The highest the player can move with vertical movement enabled.
+const PLAYER_TOP: usize = 160;
+/// Distance between the coordinate ticks of the debug grid.
+const DEBUG_GRID: usize = 8;
+
+// The ground is just below the players
+const GROUND: usize = PLAYER_START.y + 16;
+const GROUND_COLOR: [u8; 4] = [0x62, 0xde, 0x6d, 0xff];
+
+// Cracks fade out after a few seconds
+const CRACK_DURATION: Duration = Duration::from_secs(3);
+const CRACK_COLOR: [u8; 4] = [0x30, 0x18, 0x08, 0xff];
 
 // Projectile positioning
 const LASER_OFFSET: Point = Point::new(4, 10);
 const BULLET_OFFSET: Point = Point::new(7, 0);
 
+/// Events emitted by the game logic.
+///
+/// These allow the embedder to play sound effects or provide other feedback without the game
+/// depending on an audio library. See [`World::events`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A player fired their cannon.
+    Fire { player: usize },
+    /// An invader fired a laser.
+    LaserFired,
+    /// A player's bullet destroyed an invader worth `score` points.
+    InvaderKilled { player: usize, score: u32 },
+    /// A player was hit by a laser.
+    PlayerKilled { player: usize },
+    /// A bullet or laser hit a shield.
+    ShieldHit,
+    /// The game has ended.
+    GameOver,
+}
+
+/// The top-level state of the game, see [`World::state`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GameState {
+    /// The title screen is shown until a player presses fire. Fire that is still held from the
+    /// previous game is ignored.
+    Title,
+    /// The game is running.
+    Playing,
+    /// The game is frozen under a pause overlay.
+    Paused,
+    /// The game has ended. Pressing fire returns to the title screen, holding it does not.
+    GameOver,
+}
+
 #[derive(Debug)]
 pub struct World {
     invaders: Invaders,
     lasers: Vec<Laser>,
+    cracks: Vec<Crack>,
     shields: Vec<Shield>,
-    player: Player,
-    bullet: Option<Bullet>,
+    shield_layout: ShieldLayout,
+    players: Vec<Player>,
     collision: Collision,
-    _score: u32,
+    events: Vec<GameEvent>,
     assets: Assets,
+    difficulty: Difficulty,
     dt: Duration,
-    gameover: bool,
+    state: GameState,
+    width: usize,
+    height: usize,
     prng: PCG32,
+    recording: Option<Vec<Controls>>,
+    vertical_movement: bool,
+    cursor: Option<Point>,
+    /// Whether any player was holding fire during the last update.
+    fire_held: bool,
     debug: bool,
+    debug_overlays: DebugOverlays,
 }
 
 /// A fleet of invaders.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct Invaders {
     grid: Vec<Vec<Option<Invader>>>,
     stepper: Point,
@@ -81,17 +152,19 @@ struct Invaders {
 }
 
 /// Everything you ever wanted to know about Invaders.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct Invader {
     sprite: SpriteRef,
     pos: Point,
-    _score: u32,
+    score: u32,
 }
 
 /// Creates a boundary around the live invaders.
 ///
 /// Used for collision detection and minor optimizations.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct Bounds {
     pos: Point,
     left_col: usize,
@@ -101,15 +174,29 @@ struct Bounds {
 }
 
 /// The laser entity.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct Laser {
     sprite: SpriteRef,
     pos: Point,
     dt: Duration,
 }
 
+/// A crack left in the ground where a laser hit.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Crack {
+    /// Horizontal center of the crack.
+    x: usize,
+    /// Random bits selecting which pixels around the center are scorched.
+    pattern: u32,
+    /// Time remaining until the crack has faded out completely.
+    ttl: Duration,
+}
+
 /// The cannon entity.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 struct Bullet {
     sprite: SpriteRef,
     pos: Point,
@@ -172,50 +259,504 @@ impl World {
     /// let world = World::new(seed, false);
     /// ```
     pub fn new(seed: (u64, u64), debug: bool) -> World {
+        World::new_multiplayer(seed, debug, 1)
+    }
+
+    /// Create a new simple-invaders `World` for co-op play.
+    ///
+    /// Players are placed side by side, each with their own cannon and score. The game is over
+    /// when all players have been destroyed.
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - Enable debug visualizations.
+    /// * `seed` - Inputs for the pseudorandom number generator.
+    /// * `num_players` - The number of players, either 1 or 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_players` is not 1 or 2.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::World;
+    ///
+    /// let seed = (6_364_136_223_846_793_005, 1);
+    /// let world = World::new_multiplayer(seed, false, 2);
+    /// assert_eq!(world.num_players(), 2);
+    /// ```
+    pub fn new_multiplayer(seed: (u64, u64), debug: bool, num_players: usize) -> World {
+        assert!(
+            (1..=2).contains(&num_players),
+            "simple-invaders supports 1 or 2 players"
+        );
+
         // Load assets first
         let assets = load_assets();
 
         // TODO: Create invaders one-at-a-time
-        let invaders = Invaders::new(&assets);
+        let invaders = Invaders::new(&assets, START);
         let lasers = Vec::new();
-        let shields = (0..4)
-            .map(|i| Shield::new(&assets, Point::new(i * 45 + 32, 192)))
+        let cracks = Vec::new();
+        let shield_layout = ShieldLayout::default();
+        let shields = shield_layout.create(&assets, Point::default());
+        let players = (0..num_players)
+            .map(|i| Player::new(&assets, PLAYER_START + Point::new(i * PLAYER_SPACING, 0)))
             .collect();
-        let player = Player::new(&assets);
-        let bullet = None;
         let collision = Collision::default();
-        let _score = 0;
+        let events = Vec::new();
 
+        let difficulty = Difficulty::default();
         let dt = Duration::default();
-        let gameover = false;
+        let state = GameState::Playing;
+        let width = WIDTH;
+        let height = HEIGHT;
         let prng = PCG32::seed(seed.0, seed.1);
+        let recording = None;
+        let vertical_movement = false;
+        let cursor = None;
+        let fire_held = false;
 
         World {
             invaders,
             lasers,
+            cracks,
             shields,
-            player,
-            bullet,
+            shield_layout,
+            players,
             collision,
-            _score,
+            events,
             assets,
+            difficulty,
             dt,
-            gameover,
+            state,
+            width,
+            height,
             prng,
+            recording,
+            vertical_movement,
+            cursor,
+            fire_held,
             debug,
+            debug_overlays: DebugOverlays::default(),
         }
     }
 
+    /// Recreate a single player game from a recording, see [`World::record`].
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed that the recorded `World` was created with.
+    /// * `inputs` - The recorded player inputs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{Controls, World};
+    ///
+    /// let seed = (6_364_136_223_846_793_005, 1);
+    /// let mut world = World::new(seed, false);
+    /// world.record();
+    /// for _ in 0..100 {
+    ///     world.update(&Controls::default());
+    /// }
+    ///
+    /// let replay = World::from_replay(seed, world.recording());
+    /// assert_eq!(replay.score(0), world.score(0));
+    /// ```
+    pub fn from_replay(seed: (u64, u64), inputs: &[Controls]) -> World {
+        World::from_replay_multiplayer(seed, 1, inputs)
+    }
+
+    /// Recreate a co-op game from a recording, see [`World::record`].
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed that the recorded `World` was created with.
+    /// * `num_players` - The number of players, either 1 or 2.
+    /// * `inputs` - The recorded player inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_players` is not 1 or 2.
+    pub fn from_replay_multiplayer(
+        seed: (u64, u64),
+        num_players: usize,
+        inputs: &[Controls],
+    ) -> World {
+        let mut world = World::new_multiplayer(seed, false, num_players);
+        for controls in inputs.chunks(num_players) {
+            world.update_multiplayer(controls);
+        }
+
+        world
+    }
+
+    /// Create a new simple-invaders `World` with the given difficulty.
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - Enable debug visualizations.
+    /// * `seed` - Inputs for the pseudorandom number generator.
+    /// * `difficulty` - The difficulty settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{Difficulty, World};
+    ///
+    /// let seed = (6_364_136_223_846_793_005, 1);
+    /// let world = World::new_with_difficulty(seed, false, Difficulty::easy());
+    /// assert_eq!(world.difficulty(), Difficulty::easy());
+    /// ```
+    pub fn new_with_difficulty(seed: (u64, u64), debug: bool, difficulty: Difficulty) -> World {
+        let mut world = World::new(seed, debug);
+        world.set_difficulty(difficulty);
+
+        world
+    }
+
+    /// Create a new simple-invaders `World` with the given shield layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - Enable debug visualizations.
+    /// * `seed` - Inputs for the pseudorandom number generator.
+    /// * `shield_layout` - The number and placement of shields.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shields do not fit within the default [`WIDTH`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{ShieldLayout, World};
+    ///
+    /// let seed = (6_364_136_223_846_793_005, 1);
+    /// let layout = ShieldLayout {
+    ///     count: 6,
+    ///     spacing: 30,
+    ///     ..ShieldLayout::classic()
+    /// };
+    /// let world = World::new_with_shields(seed, false, layout);
+    /// assert_eq!(world.shield_layout(), layout);
+    /// ```
+    pub fn new_with_shields(seed: (u64, u64), debug: bool, shield_layout: ShieldLayout) -> World {
+        let mut world = World::new(seed, debug);
+        world.set_shield_layout(shield_layout);
+        world.shields = shield_layout.create(&world.assets, world.origin());
+
+        world
+    }
+
+    /// Create a new simple-invaders `World` with a screen larger than the default [`WIDTH`] and
+    /// [`HEIGHT`].
+    ///
+    /// The invaders and players can move across the full width of the screen. The shields and the
+    /// ground are kept together at the bottom, horizontally centered.
+    ///
+    /// # Arguments
+    ///
+    /// * `debug` - Enable debug visualizations.
+    /// * `seed` - Inputs for the pseudorandom number generator.
+    /// * `width` - The screen width in pixels.
+    /// * `height` - The screen height in pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the screen is smaller than the default size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::World;
+    ///
+    /// let seed = (6_364_136_223_846_793_005, 1);
+    /// let mut world = World::new_with_size(seed, false, 320, 256);
+    ///
+    /// let mut screen = vec![0; 320 * 256 * 4];
+    /// world.draw(&mut screen);
+    /// ```
+    pub fn new_with_size(seed: (u64, u64), debug: bool, width: usize, height: usize) -> World {
+        assert!(
+            width >= WIDTH && height >= HEIGHT,
+            "simple-invaders needs a screen of at least 224x256 pixels"
+        );
+
+        let mut world = World::new(seed, debug);
+        world.width = width;
+        world.height = height;
+        world.reset_game();
+
+        world
+    }
+
+    /// Get the screen size in pixels, as `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get the difficulty settings.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Change the difficulty settings. The change takes effect immediately.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Get the shield layout.
+    pub fn shield_layout(&self) -> ShieldLayout {
+        self.shield_layout
+    }
+
+    /// Change the shield layout, e.g. to vary the defenses between games. The change takes effect
+    /// when the next game starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shields do not fit within the default [`WIDTH`].
+    pub fn set_shield_layout(&mut self, shield_layout: ShieldLayout) {
+        assert!(
+            shield_layout.right(&self.assets) <= WIDTH,
+            "The shields must fit within the screen"
+        );
+
+        self.shield_layout = shield_layout;
+    }
+
+    /// The number of players in this `World`.
+    pub fn num_players(&self) -> usize {
+        self.players.len()
+    }
+
+    /// The number of invaders that are still alive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::World;
+    ///
+    /// let world = World::default();
+    /// assert_eq!(world.invaders_remaining(), 55);
+    /// ```
+    pub fn invaders_remaining(&self) -> usize {
+        self.invaders.count_alive()
+    }
+
+    /// Get the score for the given player index, or `None` if there is no such player.
+    pub fn score(&self, player: usize) -> Option<u32> {
+        self.players.get(player).map(|player| player.score)
+    }
+
+    /// Get the position of the given player index in pixels, as `(x, y)`, or `None` if there is
+    /// no such player.
+    ///
+    /// The position is the top-left corner of the player sprite.
+    pub fn player_position(&self, player: usize) -> Option<(usize, usize)> {
+        self.players
+            .get(player)
+            .map(|player| (player.pos.x, player.pos.y))
+    }
+
+    /// Get the position of the bullet fired by the given player index in pixels, as `(x, y)`, or
+    /// `None` if the player has no bullet in flight.
+    pub fn bullet_position(&self, player: usize) -> Option<(usize, usize)> {
+        let bullet = self.players.get(player)?.bullet.as_ref()?;
+
+        Some((bullet.pos.x, bullet.pos.y))
+    }
+
+    /// Get the positions of the lasers fired by the invaders in pixels, as `(x, y)`.
+    pub fn laser_positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.lasers.iter().map(|laser| (laser.pos.x, laser.pos.y))
+    }
+
+    /// Get the current state of the game.
+    ///
+    /// A new `World` starts in [`GameState::Playing`]. Use [`World::show_title`] to start on the
+    /// title screen instead.
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Show the title screen. The game starts when a player presses fire.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{Controls, GameState, World};
+    ///
+    /// let mut world = World::default();
+    /// world.show_title();
+    /// assert_eq!(world.state(), GameState::Title);
+    ///
+    /// let controls = Controls {
+    ///     fire: true,
+    ///     ..Controls::default()
+    /// };
+    /// world.update(&controls);
+    /// assert_eq!(world.state(), GameState::Playing);
+    /// ```
+    pub fn show_title(&mut self) {
+        self.state = GameState::Title;
+    }
+
+    /// Pause or resume the game.
+    ///
+    /// While paused, updates are ignored and `draw` renders a pause overlay over the frozen game.
+    /// Only a game in progress can be paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.state = match (self.state, paused) {
+            (GameState::Playing, true) => GameState::Paused,
+            (GameState::Paused, false) => GameState::Playing,
+            (state, _) => state,
+        };
+    }
+
+    /// Check if the game is paused.
+    pub fn is_paused(&self) -> bool {
+        self.state == GameState::Paused
+    }
+
+    /// Enable or disable debug visualizations, like collision rectangles and bounding boxes.
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /// Start recording the player inputs, discarding any previous recording.
+    ///
+    /// Updates are deterministic, so a game can be reproduced exactly from the seed and the
+    /// recorded inputs with [`World::from_replay`]. Only updates that advance the game are
+    /// recorded; updates on the title screen, while paused, or after the game is over are skipped.
+    /// Co-op games record the inputs for every player on each update, in player order.
+    ///
+    /// The replay starts from a new `World` with the default difficulty, so recording should
+    /// start right after the `World` is created, and before the game is reset.
+    pub fn record(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// The player inputs recorded since [`World::record`] was called.
+    pub fn recording(&self) -> &[Controls] {
+        self.recording.as_deref().unwrap_or_default()
+    }
+
+    /// Check if the game is over.
+    pub fn is_gameover(&self) -> bool {
+        self.state == GameState::GameOver
+    }
+
+    /// Allow the players to move up and down with [`Direction::Up`] and [`Direction::Down`].
+    ///
+    /// This is disabled by default, like the classic game where the players are stuck on the
+    /// bottom row. When enabled, the players can move between the bottom row and a little above
+    /// the shields. Disabling it does not move the players back to the bottom row until the game
+    /// is reset.
+    pub fn set_vertical_movement(&mut self, enable: bool) {
+        self.vertical_movement = enable;
+    }
+
+    /// Check if vertical movement is enabled, see [`World::set_vertical_movement`].
+    pub fn vertical_movement(&self) -> bool {
+        self.vertical_movement
+    }
+
+    /// Check if debug visualizations are enabled.
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Add a custom overlay that is drawn by [`World::draw`] when debug visualizations are
+    /// enabled, see [`DebugDraw`].
+    ///
+    /// Overlays are drawn in the order they were added, over the built-in visualizations.
+    pub fn add_debug_draw(&mut self, overlay: Box<dyn DebugDraw>) {
+        self.debug_overlays.0.push(overlay);
+    }
+
+    /// Set the position of the mouse cursor on the screen, in pixels.
+    ///
+    /// With debug visualizations enabled, a crosshair and the coordinates are drawn at the cursor
+    /// over the coordinate grid, to help with positioning sprites by hand. Use `None` when the
+    /// cursor is outside of the screen.
+    pub fn set_cursor(&mut self, cursor: Option<(usize, usize)>) {
+        self.cursor = cursor.map(|(x, y)| Point::new(x, y));
+    }
+
+    /// The events emitted by the most recent update.
+    ///
+    /// The events are cleared at the start of each update, so they should be handled after every
+    /// call to `update` or `update_multiplayer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{Controls, GameEvent, World};
+    ///
+    /// let mut world = World::default();
+    /// let controls = Controls {
+    ///     fire: true,
+    ///     ..Controls::default()
+    /// };
+    /// world.update(&controls);
+    ///
+    /// assert!(world.events().contains(&GameEvent::Fire { player: 0 }));
+    /// ```
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
     /// Update the internal state.
     ///
     /// # Arguments
     ///
-    /// * `dt`: The time delta since last update.
-    /// * `controls`: The player inputs.
-    pub fn update(&mut self, controls: &Controls) {
-        if self.gameover {
-            // TODO: Add a game over screen
-            return;
+    /// * `input`: The player inputs, e.g. [`Controls`].
+    pub fn update(&mut self, input: &impl InputSource) {
+        self.update_multiplayer(core::slice::from_ref(input));
+    }
+
+    /// Update the internal state with inputs for each player.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs`: The player inputs, in player order. Players without inputs stand still. Any
+    ///   player can pause the game.
+    pub fn update_multiplayer<I: InputSource>(&mut self, inputs: &[I]) {
+        self.events.clear();
+
+        if inputs.iter().any(InputSource::pause) {
+            self.set_paused(!self.is_paused());
+        }
+        let controls: Vec<Controls> = inputs.iter().map(Controls::from_input).collect();
+        let controls = controls.as_slice();
+
+        // The title and game over screens only react to fire being pressed, so holding it through
+        // the end of a game does not skip past them
+        let fire = controls.iter().any(|controls| controls.fire);
+        let pressed = fire && !self.fire_held;
+        self.fire_held = fire;
+        match self.state {
+            GameState::Title if pressed => {
+                self.reset_game();
+                return;
+            }
+            GameState::GameOver if pressed => {
+                self.state = GameState::Title;
+                return;
+            }
+            GameState::Playing => (),
+            _ => return,
+        }
+
+        // Record the inputs for replays, with one entry for each player
+        if let Some(recording) = &mut self.recording {
+            let num_players = self.players.len();
+            recording.extend(controls.iter().take(num_players).cloned());
+            recording.extend((controls.len()..num_players).map(|_| Controls::default()));
         }
 
         // Advance the timer by the delta time
@@ -231,53 +772,88 @@ impl World {
         }
 
         // Handle player movement and animation
-        self.step_player(controls);
-
-        if let Some(bullet) = &mut self.bullet {
-            // Handle bullet movement
-            let velocity = bullet.update();
-
-            if bullet.pos.y > velocity {
-                bullet.pos.y -= velocity;
-                bullet.sprite.animate(&self.assets);
-
-                // Handle collisions
-                if self
-                    .collision
-                    .bullet_to_invader(&mut self.bullet, &mut self.invaders)
-                {
-                    // One of the end scenarios
-                    self.gameover = self.invaders.shrink_bounds();
+        self.step_players(controls);
+
+        let players = self.players.iter_mut().enumerate();
+        for (i, player) in players.filter(|(_, player)| player.alive) {
+            if let Some(bullet) = &mut player.bullet {
+                // Handle bullet movement
+                let velocity = bullet.update() * self.difficulty.bullet_speed;
+
+                if bullet.pos.y > velocity {
+                    bullet.pos.y -= velocity;
+                    bullet.sprite.animate(&self.assets);
+
+                    // Handle collisions
+                    if let Some(score) = self.collision.bullet_to_invader(
+                        &mut player.bullet,
+                        &mut self.invaders,
+                        velocity,
+                    ) {
+                        player.score += score;
+                        self.events
+                            .push(GameEvent::InvaderKilled { player: i, score });
+
+                        // One of the end scenarios
+                        if self.invaders.shrink_bounds() {
+                            self.state = GameState::GameOver;
+                        }
+                    } else if self.collision.bullet_to_shield(
+                        &mut player.bullet,
+                        &mut self.shields,
+                        velocity,
+                    ) {
+                        self.events.push(GameEvent::ShieldHit);
+                    }
                 } else {
-                    self.collision
-                        .bullet_to_shield(&mut self.bullet, &mut self.shields);
+                    player.bullet = None;
                 }
-            } else {
-                self.bullet = None;
             }
         }
 
+        // Fade out the cracks in the ground
+        for crack in &mut self.cracks {
+            crack.ttl = crack.ttl.saturating_sub(TIME_STEP);
+        }
+        self.cracks.retain(|crack| crack.ttl > Duration::default());
+
         // Handle laser movement
         let mut destroy = Vec::new();
+        let ground = self.ground();
         for (i, laser) in self.lasers.iter_mut().enumerate() {
-            let velocity = laser.update() * 2;
+            let velocity = laser.update() * self.difficulty.laser_speed;
 
-            if laser.pos.y < self.player.pos.y {
+            if laser.pos.y + laser.sprite.height() < ground {
                 laser.pos.y += velocity;
                 laser.sprite.animate(&self.assets);
 
                 // Handle collisions
-                if self.collision.laser_to_player(laser, &self.player) {
+                if let Some(hit) = self.collision.laser_to_player(laser, &self.players) {
+                    let player = &mut self.players[hit];
+                    player.alive = false;
+                    player.bullet = None;
+                    self.events.push(GameEvent::PlayerKilled { player: hit });
+
                     // One of the end scenarios
-                    self.gameover = true;
+                    if self.players.iter().all(|player| !player.alive) {
+                        self.state = GameState::GameOver;
+                    }
 
                     destroy.push(i);
-                } else if self.collision.laser_to_bullet(laser, &mut self.bullet)
-                    || self.collision.laser_to_shield(laser, &mut self.shields)
-                {
+                } else if self.collision.laser_to_bullets(laser, &mut self.players) {
+                    destroy.push(i);
+                } else if self.collision.laser_to_shield(laser, &mut self.shields) {
+                    self.events.push(GameEvent::ShieldHit);
                     destroy.push(i);
                 }
             } else {
+                // The laser hit the ground
+                self.cracks.push(Crack {
+                    x: laser.pos.x + laser.sprite.width() / 2,
+                    pattern: self.prng.next_u32(),
+                    ttl: CRACK_DURATION,
+                });
+
                 destroy.push(i);
             }
         }
@@ -286,52 +862,145 @@ impl World {
         for &i in destroy.iter().rev() {
             self.lasers.remove(i);
         }
+
+        // No more updates are processed after the game ends, so this is only emitted once
+        if self.is_gameover() {
+            self.events.push(GameEvent::GameOver);
+        }
     }
 
     /// Draw the internal state to the screen.
     ///
-    /// Calling this method more than once without an `update` call between is a no-op.
+    /// The screen must be `width * height * 4` bytes long, see [`World::size`]. Calling this method
+    /// more than once without an `update` call between is a no-op.
     pub fn draw(&mut self, screen: &mut [u8]) {
+        let width = self.width;
+        assert_eq!(screen.len(), width * self.height * 4);
+
         // Clear the screen
         clear(screen);
 
+        if self.state == GameState::Title {
+            self.draw_title(screen);
+            return;
+        }
+
         // Draw the invaders
         for row in &self.invaders.grid {
             for invader in row.iter().flatten() {
-                blit(screen, &invader.pos, &invader.sprite);
+                blit(screen, width, &invader.pos, &invader.sprite);
             }
         }
 
         // Draw the shields
         for shield in &self.shields {
-            blit(screen, &shield.pos, &shield.sprite);
+            blit(screen, width, &shield.pos, &shield.sprite);
         }
 
-        // Draw the player
-        blit(screen, &self.player.pos, &self.player.sprite);
+        // Draw the live players and their bullets
+        for player in self.players.iter().filter(|player| player.alive) {
+            blit(screen, width, &player.pos, &player.sprite);
 
-        // Draw the bullet
-        if let Some(bullet) = &self.bullet {
-            blit(screen, &bullet.pos, &bullet.sprite);
+            if let Some(bullet) = &player.bullet {
+                blit(screen, width, &bullet.pos, &bullet.sprite);
+            }
         }
 
         // Draw lasers
         for laser in self.lasers.iter() {
-            blit(screen, &laser.pos, &laser.sprite);
+            blit(screen, width, &laser.pos, &laser.sprite);
+        }
+
+        // Draw the ground, and the cracks where lasers hit it
+        self.draw_ground(screen);
+        for crack in &self.cracks {
+            draw_crack(screen, width, crack, self.ground());
         }
 
         // Draw debug information
         if self.debug {
-            debug::draw_invaders(screen, &self.invaders, &self.collision);
-            debug::draw_bullet(screen, self.bullet.as_ref());
-            debug::draw_lasers(screen, &self.lasers);
-            debug::draw_player(screen, &self.player, &self.collision);
-            debug::draw_shields(screen, &self.shields, &self.collision);
+            debug::draw_invaders(screen, width, &self.invaders, &self.collision);
+            for player in &self.players {
+                debug::draw_bullet(screen, width, player.bullet.as_ref());
+            }
+            debug::draw_lasers(screen, width, &self.lasers);
+            debug::draw_players(screen, width, &self.players, &self.collision);
+            debug::draw_shields(screen, width, &self.shields, &self.collision);
+            debug::draw_grid(screen, width, DEBUG_GRID, self.cursor);
+            debug::draw_stats(screen, width, &self.collision.stats);
+
+            // The overlays are taken out of the world while they draw, so they can borrow it
+            let mut overlays = core::mem::take(&mut self.debug_overlays);
+            for overlay in &mut overlays.0 {
+                overlay.draw(screen, self);
+            }
+            self.debug_overlays = overlays;
         }
+
+        // Darken the frozen game and draw the overlay
+        match self.state {
+            GameState::Paused => draw_overlay(screen, width, &["PAUSED"]),
+            GameState::GameOver => draw_overlay(screen, width, &["GAME OVER", "PRESS FIRE"]),
+            _ => (),
+        }
+    }
+
+    /// Draw the ground line across the screen.
+    fn draw_ground(&self, screen: &mut [u8]) {
+        let ground = self.ground();
+        let (left, right) = (Point::new(0, ground), Point::new(self.width - 1, ground));
+        line(screen, self.width, &left, &right, GROUND_COLOR);
+    }
+
+    /// Draw the title screen, with the points for each type of invader.
+    fn draw_title(&self, screen: &mut [u8]) {
+        use Frame::*;
+
+        const WHITE: [u8; 4] = [255, 255, 255, 255];
+        const TITLE: &str = "PIXEL INVADERS";
+        const MESSAGE: &str = "PRESS FIRE";
+        let table = [
+            (Blipjoy1, "30 POINTS"),
+            (Ferris1, "20 POINTS"),
+            (Cthulhu1, "10 POINTS"),
+        ];
+
+        let width = self.width;
+        let origin = self.origin();
+
+        let pos = Point::new((width - text_width(TITLE)) / 2, origin.y + 48);
+        text(screen, width, &pos, TITLE, WHITE);
+
+        // The array is iterated by value with this syntax in the 2018 edition
+        for (i, (frame, points)) in IntoIterator::into_iter(table).enumerate() {
+            let y = origin.y + 96 + i * GRID.y;
+            let sprite = SpriteRef::new(&self.assets, frame, Duration::default());
+            let x = origin.x + 72 - sprite.width() / 2;
+            blit(screen, width, &Point::new(x, y), &sprite);
+
+            let pos = Point::new(origin.x + 88, y + (sprite.height() - GLYPH_HEIGHT) / 2);
+            text(screen, width, &pos, points, WHITE);
+        }
+
+        let pos = Point::new((width - text_width(MESSAGE)) / 2, origin.y + 176);
+        text(screen, width, &pos, MESSAGE, WHITE);
+
+        self.draw_ground(screen);
+    }
+
+    /// The top-left corner of the default-sized playfield, centered at the bottom of the screen.
+    fn origin(&self) -> Point {
+        Point::new((self.width - WIDTH) / 2, self.height - HEIGHT)
+    }
+
+    /// The vertical position of the ground line.
+    fn ground(&self) -> usize {
+        self.origin().y + GROUND
     }
 
     fn step_invaders(&mut self) {
         let (_, right, _, left) = self.invaders.get_bounds();
+        let (width, origin) = (self.width, self.origin());
         let (invader, is_leader) =
             next_invader(&mut self.invaders.grid, &mut self.invaders.stepper);
 
@@ -353,7 +1022,7 @@ impl World {
                     }
                 }
                 Direction::Right => {
-                    if right > WIDTH - 2 {
+                    if right > width - 2 {
                         self.invaders.bounds.pos.x -= 2;
                         self.invaders.bounds.pos.y += 8;
                         self.invaders.descend = true;
@@ -378,20 +1047,21 @@ impl World {
             invader.pos.y += 8;
 
             // One of the end scenarios
-            if invader.pos.y + 8 >= self.player.pos.y {
-                self.gameover = true;
+            if invader.pos.y + 8 >= origin.y + PLAYER_START.y {
+                self.state = GameState::GameOver;
             }
         }
 
         // Animate the invader
         invader.sprite.step_frame(&self.assets);
 
-        // They also shoot lasers at random with a 1:50 chance
+        // They also shoot lasers at random, with a 1:50 chance on normal difficulty
+        let shoot_chance = self.difficulty.shoot_chance;
         let r = self.prng.next_u32() as usize;
-        let chance = r % 50;
-        if self.lasers.len() < 3 && chance == 0 {
+        let chance = r % shoot_chance;
+        if self.lasers.len() < self.difficulty.max_lasers && chance == 0 {
             // Pick a random column to begin searching for an invader that can fire a laser
-            let col = r / 50 % COLS;
+            let col = r / shoot_chance % COLS;
             let invader = self.invaders.get_closest_invader(col);
 
             let laser = Laser {
@@ -400,65 +1070,175 @@ impl World {
                 dt: Duration::default(),
             };
             self.lasers.push(laser);
+            self.events.push(GameEvent::LaserFired);
         }
     }
 
-    fn step_player(&mut self, controls: &Controls) {
-        let frames = self.player.update();
-        let width = self.player.sprite.width();
+    fn step_players(&mut self, controls: &[Controls]) {
+        let top = self.origin().y + PLAYER_TOP;
+        let vertical_movement = self.vertical_movement;
+        let fire_cooldown = self.difficulty.fire_cooldown;
+        let players = self.players.iter_mut().zip(controls).enumerate();
 
-        match controls.direction {
-            Direction::Left => {
-                if self.player.pos.x > width {
-                    self.player.pos.x -= frames;
-                    self.player.sprite.animate(&self.assets);
-                }
-            }
+        for (i, (player, controls)) in players.filter(|(_, (player, _))| player.alive) {
+            let frames = player.update();
+            let width = player.sprite.width();
 
-            Direction::Right => {
-                if self.player.pos.x < WIDTH - width * 2 {
-                    self.player.pos.x += frames;
-                    self.player.sprite.animate(&self.assets);
+            match controls.direction {
+                Direction::Left if player.pos.x > width => {
+                    player.pos.x -= frames;
+                    player.sprite.animate(&self.assets);
+                }
+                Direction::Right if player.pos.x < self.width - width * 2 => {
+                    player.pos.x += frames;
+                    player.sprite.animate(&self.assets);
+                }
+                Direction::Up if vertical_movement && player.pos.y > top => {
+                    player.pos.y -= frames;
+                    player.sprite.animate(&self.assets);
+                }
+                Direction::Down if vertical_movement && player.pos.y < player.start.y => {
+                    player.pos.y += frames;
+                    player.sprite.animate(&self.assets);
                 }
+                _ => (),
             }
-            _ => (),
-        }
 
-        if controls.fire && self.bullet.is_none() {
-            self.bullet = Some(Bullet {
-                sprite: SpriteRef::new(&self.assets, Frame::Bullet1, Duration::from_millis(32)),
-                pos: self.player.pos + BULLET_OFFSET,
-                dt: Duration::default(),
-            });
+            player.fire_cooldown = player.fire_cooldown.saturating_sub(TIME_STEP);
+            if controls.fire && player.bullet.is_none() && player.fire_cooldown.is_zero() {
+                player.fire_cooldown = fire_cooldown;
+                player.bullet = Some(Bullet {
+                    sprite: SpriteRef::new(&self.assets, Frame::Bullet1, Duration::from_millis(32)),
+                    pos: player.pos + BULLET_OFFSET,
+                    dt: Duration::default(),
+                });
+                self.events.push(GameEvent::Fire { player: i });
+            }
         }
     }
 
     pub fn reset_game(&mut self) {
+        let origin = self.origin();
+
         // Recreate the alien
-        self.invaders = Invaders::new(&self.assets);
+        self.invaders = Invaders::new(&self.assets, origin + START);
 
         // Empty laser
         self.lasers.clear();
+        self.cracks.clear();
 
         // Recreate the shield
-        self.shields = (0..4)
-            .map(|i| Shield::new(&self.assets, Point::new(i * 45 + 32, 192)))
-            .collect();
+        self.shields = self.shield_layout.create(&self.assets, origin);
 
-        // Reset player position
-        self.player.pos = PLAYER_START;
-
-        // Remove bullet
-        self.bullet = None;
+        // Reset player positions, bullets, and scores
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.start = origin + PLAYER_START + Point::new(i * PLAYER_SPACING, 0);
+            player.reset(&self.assets);
+        }
 
-        // Reset collision state
+        // Reset collision state and events
         self.collision.clear();
+        self.events.clear();
 
-        // Reset game score
-        self._score = 0;
+        // Start playing
+        self.state = GameState::Playing;
+    }
 
-        // Set gameover to false
-        self.gameover = false;
+    /// Take a snapshot of the game, which can be restored later with [`World::load_state`].
+    ///
+    /// The snapshot includes the invaders, projectiles, shields with their damage, players and
+    /// their scores, the screen size, and the PRNG state, so the game continues exactly as it
+    /// would have from this point. Debug visualizations and the recording are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use simple_invaders::{Controls, World};
+    ///
+    /// let mut world = World::default();
+    /// let save_state = world.save_state();
+    ///
+    /// world.update(&Controls {
+    ///     fire: true,
+    ///     ..Controls::default()
+    /// });
+    /// world.load_state(save_state);
+    /// ```
+    pub fn save_state(&self) -> SaveState {
+        SaveState {
+            invaders: self.invaders.clone(),
+            lasers: self.lasers.clone(),
+            cracks: self.cracks.clone(),
+            shields: self.shields.clone(),
+            shield_layout: self.shield_layout,
+            players: self.players.clone(),
+            difficulty: self.difficulty,
+            dt: self.dt,
+            state: self.state,
+            width: self.width,
+            height: self.height,
+            prng: (self.prng.state, self.prng.inc),
+            vertical_movement: self.vertical_movement,
+        }
+    }
+
+    /// Restore a snapshot taken with [`World::save_state`].
+    ///
+    /// The pixel data for each sprite is reloaded from the assets, so this also restores states
+    /// that were deserialized with the `serde` feature. Note that the screen size is restored,
+    /// too.
+    pub fn load_state(&mut self, save_state: SaveState) {
+        let SaveState {
+            invaders,
+            lasers,
+            cracks,
+            shields,
+            shield_layout,
+            players,
+            difficulty,
+            dt,
+            state,
+            width,
+            height,
+            prng,
+            vertical_movement,
+        } = save_state;
+
+        self.invaders = invaders;
+        self.lasers = lasers;
+        self.cracks = cracks;
+        self.shields = shields;
+        self.shield_layout = shield_layout;
+        self.players = players;
+        self.difficulty = difficulty;
+        self.dt = dt;
+        self.state = state;
+        self.width = width;
+        self.height = height;
+        self.prng = PCG32 {
+            state: prng.0,
+            inc: prng.1,
+        };
+        self.vertical_movement = vertical_movement;
+
+        // Sprite pixels are not part of the save state
+        let invaders = self.invaders.grid.iter_mut().flatten().flatten();
+        let lasers = self.lasers.iter_mut().map(|laser| &mut laser.sprite);
+        let players = self.players.iter_mut().flat_map(|player| {
+            let bullet = player.bullet.as_mut().map(|bullet| &mut bullet.sprite);
+            core::iter::once(&mut player.sprite).chain(bullet)
+        });
+        for sprite in invaders
+            .map(|invader| &mut invader.sprite)
+            .chain(lasers)
+            .chain(players)
+        {
+            sprite.reload(&self.assets);
+        }
+
+        // Reset collision state and events
+        self.collision.clear();
+        self.events.clear();
     }
 }
 
@@ -472,13 +1252,16 @@ impl Default for World {
 }
 
 impl Invaders {
-    // New
-    pub fn new(assets: &Assets) -> Self {
-        let grid = make_invader_grid(assets);
+    /// Create the fleet with its top-left corner at `start`.
+    pub fn new(assets: &Assets, start: Point) -> Self {
+        let grid = make_invader_grid(assets, start);
         let stepper = Point::new(COLS - 1, 0);
         let direction = Direction::Right;
         let descend = false;
-        let bounds = Bounds::default();
+        let bounds = Bounds {
+            pos: start,
+            ..Bounds::default()
+        };
 
         Invaders {
             grid,
@@ -505,6 +1288,22 @@ impl Invaders {
         (top, right, bottom, left)
     }
 
+    /// Compute the bounding box for the Invader fleet as a `Rect`.
+    fn bounds_rect(&self) -> Rect {
+        let (top, right, bottom, left) = self.get_bounds();
+
+        Rect::new(&Point::new(left, top), &Point::new(right, bottom))
+    }
+
+    /// Count the invaders that are still alive.
+    fn count_alive(&self) -> usize {
+        self.grid
+            .iter()
+            .flatten()
+            .filter(|col| col.is_some())
+            .count()
+    }
+
     /// Resize the bounds to fit the live invaders.
     ///
     /// # Returns
@@ -581,14 +1380,68 @@ impl Default for Bounds {
 }
 
 /// Clear the screen
-fn clear(screen: &mut [u8]) {
-    for (i, byte) in screen.iter_mut().enumerate() {
-        *byte = if i % 4 == 3 { 255 } else { 0 };
+///
+/// This is only public for `benches/clear.rs`.
+#[doc(hidden)]
+pub fn clear(screen: &mut [u8]) {
+    // Writing whole pixels is over ten times faster than checking the index of each byte, see
+    // `benches/clear.rs`
+    for pixel in screen.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&[0, 0, 0, 255]);
+    }
+}
+
+/// Darken the screen and draw lines of text centered over it.
+fn draw_overlay(screen: &mut [u8], width: usize, lines: &[&str]) {
+    const SHADE: [u8; 4] = [0, 0, 0, 160];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const LINE_HEIGHT: usize = GLYPH_HEIGHT * 2;
+
+    let height = screen_height(screen, width);
+    blend_rect(
+        screen,
+        width,
+        &Point::new(0, 0),
+        &Point::new(width, height),
+        SHADE,
+    );
+
+    let top = (height - lines.len() * LINE_HEIGHT + GLYPH_HEIGHT) / 2;
+    for (i, message) in lines.iter().enumerate() {
+        let pos = Point::new((width - text_width(message)) / 2, top + i * LINE_HEIGHT);
+        text(screen, width, &pos, message, WHITE);
+    }
+}
+
+/// Draw a crack in the ground, fading with its remaining time to live.
+///
+/// The crack is a randomized 5x3 pixel pattern centered on the `ground` line. The center pixel is
+/// always scorched.
+fn draw_crack(screen: &mut [u8], width: usize, crack: &Crack, ground: usize) {
+    let alpha = crack.ttl.as_millis() * 255 / CRACK_DURATION.as_millis();
+    let color = [CRACK_COLOR[0], CRACK_COLOR[1], CRACK_COLOR[2], alpha as u8];
+    let pattern = crack.pattern | 1 << 7;
+
+    for bit in (0..15).filter(|bit| pattern & 1 << bit != 0) {
+        let x = (crack.x + bit % 5).checked_sub(2);
+        let y = ground + bit / 5 - 1;
+
+        if let Some(x) = x.filter(|&x| x < width) {
+            blend_rect(
+                screen,
+                width,
+                &Point::new(x, y),
+                &Point::new(x + 1, y + 1),
+                color,
+            );
+        }
     }
 }
 
 /// Create a grid of invaders.
-fn make_invader_grid(assets: &Assets) -> Vec<Vec<Option<Invader>>> {
+///
+/// Like the arcade original, invaders in the top row are worth the most points.
+fn make_invader_grid(assets: &Assets, start: Point) -> Vec<Vec<Option<Invader>>> {
     use Frame::*;
 
     const BLIPJOY_OFFSET: Point = Point::new(3, 4);
@@ -601,8 +1454,8 @@ fn make_invader_grid(assets: &Assets) -> Vec<Vec<Option<Invader>>> {
                 .map(|x| {
                     Some(Invader {
                         sprite: SpriteRef::new(assets, Blipjoy1, Duration::default()),
-                        pos: START + BLIPJOY_OFFSET + Point::new(x, y) * GRID,
-                        _score: 10,
+                        pos: start + BLIPJOY_OFFSET + Point::new(x, y) * GRID,
+                        score: 30,
                     })
                 })
                 .collect()
@@ -612,8 +1465,8 @@ fn make_invader_grid(assets: &Assets) -> Vec<Vec<Option<Invader>>> {
                 .map(|x| {
                     Some(Invader {
                         sprite: SpriteRef::new(assets, Ferris1, Duration::default()),
-                        pos: START + FERRIS_OFFSET + Point::new(x, y) * GRID,
-                        _score: 10,
+                        pos: start + FERRIS_OFFSET + Point::new(x, y) * GRID,
+                        score: 20,
                     })
                 })
                 .collect()
@@ -623,8 +1476,8 @@ fn make_invader_grid(assets: &Assets) -> Vec<Vec<Option<Invader>>> {
                 .map(|x| {
                     Some(Invader {
                         sprite: SpriteRef::new(assets, Cthulhu1, Duration::default()),
-                        pos: START + CTHULHU_OFFSET + Point::new(x, y) * GRID,
-                        _score: 10,
+                        pos: start + CTHULHU_OFFSET + Point::new(x, y) * GRID,
+                        score: 10,
                     })
                 })
                 .collect()
@@ -658,3 +1511,548 @@ fn next_invader<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::CollisionStats;
+
+    #[test]
+    fn test_replay() {
+        let seed = (0x853c_49e6_748f_ea9b, 0xda3e_39cb_94b9_5bdb);
+        let mut world = World::new(seed, false);
+        world.record();
+
+        // Play a game with inputs that change every few updates
+        let mut ticks = 0;
+        while !world.is_gameover() && ticks < 20_000 {
+            let direction = match (ticks / 120) % 3 {
+                0 => Direction::Left,
+                1 => Direction::Right,
+                _ => Direction::Still,
+            };
+            let controls = Controls {
+                direction,
+                fire: ticks % 5 == 0,
+            };
+            world.update(&controls);
+            ticks += 1;
+        }
+        assert!(world.is_gameover());
+        assert!(world.score(0).unwrap() > 0);
+        assert!(world.invaders_remaining() < ROWS * COLS);
+        assert_eq!(world.recording().len(), ticks);
+
+        // Pausing does not record anything
+        world.set_paused(true);
+        world.update(&Controls::default());
+        assert_eq!(world.recording().len(), ticks);
+
+        let replay = World::from_replay(seed, world.recording());
+        assert_eq!(replay.is_gameover(), world.is_gameover());
+        assert_eq!(replay.score(0), world.score(0));
+        assert_eq!(replay.invaders_remaining(), world.invaders_remaining());
+        assert_eq!(replay.lasers.len(), world.lasers.len());
+        let (a, b) = (replay.invaders.bounds.pos, world.invaders.bounds.pos);
+        assert_eq!((a.x, a.y), (b.x, b.y));
+        let (a, b) = (replay.players[0].pos, world.players[0].pos);
+        assert_eq!((a.x, a.y), (b.x, b.y));
+    }
+
+    #[test]
+    fn test_invader_scores() {
+        let mut world = World::new((0x853c_49e6_748f_ea9b, 0xda3e_39cb_94b9_5bdb), false);
+
+        // Shoot the first invader in each row
+        for (row, expected) in [(0, 30), (1, 20), (2, 20), (3, 10), (4, 10)] {
+            let pos = world.invaders.grid[row][0].as_ref().unwrap().pos;
+            let mut bullet = Some(Bullet {
+                sprite: SpriteRef::new(&world.assets, Frame::Bullet1, Duration::default()),
+                pos: pos + Point::new(2, 2),
+                dt: Duration::default(),
+            });
+
+            let score = world
+                .collision
+                .bullet_to_invader(&mut bullet, &mut world.invaders, 0);
+            assert_eq!(score, Some(expected));
+            assert!(bullet.is_none());
+            assert!(world.invaders.grid[row][0].is_none());
+            world.collision.clear();
+        }
+    }
+
+    #[test]
+    fn test_bullet_tunneling() {
+        let mut world = World::default();
+        let invader = world.invaders.grid[0][0].as_ref().unwrap();
+        let (pos, invader_height) = (invader.pos, invader.sprite.height());
+        let sprite = SpriteRef::new(&world.assets, Frame::Bullet1, Duration::default());
+        let bullet_height = sprite.height();
+
+        // The bullet is just above the top left invader, after starting just below it
+        let bullet_pos = Point::new(pos.x + 2, pos.y - bullet_height - 1);
+        let distance = bullet_height + invader_height + 2;
+        let mut bullet = Some(Bullet {
+            sprite,
+            pos: bullet_pos,
+            dt: Duration::default(),
+        });
+
+        // Only the position after the move misses the invader
+        let score = world
+            .collision
+            .bullet_to_invader(&mut bullet, &mut world.invaders, 0);
+        assert_eq!(score, None);
+        world.collision.clear();
+
+        // But the bullet passed through it
+        let score = world
+            .collision
+            .bullet_to_invader(&mut bullet, &mut world.invaders, distance);
+        assert_eq!(score, Some(30));
+        assert!(bullet.is_none());
+        assert!(world.invaders.grid[0][0].is_none());
+        world.collision.clear();
+
+        // A path through two invaders hits the lower one, which the bullet reached first
+        let mut bullet = Some(Bullet {
+            sprite: SpriteRef::new(&world.assets, Frame::Bullet1, Duration::default()),
+            pos: bullet_pos,
+            dt: Duration::default(),
+        });
+        let score = world
+            .collision
+            .bullet_to_invader(&mut bullet, &mut world.invaders, GRID.y * 2);
+        assert_eq!(score, Some(20));
+        assert!(world.invaders.grid[1][0].is_none());
+        assert!(world.invaders.grid[2][0].is_some());
+        world.collision.clear();
+
+        // Shields are tested along the bullet path, too
+        let shield = &world.shields[0];
+        let (pos, shield_height) = (shield.pos, shield.sprite.height());
+        let distance = bullet_height + shield_height + 2;
+        let mut bullet = Some(Bullet {
+            sprite: SpriteRef::new(&world.assets, Frame::Bullet1, Duration::default()),
+            pos: Point::new(pos.x + 2, pos.y - bullet_height - 1),
+            dt: Duration::default(),
+        });
+        let shields = &mut world.shields;
+        assert!(!world.collision.bullet_to_shield(&mut bullet, shields, 0));
+        assert!(world
+            .collision
+            .bullet_to_shield(&mut bullet, shields, distance));
+        assert!(bullet.is_none());
+    }
+
+    #[test]
+    fn test_multiplayer_bullets() {
+        let mut world =
+            World::new_multiplayer((0x853c_49e6_748f_ea9b, 0xda3e_39cb_94b9_5bdb), false, 2);
+
+        // Both bullets are inside the fleet in the same update, and the first one kills its target
+        for (player, col) in [(0, 0), (1, COLS - 1)] {
+            let pos = world.invaders.grid[ROWS - 1][col].as_ref().unwrap().pos;
+            world.players[player].bullet = Some(Bullet {
+                sprite: SpriteRef::new(&world.assets, Frame::Bullet1, Duration::default()),
+                pos: pos + Point::new(4, 4),
+                dt: Duration::default(),
+            });
+        }
+        world.update_multiplayer(&[Controls::default(), Controls::default()]);
+
+        let kills = world
+            .events()
+            .iter()
+            .filter(|event| matches!(event, GameEvent::InvaderKilled { .. }))
+            .count();
+        assert_eq!(kills, 2);
+        assert!(world.invaders.grid[ROWS - 1][0].is_none());
+        assert!(world.invaders.grid[ROWS - 1][COLS - 1].is_none());
+    }
+
+    #[test]
+    fn test_save_state() {
+        let mut world = World::new((0x853c_49e6_748f_ea9b, 0xda3e_39cb_94b9_5bdb), false);
+        let play = |world: &mut World, ticks: usize| {
+            for tick in 0..ticks {
+                let direction = match (tick / 120) % 2 {
+                    0 => Direction::Left,
+                    _ => Direction::Right,
+                };
+                let controls = Controls {
+                    direction,
+                    fire: tick % 7 == 0,
+                };
+                world.update(&controls);
+            }
+        };
+
+        // Take a snapshot in the middle of a game, with damaged shields
+        play(&mut world, 3_000);
+        let save_state = world.save_state();
+        let mut screen = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut screen);
+
+        // The game continues the same way after restoring the snapshot
+        play(&mut world, 2_000);
+        let mut expected = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut expected);
+        let score = world.score(0);
+
+        world.load_state(save_state.clone());
+        let mut restored = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut restored);
+        assert_eq!(restored, screen);
+
+        play(&mut world, 2_000);
+        let mut actual = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut actual);
+        assert_eq!(world.score(0), score);
+        assert_eq!(actual, expected);
+
+        // Even in a different world
+        let mut other = World::default();
+        other.load_state(save_state);
+        play(&mut other, 2_000);
+        other.draw(&mut actual);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_game_state() {
+        let mut world = World::default();
+        let fire = Controls {
+            fire: true,
+            ..Controls::default()
+        };
+        world.show_title();
+
+        // The title screen waits for a player to press fire
+        world.update(&Controls::default());
+        assert_eq!(world.state(), GameState::Title);
+        let mut screen = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut screen);
+        world.set_paused(true);
+        assert_eq!(world.state(), GameState::Title);
+        world.update(&fire);
+        assert_eq!(world.state(), GameState::Playing);
+        assert!(world.events().is_empty());
+
+        // Pausing only affects a game in progress
+        world.set_paused(true);
+        assert_eq!(world.state(), GameState::Paused);
+        world.update(&fire);
+        assert_eq!(world.state(), GameState::Paused);
+        world.set_paused(false);
+        assert_eq!(world.state(), GameState::Playing);
+
+        // Firing after the game is over returns to the title screen
+        world.state = GameState::GameOver;
+        world.set_paused(true);
+        assert!(world.is_gameover());
+        world.update(&Controls::default());
+        world.update(&fire);
+        assert_eq!(world.state(), GameState::Title);
+    }
+
+    #[test]
+    fn test_hold_fire_across_death() {
+        let mut world = World::default();
+        let fire = Controls {
+            fire: true,
+            ..Controls::default()
+        };
+        world.update(&fire);
+
+        // Drop a laser on the player while fire is held
+        let laser = Laser {
+            sprite: SpriteRef::new(&world.assets, Frame::Laser1, Duration::from_millis(16)),
+            pos: world.players[0].pos,
+            dt: Duration::default(),
+        };
+        world.lasers = alloc::vec![laser];
+        world.update(&fire);
+        assert!(world.is_gameover());
+
+        // Holding fire does not leave the game over screen
+        for _ in 0..FPS {
+            world.update(&fire);
+            assert_eq!(world.state(), GameState::GameOver);
+        }
+
+        // Nor the title screen
+        world.update(&Controls::default());
+        world.update(&fire);
+        assert_eq!(world.state(), GameState::Title);
+        for _ in 0..FPS {
+            world.update(&fire);
+            assert_eq!(world.state(), GameState::Title);
+        }
+
+        world.update(&Controls::default());
+        world.update(&fire);
+        assert_eq!(world.state(), GameState::Playing);
+    }
+
+    #[test]
+    fn test_fire_cooldown() {
+        let difficulty = Difficulty {
+            fire_cooldown: TIME_STEP * (FPS / 2) as u32,
+            ..Difficulty::default()
+        };
+        let mut world = World::new_with_difficulty((0x853c_49e6_748f_ea9b, 1), false, difficulty);
+        let fire = Controls {
+            fire: true,
+            ..Controls::default()
+        };
+        let fired = |world: &World| {
+            world
+                .events()
+                .iter()
+                .any(|event| matches!(event, GameEvent::Fire { .. }))
+        };
+
+        world.update(&fire);
+        assert!(fired(&world));
+
+        // The bullet is gone, but the player cannot fire again until the cooldown has elapsed
+        world.players[0].bullet = None;
+        for _ in 1..FPS / 2 {
+            world.lasers.clear();
+            world.update(&fire);
+            assert!(!fired(&world));
+            world.players[0].bullet = None;
+        }
+        world.update(&fire);
+        assert!(fired(&world));
+    }
+
+    #[test]
+    fn test_world_size() {
+        let (width, height) = (WIDTH + 96, HEIGHT + 32);
+        let mut world = World::new_with_size((0x853c_49e6_748f_ea9b, 1), true, width, height);
+        assert_eq!(world.size(), (width, height));
+
+        // The default layout is centered at the bottom of the larger screen
+        let pos = world.invaders.bounds.pos;
+        assert_eq!((pos.x, pos.y), (START.x + 48, START.y + 32));
+        let pos = world.players[0].pos;
+        assert_eq!((pos.x, pos.y), (PLAYER_START.x + 48, PLAYER_START.y + 32));
+
+        // The invaders march across the full width of the screen
+        let mut right = 0;
+        for _ in 0..ROWS * COLS * 40 {
+            world.step_invaders();
+            right = right.max(world.invaders.get_bounds().1);
+        }
+        assert_eq!(right, width);
+
+        let mut screen = alloc::vec![0; width * height * 4];
+        world.update(&Controls::default());
+        world.draw(&mut screen);
+    }
+
+    #[test]
+    fn test_shield_layout() {
+        let seed = (0x853c_49e6_748f_ea9b, 1);
+        let world = World::new_with_shields(seed, true, ShieldLayout::classic());
+        let xs: Vec<usize> = world.shields.iter().map(|shield| shield.pos.x).collect();
+        assert_eq!(xs, [32, 77, 122, 167]);
+
+        // Without shields, bullets and lasers fly through
+        let layout = ShieldLayout {
+            count: 0,
+            ..ShieldLayout::classic()
+        };
+        let mut world = World::new_with_shields(seed, true, layout);
+        assert!(world.shields.is_empty());
+        let fire = Controls {
+            direction: Direction::Still,
+            fire: true,
+        };
+        for _ in 0..1000 {
+            world.update(&fire);
+            assert!(!world.events().contains(&GameEvent::ShieldHit));
+        }
+        let mut screen = alloc::vec![0; WIDTH * HEIGHT * 4];
+        world.draw(&mut screen);
+
+        // A new layout takes effect when the next game starts
+        let layout = ShieldLayout {
+            count: 7,
+            spacing: 28,
+            x: 10,
+        };
+        world.set_shield_layout(layout);
+        assert!(world.shields.is_empty());
+        world.reset_game();
+        assert_eq!(world.shield_layout(), layout);
+        assert_eq!(world.shields.len(), 7);
+        assert!(layout.right(&world.assets) <= WIDTH);
+    }
+
+    #[test]
+    fn test_collision_stats() {
+        let mut world = World::default();
+        let fire = Controls {
+            direction: Direction::Still,
+            fire: true,
+        };
+
+        // Fire until a bullet destroys an invader, without shields or lasers in the way
+        world.shields.clear();
+        let mut hit = false;
+        for _ in 0..FPS * 10 {
+            world.lasers.clear();
+            world.update(&fire);
+
+            let stats = world.collision.stats;
+            assert!(stats.broad_phase <= stats.tested);
+            assert!(stats.narrow_phase <= stats.broad_phase);
+            let killed = |event: &GameEvent| matches!(event, GameEvent::InvaderKilled { .. });
+            if world.events().iter().any(killed) {
+                assert_eq!(stats.narrow_phase, 1);
+                hit = true;
+                break;
+            }
+        }
+        assert!(hit);
+
+        // The counters are reset for each update
+        world.collision.clear();
+        assert_eq!(world.collision.stats, CollisionStats::default());
+    }
+
+    #[test]
+    fn test_vertical_movement() {
+        let up = Controls {
+            direction: Direction::Up,
+            fire: false,
+        };
+        let down = Controls {
+            direction: Direction::Down,
+            fire: false,
+        };
+
+        // The classic game ignores vertical movement
+        let mut world = World::default();
+        for _ in 0..FPS {
+            world.update(&up);
+        }
+        assert_eq!(world.players[0].pos.y, PLAYER_START.y);
+
+        // Players move up to the top of their range, and back down to the bottom row. Lasers are
+        // removed so the player survives long enough to get there.
+        world.set_vertical_movement(true);
+        for _ in 0..FPS * 2 {
+            world.lasers.clear();
+            world.update(&up);
+        }
+        assert_eq!(world.players[0].pos.y, PLAYER_TOP);
+        for _ in 0..FPS * 2 {
+            world.lasers.clear();
+            world.update(&down);
+        }
+        assert_eq!(world.players[0].pos.y, PLAYER_START.y);
+
+        assert!(matches!(
+            Direction::from_inputs_4way(false, true, true, false),
+            Direction::Right
+        ));
+        assert!(matches!(
+            Direction::from_inputs_4way(false, false, true, true),
+            Direction::Up
+        ));
+        assert!(matches!(
+            Direction::from_inputs_4way(false, false, false, true),
+            Direction::Down
+        ));
+    }
+
+    #[test]
+    fn test_debug_draw() {
+        const MAGENTA: [u8; 4] = [0xff, 0x00, 0xff, 0xff];
+
+        let mut world = World::default();
+        assert_eq!(
+            world.player_position(0),
+            Some((PLAYER_START.x, PLAYER_START.y))
+        );
+        assert_eq!(world.player_position(1), None);
+        assert_eq!(world.bullet_position(0), None);
+
+        // Mark each player and laser with a magenta pixel
+        world.add_debug_draw(Box::new(|screen: &mut [u8], world: &World| {
+            let (width, _) = world.size();
+            let player = (0..world.num_players()).filter_map(|i| world.player_position(i));
+            for (x, y) in player.chain(world.laser_positions()) {
+                let i = (y * width + x) * 4;
+                screen[i..i + 4].copy_from_slice(&MAGENTA);
+            }
+        }));
+        let mut screen = alloc::vec![0; WIDTH * HEIGHT * 4];
+        let marked = |screen: &[u8]| screen.chunks_exact(4).filter(|&p| p == MAGENTA).count();
+
+        // Overlays are only drawn with debug visualizations enabled
+        world.draw(&mut screen);
+        assert_eq!(marked(&screen), 0);
+        world.set_debug(true);
+        world.lasers.clear();
+        world.draw(&mut screen);
+        assert_eq!(marked(&screen), 1);
+
+        // The overlay sees the current state of the world
+        while world.laser_positions().count() == 0 {
+            world.update(&Controls::default());
+        }
+        world.draw(&mut screen);
+        assert_eq!(marked(&screen), 1 + world.laser_positions().count());
+    }
+
+    #[test]
+    fn test_input_source() {
+        // An input source that is not `Controls`, like a gamepad with a start button
+        struct Gamepad {
+            stick_x: i8,
+            south: bool,
+            start: bool,
+        }
+
+        impl InputSource for Gamepad {
+            fn direction(&self) -> Direction {
+                Direction::from_inputs(self.stick_x < 0, self.stick_x > 0)
+            }
+
+            fn fire(&self) -> bool {
+                self.south
+            }
+
+            fn pause(&self) -> bool {
+                self.start
+            }
+        }
+
+        let mut world = World::default();
+        world.record();
+        world.update(&Gamepad {
+            stick_x: -100,
+            south: true,
+            start: false,
+        });
+        assert!(world.events().contains(&GameEvent::Fire { player: 0 }));
+        assert!(matches!(world.recording()[0].direction, Direction::Left));
+
+        // Pressing start toggles the pause state
+        let start = Gamepad {
+            stick_x: 0,
+            south: false,
+            start: true,
+        };
+        world.update(&start);
+        assert_eq!(world.state(), GameState::Paused);
+        world.update(&start);
+        assert_eq!(world.state(), GameState::Playing);
+    }
+}
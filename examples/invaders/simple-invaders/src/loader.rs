@@ -16,42 +16,75 @@ impl Assets {
     }
 }
 
+/// A region of a sprite atlas, as `(key, x, y, width, height)` in pixels.
+type AtlasRegion<K> = (K, usize, usize, usize, usize);
+
+/// Where each frame is packed in `assets/atlas.pcx`.
+///
+/// New sprites are added by pasting them into any unused space in the atlas image (or growing
+/// it), and adding their region here.
+const SPRITE_ATLAS: &[AtlasRegion<Frame>] = &[
+    (Frame::Blipjoy1, 0, 0, 10, 8),
+    (Frame::Blipjoy2, 10, 0, 10, 8),
+    (Frame::Ferris1, 20, 0, 11, 7),
+    (Frame::Ferris2, 31, 0, 11, 7),
+    (Frame::Cthulhu1, 42, 0, 13, 10),
+    (Frame::Cthulhu2, 55, 0, 13, 10),
+    (Frame::Player1, 0, 10, 16, 16),
+    (Frame::Player2, 16, 10, 16, 16),
+    (Frame::Shield1, 32, 10, 22, 16),
+    (Frame::Bullet1, 0, 26, 2, 4),
+    (Frame::Bullet2, 2, 26, 2, 4),
+    (Frame::Bullet3, 4, 26, 2, 4),
+    (Frame::Bullet4, 6, 26, 2, 4),
+    (Frame::Bullet5, 8, 26, 2, 4),
+    (Frame::Laser1, 10, 26, 5, 7),
+    (Frame::Laser2, 15, 26, 5, 7),
+    (Frame::Laser3, 20, 26, 5, 7),
+    (Frame::Laser4, 25, 26, 5, 7),
+    (Frame::Laser5, 30, 26, 5, 7),
+    (Frame::Laser6, 35, 26, 5, 7),
+    (Frame::Laser7, 40, 26, 5, 7),
+    (Frame::Laser8, 45, 26, 5, 7),
+];
+
 /// Load all static assets into an `Assets` structure
 pub(crate) fn load_assets() -> Assets {
-    use Frame::*;
-
-    let mut sprites = BTreeMap::new();
-
-    sprites.insert(Blipjoy1, load_pcx(include_bytes!("assets/blipjoy1.pcx")));
-    sprites.insert(Blipjoy2, load_pcx(include_bytes!("assets/blipjoy2.pcx")));
-
-    sprites.insert(Ferris1, load_pcx(include_bytes!("assets/ferris1.pcx")));
-    sprites.insert(Ferris2, load_pcx(include_bytes!("assets/ferris2.pcx")));
-
-    sprites.insert(Cthulhu1, load_pcx(include_bytes!("assets/cthulhu1.pcx")));
-    sprites.insert(Cthulhu2, load_pcx(include_bytes!("assets/cthulhu2.pcx")));
+    let sprites = load_atlas(include_bytes!("assets/atlas.pcx"), SPRITE_ATLAS);
 
-    sprites.insert(Player1, load_pcx(include_bytes!("assets/player1.pcx")));
-    sprites.insert(Player2, load_pcx(include_bytes!("assets/player2.pcx")));
-
-    sprites.insert(Shield1, load_pcx(include_bytes!("assets/shield.pcx")));
-
-    sprites.insert(Bullet1, load_pcx(include_bytes!("assets/bullet1.pcx")));
-    sprites.insert(Bullet2, load_pcx(include_bytes!("assets/bullet2.pcx")));
-    sprites.insert(Bullet3, load_pcx(include_bytes!("assets/bullet3.pcx")));
-    sprites.insert(Bullet4, load_pcx(include_bytes!("assets/bullet4.pcx")));
-    sprites.insert(Bullet5, load_pcx(include_bytes!("assets/bullet5.pcx")));
+    Assets { sprites }
+}
 
-    sprites.insert(Laser1, load_pcx(include_bytes!("assets/laser1.pcx")));
-    sprites.insert(Laser2, load_pcx(include_bytes!("assets/laser2.pcx")));
-    sprites.insert(Laser3, load_pcx(include_bytes!("assets/laser3.pcx")));
-    sprites.insert(Laser4, load_pcx(include_bytes!("assets/laser4.pcx")));
-    sprites.insert(Laser5, load_pcx(include_bytes!("assets/laser5.pcx")));
-    sprites.insert(Laser6, load_pcx(include_bytes!("assets/laser6.pcx")));
-    sprites.insert(Laser7, load_pcx(include_bytes!("assets/laser7.pcx")));
-    sprites.insert(Laser8, load_pcx(include_bytes!("assets/laser8.pcx")));
+/// Cut a sprite atlas into sprites.
+///
+/// The atlas is a single PCX image with many sprites packed into it. Each region is copied out of
+/// the image into its own sprite, keyed by the region's key.
+///
+/// # Panics
+///
+/// Panics when any region is outside of the image.
+fn load_atlas<K: Copy + Ord>(pcx: &[u8], regions: &[AtlasRegion<K>]) -> BTreeMap<K, CachedSprite> {
+    let (width, height, pixels) = load_pcx(pcx);
+
+    regions
+        .iter()
+        .map(|&(key, x, y, w, h)| {
+            assert!(
+                x + w <= width && y + h <= height,
+                "Atlas region is outside of the image"
+            );
+
+            let sprite = (y..y + h)
+                .flat_map(|row| {
+                    let i = (x + row * width) * 4;
+                    &pixels[i..i + w * 4]
+                })
+                .copied()
+                .collect::<Vec<u8>>();
 
-    Assets { sprites }
+            (key, (w, h, Rc::from(sprite.as_ref())))
+        })
+        .collect()
 }
 
 /// Convert PCX data to raw pixels
@@ -118,7 +151,8 @@ mod tests {
 
     #[test]
     fn test_pcx() {
-        let pixels = load_pcx(include_bytes!("assets/blipjoy1.pcx"));
+        let sprites = load_atlas(include_bytes!("assets/atlas.pcx"), SPRITE_ATLAS);
+        let pixels = &sprites[&Frame::Blipjoy1];
         let expected = vec![
             0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0,
             255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0,
@@ -141,4 +175,21 @@ mod tests {
         assert_eq!(pixels.1, 8, "Height differs");
         assert_eq!(pixels.2.to_vec(), expected, "Pixels differ");
     }
+
+    #[test]
+    fn test_atlas() {
+        let sprites = load_assets().sprites;
+
+        // Every region is a distinct frame, and the sprites are cut to the size of the region
+        assert_eq!(sprites.len(), SPRITE_ATLAS.len());
+        for &(frame, _, _, width, height) in SPRITE_ATLAS {
+            let (w, h, pixels) = &sprites[&frame];
+            assert_eq!((*w, *h), (width, height), "{frame:?} size differs");
+            assert_eq!(pixels.len(), width * height * 4, "{frame:?} length differs");
+        }
+
+        // The player sprite is cut from the middle of the atlas
+        let (_, _, player) = &sprites[&Frame::Player1];
+        assert!(player.chunks(4).any(|rgba| rgba != [0, 0, 0, 255]));
+    }
 }
@@ -1,24 +1,49 @@
 use crate::geo::Point;
 use crate::loader::Assets;
-use crate::sprites::{Frame, SpriteRef};
+use crate::sprites::{Animation, Frame, SpriteRef};
+use crate::Bullet;
 use core::time::Duration;
 
-// Player positioning
-const PLAYER_START: Point = Point::new(80, 216);
-
 /// The player entity.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct Player {
     pub sprite: SpriteRef,
     pub pos: Point,
     pub dt: Duration,
+    pub start: Point,
+    pub bullet: Option<Bullet>,
+    /// Time remaining until the player can fire again.
+    pub fire_cooldown: Duration,
+    pub score: u32,
+    pub alive: bool,
 }
 
 impl Player {
-    pub fn new(assets: &Assets) -> Self {
+    pub fn new(assets: &Assets, start: Point) -> Self {
         let sprite = SpriteRef::new(assets, Frame::Player1, Duration::from_millis(100));
-        let pos = PLAYER_START;
+        let pos = start;
         let dt = Duration::default();
-        Player { sprite, pos, dt }
+        Player {
+            sprite,
+            pos,
+            dt,
+            start,
+            bullet: None,
+            fire_cooldown: Duration::default(),
+            score: 0,
+            alive: true,
+        }
+    }
+
+    /// Put the player back in its starting state.
+    pub fn reset(&mut self, assets: &Assets) {
+        self.sprite.reset_frame(assets, Frame::Player1);
+        self.pos = self.start;
+        self.dt = Duration::default();
+        self.bullet = None;
+        self.fire_cooldown = Duration::default();
+        self.score = 0;
+        self.alive = true;
     }
 }
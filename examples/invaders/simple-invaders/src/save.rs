@@ -0,0 +1,32 @@
+//! Save states, see [`World::save_state`](crate::World::save_state).
+
+use crate::player::Player;
+use crate::shield::Shield;
+use crate::{Crack, Difficulty, GameState, Invaders, Laser, ShieldLayout};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// A snapshot of everything that affects how the game plays out, see [`World::save_state`].
+///
+/// Sprites are saved by their animation frame, and deformed shields by their pixels. With the
+/// `serde` feature, a `SaveState` can be serialized to keep it across runs.
+///
+/// [`World::save_state`]: crate::World::save_state
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SaveState {
+    pub(crate) invaders: Invaders,
+    pub(crate) lasers: Vec<Laser>,
+    pub(crate) cracks: Vec<Crack>,
+    pub(crate) shields: Vec<Shield>,
+    pub(crate) shield_layout: ShieldLayout,
+    pub(crate) players: Vec<Player>,
+    pub(crate) difficulty: Difficulty,
+    pub(crate) dt: Duration,
+    pub(crate) state: GameState,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    /// The PRNG `state` and `inc`.
+    pub(crate) prng: (u64, u64),
+    pub(crate) vertical_movement: bool,
+}
@@ -1,15 +1,86 @@
 use crate::geo::Point;
 use crate::loader::Assets;
-use crate::sprites::{Frame, Sprite};
+use crate::sprites::{Drawable, Frame, Sprite};
+use alloc::vec::Vec;
+
+/// The vertical position of the shields.
+const SHIELD_Y: usize = 192;
 
 /// The shield entity.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct Shield {
     // Shield sprite is not referenced because we want to deform it when it gets shot
     pub sprite: Sprite,
     pub pos: Point,
 }
 
+/// The number and placement of shields, see
+/// [`World::new_with_shields`](crate::World::new_with_shields).
+///
+/// Shields are placed in a row above the players, `spacing` pixels apart:
+///
+/// ```
+/// use simple_invaders::{ShieldLayout, World};
+///
+/// // Two shields, close to the center of the screen
+/// let layout = ShieldLayout {
+///     count: 2,
+///     spacing: 45,
+///     x: 77,
+/// };
+/// let world = World::new_with_shields((6_364_136_223_846_793_005, 1), false, layout);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ShieldLayout {
+    /// The number of shields. May be zero.
+    pub count: usize,
+    /// The distance between the left edges of neighboring shields, in pixels.
+    pub spacing: usize,
+    /// The left edge of the first shield, in pixels from the left edge of the default screen.
+    pub x: usize,
+}
+
+impl ShieldLayout {
+    /// The classic layout, with four evenly spaced shields.
+    pub const fn classic() -> Self {
+        Self {
+            count: 4,
+            spacing: 45,
+            x: 32,
+        }
+    }
+
+    /// Create the shields with positions relative to `origin`.
+    pub(crate) fn create(&self, assets: &Assets, origin: Point) -> Vec<Shield> {
+        (0..self.count)
+            .map(|i| {
+                Shield::new(
+                    assets,
+                    origin + Point::new(self.x + i * self.spacing, SHIELD_Y),
+                )
+            })
+            .collect()
+    }
+
+    /// The right edge of the last shield, in pixels from the left edge of the default screen.
+    pub(crate) fn right(&self, assets: &Assets) -> usize {
+        let width = Sprite::new(assets, Frame::Shield1).width();
+
+        match self.count {
+            0 => 0,
+            count => self.x + (count - 1) * self.spacing + width,
+        }
+    }
+}
+
+impl Default for ShieldLayout {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
 impl Shield {
     // New
     pub fn new(assets: &Assets, pos: Point) -> Self {
@@ -1,6 +1,6 @@
 use crate::loader::Assets;
+use crate::Point;
 use crate::TIME_STEP;
-use crate::{Point, HEIGHT, WIDTH};
 use alloc::rc::Rc;
 use alloc::vec::Vec;
 use core::time::Duration;
@@ -9,7 +9,8 @@ use core::time::Duration;
 pub(crate) type CachedSprite = (usize, usize, Rc<[u8]>);
 
 /// Frame identifier for managing animations.
-#[derive(Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) enum Frame {
     Blipjoy1,
     Blipjoy2,
@@ -45,7 +46,8 @@ pub(crate) enum Frame {
 ///
 /// A `Sprite` owns its pixel data, and cannot be animated. Use a `SpriteRef` if you need
 /// animations.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct Sprite {
     width: usize,
     height: usize,
@@ -55,16 +57,25 @@ pub(crate) struct Sprite {
 /// SpriteRefs can be drawn and animated.
 ///
 /// They reference their pixel data (instead of owning it).
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub(crate) struct SpriteRef {
     width: usize,
     height: usize,
+    // Pixel data is not saved, it is reloaded from the assets by frame
+    #[cfg_attr(feature = "serde", serde(skip, default = "no_pixels"))]
     pixels: Rc<[u8]>,
     frame: Frame,
     duration: Duration,
     dt: Duration,
 }
 
+/// Placeholder pixel data for deserialized `SpriteRef`s, until they are reloaded.
+#[cfg(feature = "serde")]
+fn no_pixels() -> Rc<[u8]> {
+    Rc::from(Vec::new())
+}
+
 /// Drawables can be blitted to the pixel buffer and animated.
 pub(crate) trait Drawable {
     fn width(&self) -> usize;
@@ -74,6 +85,9 @@ pub(crate) trait Drawable {
 
 pub(crate) trait Animation {
     fn animate(&mut self, assets: &Assets);
+
+    /// Rewind the animation to `frame`, as if it was just created.
+    fn reset_frame(&mut self, assets: &Assets, frame: Frame);
 }
 
 impl Sprite {
@@ -102,6 +116,17 @@ impl SpriteRef {
         }
     }
 
+    /// Reload the pixel data for the current frame, e.g. after restoring a save state.
+    ///
+    /// The animation is not rewound, unlike [`Animation::reset_frame`].
+    pub(crate) fn reload(&mut self, assets: &Assets) {
+        let (width, height, pixels) = assets.sprites().get(&self.frame).unwrap();
+
+        self.width = *width;
+        self.height = *height;
+        self.pixels = Rc::clone(pixels);
+    }
+
     pub(crate) fn step_frame(&mut self, assets: &Assets) {
         use Frame::*;
 
@@ -183,22 +208,80 @@ impl Animation for SpriteRef {
             }
         }
     }
+
+    fn reset_frame(&mut self, assets: &Assets, frame: Frame) {
+        let (width, height, pixels) = assets.sprites().get(&frame).unwrap();
+
+        self.width = *width;
+        self.height = *height;
+        self.pixels = Rc::clone(pixels);
+        self.frame = frame;
+        self.dt = Duration::default();
+    }
+}
+
+/// Get the height of a pixel buffer that is `width` pixels wide.
+pub(crate) fn screen_height(screen: &[u8], width: usize) -> usize {
+    screen.len() / (width * 4)
 }
 
-/// Blit a drawable to the pixel buffer.
-pub(crate) fn blit<S>(screen: &mut [u8], dest: &Point, sprite: &S)
+/// Blit a drawable to a pixel buffer that is `screen_width` pixels wide.
+///
+/// # Panics
+///
+/// Panics if the drawable does not fit entirely on the screen. Use [`blit_clipped`] for drawables
+/// that may be partially off-screen.
+pub(crate) fn blit<S>(screen: &mut [u8], screen_width: usize, dest: &Point, sprite: &S)
 where
     S: Drawable,
 {
-    assert!(dest.x + sprite.width() <= WIDTH);
-    assert!(dest.y + sprite.height() <= HEIGHT);
+    assert!(dest.x + sprite.width() <= screen_width);
+    assert!(dest.y + sprite.height() <= screen_height(screen, screen_width));
+
+    blit_clipped(
+        screen,
+        screen_width,
+        dest.x as isize,
+        dest.y as isize,
+        sprite,
+    );
+}
 
+/// Blit a drawable to a pixel buffer that is `screen_width` pixels wide, skipping the parts that
+/// are off-screen.
+///
+/// The destination is signed, so the drawable can hang off any edge of the screen.
+pub(crate) fn blit_clipped<S>(
+    screen: &mut [u8],
+    screen_width: usize,
+    x: isize,
+    y: isize,
+    sprite: &S,
+) where
+    S: Drawable,
+{
+    let screen_height = screen_height(screen, screen_width);
     let pixels = sprite.pixels();
-    let width = sprite.width() * 4;
 
-    let mut s = 0;
-    for y in 0..sprite.height() {
-        let i = dest.x * 4 + dest.y * WIDTH * 4 + y * WIDTH * 4;
+    // The visible sub-rectangle, in sprite coordinates
+    let left = x.min(0).unsigned_abs().min(sprite.width());
+    let top = y.min(0).unsigned_abs().min(sprite.height());
+    let right = (screen_width as isize).saturating_sub(x);
+    let right = right.clamp(0, sprite.width() as isize) as usize;
+    let bottom = (screen_height as isize).saturating_sub(y);
+    let bottom = bottom.clamp(0, sprite.height() as isize) as usize;
+    if left >= right || top >= bottom {
+        return;
+    }
+
+    // Both are on-screen after clipping
+    let dest_x = (x + left as isize) as usize;
+    let width = (right - left) * 4;
+
+    for row in top..bottom {
+        let dest_y = (y + row as isize) as usize;
+        let i = (dest_x + dest_y * screen_width) * 4;
+        let s = (left + row * sprite.width()) * 4;
 
         // Merge pixels from sprite into screen
         let zipped = screen[i..i + width].iter_mut().zip(&pixels[s..s + width]);
@@ -207,33 +290,115 @@ where
                 *left = right;
             }
         }
-
-        s += width;
     }
 }
 
-/// Draw a line to the pixel buffer using Bresenham's algorithm.
-pub(crate) fn line(screen: &mut [u8], p1: &Point, p2: &Point, color: [u8; 4]) -> Option<()> {
+/// Draw a line to a pixel buffer that is `width` pixels wide using Bresenham's algorithm.
+pub(crate) fn line(
+    screen: &mut [u8],
+    width: usize,
+    p1: &Point,
+    p2: &Point,
+    color: [u8; 4],
+) -> Option<()> {
     let p1 = (p1.x as isize, p1.y as isize);
     let p2 = (p2.x as isize, p2.y as isize);
-    let clip_max = (WIDTH as isize - 1, HEIGHT as isize - 1);
+    let height = screen_height(screen, width);
+    let clip_max = (width as isize - 1, height as isize - 1);
     for (x, y) in clipline::Clipline::new((p1, p2), ((0, 0), clip_max))? {
         let (x, y) = (x as usize, y as usize);
-        let i = x * 4 + y * WIDTH * 4;
+        let i = x * 4 + y * width * 4;
 
         screen[i..i + 4].copy_from_slice(&color);
     }
     Some(())
 }
 
-/// Draw a rectangle to the pixel buffer using two points in opposite corners.
-pub(crate) fn rect(screen: &mut [u8], p1: &Point, p2: &Point, color: [u8; 4]) {
-    let p2 = Point::new(p2.x - 1, p2.y - 1);
+/// Draw a rectangle to a pixel buffer that is `width` pixels wide using two points in opposite
+/// corners.
+pub(crate) fn rect(screen: &mut [u8], width: usize, p1: &Point, p2: &Point, color: [u8; 4]) {
+    let p2 = *p2 - Point::new(1, 1);
     let p3 = Point::new(p1.x, p2.y);
     let p4 = Point::new(p2.x, p1.y);
 
-    line(screen, p1, &p3, color);
-    line(screen, &p3, &p2, color);
-    line(screen, &p2, &p4, color);
-    line(screen, &p4, p1, color);
+    line(screen, width, p1, &p3, color);
+    line(screen, width, &p3, &p2, color);
+    line(screen, width, &p2, &p4, color);
+    line(screen, width, &p4, p1, color);
+}
+
+/// Blend a translucent color over a rectangle in a pixel buffer that is `width` pixels wide, using
+/// two points in opposite corners.
+///
+/// The alpha channel of `color` is the opacity of the blended color. The alpha channel of the
+/// screen is left untouched.
+pub(crate) fn blend_rect(screen: &mut [u8], width: usize, p1: &Point, p2: &Point, color: [u8; 4]) {
+    let alpha = color[3] as u16;
+    let height = screen_height(screen, width);
+    let x_range = p1.x.min(width) * 4..p2.x.min(width) * 4;
+
+    for y in p1.y.min(height)..p2.y.min(height) {
+        let row = &mut screen[y * width * 4..(y + 1) * width * 4];
+
+        for pixel in row[x_range.clone()].chunks_exact_mut(4) {
+            for (dest, &src) in pixel.iter_mut().zip(&color[..3]) {
+                *dest = ((src as u16 * alpha + *dest as u16 * (255 - alpha)) / 255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::load_assets;
+
+    #[test]
+    fn test_blit_clipped() {
+        // A 3x2 sprite with a distinct value in each pixel
+        let sprite = Sprite {
+            width: 3,
+            height: 2,
+            pixels: (1..=6).flat_map(|i| [i; 4]).collect(),
+        };
+        let pixel = |screen: &[u8], x: usize, y: usize| screen[(x + y * 4) * 4];
+
+        // Hanging off the top-left corner
+        let mut screen = [0; 4 * 4 * 4];
+        blit_clipped(&mut screen, 4, -1, -1, &sprite);
+        assert_eq!(pixel(&screen, 0, 0), 5);
+        assert_eq!(pixel(&screen, 1, 0), 6);
+        assert_eq!(screen.iter().filter(|&&byte| byte != 0).count(), 8);
+
+        // Hanging off the bottom-right corner
+        let mut screen = [0; 4 * 4 * 4];
+        blit_clipped(&mut screen, 4, 2, 3, &sprite);
+        assert_eq!(pixel(&screen, 2, 3), 1);
+        assert_eq!(pixel(&screen, 3, 3), 2);
+        assert_eq!(screen.iter().filter(|&&byte| byte != 0).count(), 8);
+
+        // Entirely off-screen
+        let mut screen = [0; 4 * 4 * 4];
+        for (x, y) in [(-3, 0), (0, -2), (4, 0), (0, 4), (isize::MIN, isize::MAX)] {
+            blit_clipped(&mut screen, 4, x, y, &sprite);
+        }
+        assert!(screen.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_reset_frame() {
+        let assets = load_assets();
+        let mut sprite = SpriteRef::new(&assets, Frame::Bullet1, Duration::from_millis(32));
+        for _ in 0..10 {
+            sprite.animate(&assets);
+        }
+        assert_ne!(sprite.frame, Frame::Bullet1);
+        assert_ne!(sprite.dt, Duration::default());
+
+        sprite.reset_frame(&assets, Frame::Bullet1);
+        assert_eq!(sprite.frame, Frame::Bullet1);
+        assert_eq!(sprite.dt, Duration::default());
+        let (_, _, pixels) = assets.sprites().get(&Frame::Bullet1).unwrap();
+        assert!(Rc::ptr_eq(&sprite.pixels, pixels));
+    }
 }
@@ -0,0 +1,129 @@
+//! A tiny bitmap font for drawing text to the pixel buffer.
+
+use crate::geo::Point;
+use crate::sprites::screen_height;
+
+/// Width of each glyph in pixels.
+pub(crate) const GLYPH_WIDTH: usize = 5;
+/// Height of each glyph in pixels.
+pub(crate) const GLYPH_HEIGHT: usize = 7;
+
+// Horizontal distance between the start of each glyph
+const ADVANCE: usize = GLYPH_WIDTH + 1;
+
+/// Measure the width of a line of text in pixels.
+pub(crate) fn text_width(text: &str) -> usize {
+    (text.chars().count() * ADVANCE).saturating_sub(1)
+}
+
+/// Draw a line of text to a pixel buffer that is `width` pixels wide.
+///
+/// Letters are drawn in uppercase. Characters without a glyph are drawn as a space, and anything
+/// that falls outside of the screen is clipped.
+pub(crate) fn text(screen: &mut [u8], width: usize, dest: &Point, text: &str, color: [u8; 4]) {
+    let height = screen_height(screen, width);
+
+    for (n, c) in text.chars().enumerate() {
+        let x = dest.x + n * ADVANCE;
+
+        for (y, row) in glyph(c).iter().enumerate() {
+            let y = dest.y + y;
+
+            for col in 0..GLYPH_WIDTH {
+                let x = x + col;
+
+                if row & (0b10000 >> col) != 0 && x < width && y < height {
+                    let i = x * 4 + y * width * 4;
+
+                    screen[i..i + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+/// Get the glyph for a character. Each row uses the low 5 bits, with the MSB on the left.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'D' => [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'F' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+        'G' => [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+        'H' => [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'P' => [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+        'Q' => [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04],
+        'Z' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        ':' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        _ => [0x00; GLYPH_HEIGHT],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HEIGHT, WIDTH};
+    use alloc::vec;
+
+    #[test]
+    fn test_text() {
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("PAUSED"), 35);
+
+        let white = [255, 255, 255, 255];
+        let mut screen = vec![0; WIDTH * HEIGHT * 4];
+        text(&mut screen, WIDTH, &Point::new(0, 0), "i", white);
+
+        // Lowercase letters use the uppercase glyph: a serif on the top row, and a lone stem
+        assert_eq!(pixel(&screen, 0, 0), [0; 4]);
+        assert_eq!(pixel(&screen, 1, 0), white);
+        assert_eq!(pixel(&screen, 2, 3), white);
+        assert_eq!(pixel(&screen, 1, 3), [0; 4]);
+
+        // Text is clipped at the screen edge
+        text(
+            &mut screen,
+            WIDTH,
+            &Point::new(WIDTH - 2, HEIGHT - 2),
+            "W",
+            white,
+        );
+        assert_eq!(pixel(&screen, WIDTH - 2, HEIGHT - 1), white);
+    }
+
+    fn pixel(screen: &[u8], x: usize, y: usize) -> &[u8] {
+        let i = x * 4 + y * WIDTH * 4;
+
+        &screen[i..i + 4]
+    }
+}
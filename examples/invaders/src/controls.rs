@@ -0,0 +1,339 @@
+//! Remappable player controls.
+
+use gilrs::{Button, Gamepad};
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, fs, io};
+use winit::keyboard::KeyCode;
+use winit_input_helper::WinitInputHelper;
+
+/// The action prefix for the second player's bindings in the controls config file.
+const PLAYER_TWO_PREFIX: &str = "player2.";
+
+/// Keyboard keys that can be bound in the controls config file.
+///
+/// `R` and `Escape` are missing because they are reserved for resetting and quitting the game.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::ArrowUp,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowRight,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Tab,
+    KeyCode::Backspace,
+    KeyCode::Pause,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Semicolon,
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Gamepad buttons that can be bound in the controls config file.
+const BINDABLE_BUTTONS: &[Button] = &[
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+/// Maps player actions to keyboard keys and gamepad buttons.
+#[derive(Debug)]
+pub(crate) struct ControlsConfig {
+    /// Move the tank to the left.
+    pub(crate) left: Binding,
+    /// Move the tank to the right.
+    pub(crate) right: Binding,
+    /// Move the tank up, when vertical movement is enabled.
+    pub(crate) up: Binding,
+    /// Move the tank down, when vertical movement is enabled.
+    pub(crate) down: Binding,
+    /// Fire the cannon.
+    pub(crate) fire: Binding,
+    /// Pause or resume the game.
+    pub(crate) pause: Binding,
+}
+
+/// All keyboard keys and gamepad buttons bound to a single action.
+#[derive(Debug)]
+pub(crate) struct Binding {
+    keys: Vec<KeyCode>,
+    buttons: Vec<Button>,
+}
+
+impl ControlsConfig {
+    /// Load the controls config for the given number of players from a file.
+    ///
+    /// Each line binds an action to a whitespace-separated list of inputs, e.g.
+    /// `left = key:ArrowLeft key:KeyA button:DPadLeft`. Actions prefixed with `player2.` are bound
+    /// for the second player. Actions missing from the file keep their default bindings.
+    ///
+    /// Returns an error when an input is bound for more than one of the players.
+    pub(crate) fn load(path: &Path, players: usize) -> io::Result<Vec<Self>> {
+        let mut configs = [Self::default(), Self::player_two()];
+
+        for (number, line) in fs::read_to_string(path)?.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |reason: String| {
+                let msg = format!("{}:{}: {reason}", path.display(), number + 1);
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            };
+
+            let (action, inputs) = line
+                .split_once('=')
+                .ok_or_else(|| invalid("expected `action = inputs`".to_string()))?;
+            let (config, action) = match action.trim().strip_prefix(PLAYER_TWO_PREFIX) {
+                Some(action) => (&mut configs[1], action),
+                None => (&mut configs[0], action.trim()),
+            };
+            let binding = match action {
+                "left" => &mut config.left,
+                "right" => &mut config.right,
+                "up" => &mut config.up,
+                "down" => &mut config.down,
+                "fire" => &mut config.fire,
+                "pause" => &mut config.pause,
+                action => return Err(invalid(format!("unknown action `{action}`"))),
+            };
+            *binding = inputs.parse().map_err(invalid)?;
+        }
+
+        let configs = configs.into_iter().take(players).collect::<Vec<_>>();
+        if let [one, two] = configs.as_slice() {
+            if let Some(input) = one.find_conflict(two) {
+                let msg = format!("{}: `{input}` is bound for both players", path.display());
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        }
+
+        Ok(configs)
+    }
+
+    /// Save the default controls config for both players to a file in the format expected by
+    /// [`ControlsConfig::load`].
+    pub(crate) fn save_defaults(path: &Path) -> io::Result<()> {
+        let contents = format!(
+            "# Bind each action to any number of `key:<KeyCode>` and `button:<Button>` inputs\n\
+             {}\n\
+             # The second player in co-op mode only has keyboard controls\n\
+             {}",
+            Self::default().to_lines(""),
+            Self::player_two().to_lines(PLAYER_TWO_PREFIX),
+        );
+
+        fs::write(path, contents)
+    }
+
+    /// Format the bindings as config file lines, with each action name prefixed by `prefix`.
+    fn to_lines(&self, prefix: &str) -> String {
+        self.bindings()
+            .iter()
+            .map(|(action, binding)| format!("{prefix}{action} = {binding}\n"))
+            .collect()
+    }
+
+    /// All bindings with their action names.
+    fn bindings(&self) -> [(&'static str, &Binding); 6] {
+        [
+            ("left", &self.left),
+            ("right", &self.right),
+            ("up", &self.up),
+            ("down", &self.down),
+            ("fire", &self.fire),
+            ("pause", &self.pause),
+        ]
+    }
+
+    /// Find an input that is bound in both configs, formatted as it appears in the config file.
+    fn find_conflict(&self, other: &Self) -> Option<String> {
+        let bindings = self.bindings();
+        let other_bindings = other.bindings();
+
+        bindings.iter().find_map(|(_, binding)| {
+            other_bindings.iter().find_map(|(_, other)| {
+                let key = binding.keys.iter().find(|key| other.keys.contains(key));
+                let button = binding
+                    .buttons
+                    .iter()
+                    .find(|button| other.buttons.contains(button));
+
+                key.map(|key| format!("key:{key:?}"))
+                    .or_else(|| button.map(|button| format!("button:{button:?}")))
+            })
+        })
+    }
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self {
+            left: Binding::new(&[KeyCode::ArrowLeft], &[Button::DPadLeft]),
+            right: Binding::new(&[KeyCode::ArrowRight], &[Button::DPadRight]),
+            up: Binding::new(&[KeyCode::ArrowUp], &[Button::DPadUp]),
+            down: Binding::new(&[KeyCode::ArrowDown], &[Button::DPadDown]),
+            fire: Binding::new(&[KeyCode::Space], &[Button::South]),
+            pause: Binding::new(&[KeyCode::Pause, KeyCode::KeyP], &[Button::Start]),
+        }
+    }
+}
+
+impl ControlsConfig {
+    /// Default bindings for the given number of players.
+    pub(crate) fn defaults(players: usize) -> Vec<Self> {
+        [Self::default(), Self::player_two()]
+            .into_iter()
+            .take(players)
+            .collect()
+    }
+
+    /// Default bindings for the second player in co-op mode.
+    ///
+    /// The second player only has keyboard controls; the gamepad belongs to the first player.
+    fn player_two() -> Self {
+        Self {
+            left: Binding::new(&[KeyCode::KeyA], &[]),
+            right: Binding::new(&[KeyCode::KeyD], &[]),
+            up: Binding::new(&[], &[]),
+            down: Binding::new(&[], &[]),
+            fire: Binding::new(&[KeyCode::KeyW], &[]),
+            pause: Binding::new(&[], &[]),
+        }
+    }
+}
+
+impl Binding {
+    fn new(keys: &[KeyCode], buttons: &[Button]) -> Self {
+        Self {
+            keys: keys.to_vec(),
+            buttons: buttons.to_vec(),
+        }
+    }
+
+    /// Check if any of the bound inputs are held down.
+    pub(crate) fn held(&self, input: &WinitInputHelper, gamepad: Option<&Gamepad>) -> bool {
+        self.keys.iter().any(|&key| input.key_held(key))
+            || gamepad.is_some_and(|gamepad| {
+                self.buttons
+                    .iter()
+                    .any(|&button| gamepad.is_pressed(button))
+            })
+    }
+
+    /// Check if any of the bound inputs were pressed since the last update.
+    ///
+    /// `counter` is the current [`gilrs::Gilrs::counter`] value, used to ignore gamepad buttons
+    /// that were pressed in a previous update and are still held.
+    pub(crate) fn pressed(
+        &self,
+        input: &WinitInputHelper,
+        gamepad: Option<&Gamepad>,
+        counter: u64,
+    ) -> bool {
+        self.keys.iter().any(|&key| input.key_pressed(key))
+            || gamepad.is_some_and(|gamepad| {
+                self.buttons.iter().any(|&button| {
+                    gamepad
+                        .button_data(button)
+                        .is_some_and(|button| button.is_pressed() && button.counter() == counter)
+                })
+            })
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys = self.keys.iter().map(|key| format!("key:{key:?}"));
+        let buttons = self
+            .buttons
+            .iter()
+            .map(|button| format!("button:{button:?}"));
+        let inputs = keys.chain(buttons).collect::<Vec<_>>();
+
+        write!(f, "{}", inputs.join(" "))
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut binding = Self::new(&[], &[]);
+
+        for input in s.split_whitespace() {
+            if let Some(name) = input.strip_prefix("key:") {
+                let key = BINDABLE_KEYS
+                    .iter()
+                    .find(|key| format!("{key:?}") == name)
+                    .ok_or_else(|| format!("unknown key `{name}`"))?;
+                binding.keys.push(*key);
+            } else if let Some(name) = input.strip_prefix("button:") {
+                let button = BINDABLE_BUTTONS
+                    .iter()
+                    .find(|button| format!("{button:?}") == name)
+                    .ok_or_else(|| format!("unknown button `{name}`"))?;
+                binding.buttons.push(*button);
+            } else {
+                return Err(format!("expected `key:` or `button:` prefix on `{input}`"));
+            }
+        }
+
+        Ok(binding)
+    }
+}
@@ -1,45 +1,56 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
+use crate::controls::ControlsConfig;
 use error_iter::ErrorIter as _;
 use game_loop::{game_loop, Time, TimeTrait as _};
-use gilrs::{Button, GamepadId, Gilrs};
+use gilrs::{GamepadId, Gilrs};
 use log::{debug, error};
 use pixels::{Error, Pixels, SurfaceTexture};
-use simple_invaders::{Controls, Direction, World, FPS, HEIGHT, TIME_STEP, WIDTH};
+use simple_invaders::{
+    Controls, Difficulty, Direction, SaveState, World, FPS, HEIGHT, TIME_STEP, WIDTH,
+};
+use std::path::Path;
 use std::sync::Arc;
 use std::{env, time::Duration};
 use winit::{dpi::LogicalSize, event_loop::EventLoop, keyboard::KeyCode, window::WindowBuilder};
 use winit_input_helper::WinitInputHelper;
 
+mod controls;
+
 /// Uber-struct representing the entire game.
 struct Game {
     /// Software renderer.
     pixels: Pixels<'static>,
     /// Invaders world.
     world: World,
-    /// Player controls for world updates.
-    controls: Controls,
+    /// Controls for world updates, one per player.
+    controls: Vec<Controls>,
+    /// Key and button bindings for each player.
+    controls_configs: Vec<ControlsConfig>,
     /// Event manager.
     input: WinitInputHelper,
     /// GamePad manager.
     gilrs: Gilrs,
-    /// GamePad ID for the player.
+    /// GamePad ID for the first player.
     gamepad: Option<GamepadId>,
-    /// Game pause state.
-    paused: bool,
+    /// Quick save slot.
+    save_state: Option<SaveState>,
 }
 
 impl Game {
-    fn new(pixels: Pixels<'static>, debug: bool) -> Self {
+    fn new(pixels: Pixels<'static>, controls_configs: Vec<ControlsConfig>, debug: bool) -> Self {
+        let num_players = controls_configs.len();
+
         Self {
             pixels,
-            world: World::new(generate_seed(), debug),
-            controls: Controls::default(),
+            world: World::new_multiplayer(generate_seed(), debug, num_players),
+            controls: vec![Controls::default(); num_players],
+            controls_configs,
             input: WinitInputHelper::new(),
             gilrs: Gilrs::new().unwrap(), // XXX: Don't unwrap.
             gamepad: None,
-            paused: false,
+            save_state: None,
         }
     }
 
@@ -56,43 +67,34 @@ impl Game {
             }
         }
 
-        self.controls = {
-            // Keyboard controls
-            let mut left = self.input.key_held(KeyCode::ArrowLeft);
-            let mut right = self.input.key_held(KeyCode::ArrowRight);
-            let mut fire = self.input.key_pressed(KeyCode::Space);
-            let mut pause =
-                self.input.key_pressed(KeyCode::Pause) | self.input.key_pressed(KeyCode::KeyP);
-
-            // GamePad controls
-            if let Some(id) = self.gamepad {
-                let gamepad = self.gilrs.gamepad(id);
-
-                left |= gamepad.is_pressed(Button::DPadLeft);
-                right |= gamepad.is_pressed(Button::DPadRight);
-                fire |= gamepad.button_data(Button::South).is_some_and(|button| {
-                    button.is_pressed() && button.counter() == self.gilrs.counter()
-                });
-                pause |= gamepad.button_data(Button::Start).is_some_and(|button| {
-                    button.is_pressed() && button.counter() == self.gilrs.counter()
-                });
-            }
-            self.gilrs.inc();
+        let gamepad = self.gamepad.map(|id| self.gilrs.gamepad(id));
+        let counter = self.gilrs.counter();
+        let mut pause = false;
 
-            if pause {
-                self.paused = !self.paused;
-            }
+        for (i, (controls, config)) in self
+            .controls
+            .iter_mut()
+            .zip(&self.controls_configs)
+            .enumerate()
+        {
+            // Keyboard controls for everyone, GamePad controls for the first player
+            let gamepad = gamepad.as_ref().filter(|_| i == 0);
+            let left = config.left.held(&self.input, gamepad);
+            let right = config.right.held(&self.input, gamepad);
+            let up = config.up.held(&self.input, gamepad);
+            let down = config.down.held(&self.input, gamepad);
+            let fire = config.fire.pressed(&self.input, gamepad, counter);
+            pause |= config.pause.pressed(&self.input, gamepad, counter);
 
-            let direction = if left {
-                Direction::Left
-            } else if right {
-                Direction::Right
-            } else {
-                Direction::Still
-            };
+            let direction = Direction::from_inputs_4way(left, right, up, down);
 
-            Controls { direction, fire }
-        };
+            *controls = Controls { direction, fire };
+        }
+
+        if pause {
+            self.world.set_paused(!self.world.is_paused());
+        }
+        self.gilrs.inc();
     }
 
     fn reset_game(&mut self) {
@@ -110,6 +112,31 @@ fn main() -> Result<(), Error> {
         .parse()
         .unwrap_or(false);
 
+    // Enable co-op mode with `PLAYERS=2` environment variable
+    let players = if env::var("PLAYERS").is_ok_and(|players| players == "2") {
+        2
+    } else {
+        1
+    };
+
+    // Load remapped controls with `CONTROLS=path/to/controls.cfg` environment variable
+    let controls_configs = env::var_os("CONTROLS")
+        .map(|path| load_controls_config(Path::new(&path), players))
+        .unwrap_or_else(|| ControlsConfig::defaults(players));
+
+    // Select the difficulty with `DIFFICULTY=easy` or `DIFFICULTY=hard` environment variable
+    let difficulty = match env::var("DIFFICULTY").as_deref() {
+        Ok("easy") => Difficulty::easy(),
+        Ok("hard") => Difficulty::hard(),
+        _ => Difficulty::normal(),
+    };
+
+    // Let the tanks move up and down with `VERTICAL=true` environment variable
+    let vertical = env::var("VERTICAL")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+
     let window = {
         let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
         let scaled_size = LogicalSize::new(WIDTH as f64 * 3.0, HEIGHT as f64 * 3.0);
@@ -129,7 +156,10 @@ fn main() -> Result<(), Error> {
         Pixels::new(WIDTH as u32, HEIGHT as u32, surface_texture)?
     };
 
-    let game = Game::new(pixels, debug);
+    let mut game = Game::new(pixels, controls_configs, debug);
+    game.world.set_difficulty(difficulty);
+    game.world.set_vertical_movement(vertical);
+    game.world.show_title();
 
     let res = game_loop(
         event_loop,
@@ -139,8 +169,11 @@ fn main() -> Result<(), Error> {
         0.1,
         move |g| {
             // Update the world
-            if !g.game.paused {
-                g.game.world.update(&g.game.controls);
+            g.game.world.update_multiplayer(&g.game.controls);
+
+            // TODO: Play sound effects
+            for event in g.game.world.events() {
+                debug!("Game event: {event:?}");
             }
         },
         move |g| {
@@ -175,6 +208,30 @@ fn main() -> Result<(), Error> {
                     g.game.reset_game();
                 }
 
+                // Quick save and load
+                if g.game.input.key_pressed(KeyCode::F5) {
+                    g.game.save_state = Some(g.game.world.save_state());
+                }
+                if g.game.input.key_pressed(KeyCode::F9) {
+                    if let Some(save_state) = g.game.save_state.clone() {
+                        g.game.world.load_state(save_state);
+                    }
+                }
+
+                // Toggle debug visualizations
+                if g.game.input.key_pressed(KeyCode::F1) {
+                    let debug = !g.game.world.is_debug();
+                    g.game.world.set_debug(debug);
+                }
+
+                // Track the cursor for the debug grid
+                let cursor = g
+                    .game
+                    .input
+                    .cursor()
+                    .and_then(|pos| g.game.pixels.window_pos_to_pixel(pos).ok());
+                g.game.world.set_cursor(cursor);
+
                 // Resize the window
                 if let Some(size) = g.game.input.window_resized() {
                     if let Err(err) = g.game.pixels.resize_surface(size.width, size.height) {
@@ -195,6 +252,23 @@ fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
     }
 }
 
+/// Load the controls config for each player, or create it with the default bindings when it does
+/// not exist.
+///
+/// Errors are logged and the default bindings are used instead.
+fn load_controls_config(path: &Path, players: usize) -> Vec<ControlsConfig> {
+    let result = if path.exists() {
+        ControlsConfig::load(path, players)
+    } else {
+        ControlsConfig::save_defaults(path).map(|_| ControlsConfig::defaults(players))
+    };
+
+    result.unwrap_or_else(|err| {
+        log_error("load_controls_config", err);
+        ControlsConfig::defaults(players)
+    })
+}
+
 /// Generate a pseudorandom seed for the game's PRNG.
 fn generate_seed() -> (u64, u64) {
     use byteorder::{ByteOrder, NativeEndian};
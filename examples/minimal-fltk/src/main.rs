@@ -66,7 +66,7 @@ fn main() -> Result<(), Error> {
         }
 
         // Draw the current frame
-        world.draw(pixels.frame_mut());
+        world.draw(&mut pixels);
         if let Err(err) = pixels.render() {
             log_error("pixels.render", err);
             app.quit();
@@ -113,10 +113,9 @@ impl World {
     /// Draw the `World` state to the frame buffer.
     ///
     /// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
-    fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = (i % WIDTH as usize) as i16;
-            let y = (i / WIDTH as usize) as i16;
+    fn draw(&self, pixels: &mut Pixels) {
+        for (x, y, pixel) in pixels.frame_chunks_mut() {
+            let (x, y) = (x as i16, y as i16);
             let d = {
                 let xd = x as i32 - self.circle_x as i32;
                 let yd = y as i32 - self.circle_y as i32;
@@ -130,7 +129,7 @@ impl World {
                 [0x26, 0x00, 0x33, 0xff]
             };
 
-            pixel.copy_from_slice(&rgba);
+            *pixel = rgba;
         }
     }
 }
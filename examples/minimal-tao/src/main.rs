@@ -80,7 +80,7 @@ fn main() -> Result<(), Error> {
 
             // Draw the current frame
             Event::RedrawRequested(_) => {
-                world.draw(pixels.frame_mut());
+                world.draw(&mut pixels);
                 if let Err(err) = pixels.render() {
                     log_error("pixels.render", err);
                     *control_flow = ControlFlow::Exit;
@@ -126,10 +126,9 @@ impl World {
     /// Draw the `World` state to the frame buffer.
     ///
     /// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
-    fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = (i % WIDTH as usize) as i16;
-            let y = (i / WIDTH as usize) as i16;
+    fn draw(&self, pixels: &mut Pixels) {
+        for (x, y, pixel) in pixels.frame_chunks_mut() {
+            let (x, y) = (x as i16, y as i16);
 
             let inside_the_box = x >= self.box_x
                 && x < self.box_x + BOX_SIZE
@@ -142,7 +141,7 @@ impl World {
                 [0x48, 0xb2, 0xe8, 0xff]
             };
 
-            pixel.copy_from_slice(&rgba);
+            *pixel = rgba;
         }
     }
 }
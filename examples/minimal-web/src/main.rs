@@ -3,7 +3,7 @@
 
 use error_iter::ErrorIter as _;
 use log::error;
-use pixels::{PixelsBuilder, SurfaceTexture};
+use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
 use std::rc::Rc;
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
@@ -110,14 +110,9 @@ async fn run() {
             SurfaceTexture::new(window_size.width, window_size.height, window.as_ref());
         let builder = PixelsBuilder::new(WIDTH, HEIGHT, surface_texture);
 
+        // WebGL2 has lower limits
         #[cfg(target_arch = "wasm32")]
-        let builder = {
-            // Web targets do not support the default texture format
-            let texture_format = pixels::wgpu::TextureFormat::Rgba8Unorm;
-            builder
-                .texture_format(texture_format)
-                .surface_texture_format(texture_format)
-        };
+        let builder = builder.limits_preset(pixels::LimitsPreset::DownlevelWebGL2);
 
         builder.build_async().await.expect("Pixels error")
     };
@@ -130,7 +125,7 @@ async fn run() {
                 ..
             } => {
                 // Draw the current frame
-                world.draw(pixels.frame_mut());
+                world.draw(&mut pixels);
                 if let Err(err) = pixels.render() {
                     log_error("pixels.render", err);
                     elwt.exit();
@@ -199,10 +194,9 @@ impl World {
     /// Draw the `World` state to the frame buffer.
     ///
     /// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
-    fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            let x = (i % WIDTH as usize) as i16;
-            let y = (i / WIDTH as usize) as i16;
+    fn draw(&self, pixels: &mut Pixels) {
+        for (x, y, pixel) in pixels.frame_chunks_mut() {
+            let (x, y) = (x as i16, y as i16);
 
             let inside_the_box = x >= self.box_x
                 && x < self.box_x + BOX_SIZE
@@ -215,7 +209,7 @@ impl World {
                 [0x48, 0xb2, 0xe8, 0xff]
             };
 
-            pixel.copy_from_slice(&rgba);
+            *pixel = rgba;
         }
     }
 }
@@ -22,13 +22,16 @@ struct World {
     velocity_y: i16,
 }
 
-struct Display<'win> {
-    window: Arc<Window>,
-    pixels: Pixels<'win>,
+struct Display {
+    window: Option<Arc<Window>>,
+    pixels: Option<Pixels<'static>>,
 }
 
 fn _main(event_loop: EventLoop<()>) {
-    let mut display: Option<Display> = None;
+    let mut display = Display {
+        window: None,
+        pixels: None,
+    };
 
     let mut world = World::new();
 
@@ -37,34 +40,36 @@ fn _main(event_loop: EventLoop<()>) {
         match event {
             Event::Resumed => {
                 let window = Arc::new(Window::new(elwt).unwrap());
-                let pixels = {
-                    let window_size = window.inner_size();
-                    let surface_texture = SurfaceTexture::new(
-                        window_size.width,
-                        window_size.height,
-                        Arc::clone(&window),
-                    );
-                    Pixels::new(WIDTH, HEIGHT, surface_texture).unwrap()
-                };
+                let window_size = window.inner_size();
+                let surface_texture =
+                    SurfaceTexture::new(window_size.width, window_size.height, Arc::clone(&window));
+
+                // Keep the existing pixel buffer across suspend/resume, only the surface is lost
+                match &mut display.pixels {
+                    Some(pixels) => pixels.recreate_surface(surface_texture).unwrap(),
+                    None => {
+                        display.pixels = Some(Pixels::new(WIDTH, HEIGHT, surface_texture).unwrap())
+                    }
+                }
                 window.request_redraw();
-                display = Some(Display { window, pixels });
+                display.window = Some(window);
             }
             Event::Suspended => {
-                display = None;
+                display.window = None;
             }
             Event::WindowEvent {
                 event: WindowEvent::RedrawRequested,
                 ..
             } => {
-                if let Some(display) = &mut display {
-                    world.draw(display.pixels.frame_mut());
-                    display.pixels.render().unwrap();
-                    display.window.request_redraw();
+                if let (Some(window), Some(pixels)) = (&display.window, &mut display.pixels) {
+                    world.draw(pixels.frame_mut());
+                    pixels.render().unwrap();
+                    window.request_redraw();
                 }
             }
             _ => {}
         }
-        if display.is_some() {
+        if display.window.is_some() {
             world.update();
         }
     });
@@ -0,0 +1,110 @@
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+use bouncing_box::{World, HEIGHT, WIDTH};
+use error_iter::ErrorIter as _;
+use log::error;
+use softbuffer::{Context, SoftBufferError, Surface};
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use winit::dpi::LogicalSize;
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::EventLoop;
+use winit::keyboard::KeyCode;
+use winit::window::{Window, WindowBuilder};
+use winit_input_helper::WinitInputHelper;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let event_loop = EventLoop::new()?;
+    let mut input = WinitInputHelper::new();
+    let window = {
+        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
+        let window = WindowBuilder::new()
+            .with_title("Hello softbuffer")
+            .with_inner_size(size)
+            .with_min_inner_size(size)
+            .build(&event_loop)?;
+
+        // The surface needs a handle to the window that lives as long as it does
+        Rc::new(window)
+    };
+
+    let context = Context::new(window.clone())?;
+    let mut surface = Surface::new(&context, window.clone())?;
+
+    // There is no texture to upload; the frame is scaled to the window on the CPU
+    let mut frame = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    let mut world = World::new();
+
+    let res = event_loop.run(|event, elwt| {
+        // Draw the current frame
+        if let Event::WindowEvent {
+            event: WindowEvent::RedrawRequested,
+            ..
+        } = event
+        {
+            world.draw(&mut frame);
+            if let Err(err) = present(&mut surface, &window, &frame) {
+                log_error("present", err);
+                elwt.exit();
+                return;
+            }
+        }
+
+        // Handle input events
+        if input.update(&event) {
+            // Close events
+            if input.key_pressed(KeyCode::Escape) || input.close_requested() {
+                elwt.exit();
+                return;
+            }
+
+            // Update internal state and request a redraw
+            world.update();
+            window.request_redraw();
+        }
+    });
+
+    Ok(res?)
+}
+
+/// Stretch the RGBA frame over the whole window with nearest neighbor scaling, and present it.
+///
+/// This is the work that `pixels` does on the GPU. Here, every window pixel is converted to the
+/// `0RGB` format that softbuffer expects, one at a time.
+fn present(
+    surface: &mut Surface<Rc<Window>, Rc<Window>>,
+    window: &Window,
+    frame: &[u8],
+) -> Result<(), SoftBufferError> {
+    let size = window.inner_size();
+    let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+    else {
+        // The window is minimized
+        return Ok(());
+    };
+    surface.resize(width, height)?;
+
+    let mut buffer = surface.buffer_mut()?;
+    let (width, height) = (size.width as usize, size.height as usize);
+    for (row, dst) in buffer.chunks_exact_mut(width).enumerate() {
+        let src_y = row * HEIGHT as usize / height;
+        for (col, dst) in dst.iter_mut().enumerate() {
+            let src_x = col * WIDTH as usize / width;
+            let i = (src_y * WIDTH as usize + src_x) * 4;
+            let [r, g, b] = [frame[i], frame[i + 1], frame[i + 2]].map(u32::from);
+
+            *dst = (r << 16) | (g << 8) | b;
+        }
+    }
+
+    buffer.present()
+}
+
+fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
+    error!("{method_name}() failed: {err}");
+    for source in err.sources().skip(1) {
+        error!("  Caused by: {source}");
+    }
+}
@@ -0,0 +1,176 @@
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+use error_iter::ErrorIter as _;
+use log::error;
+use pixels::{Error, Pixels, SurfaceTexture};
+use winit::dpi::LogicalSize;
+use winit::event::{Event, WindowEvent};
+use winit::event_loop::EventLoop;
+use winit::keyboard::KeyCode;
+use winit::window::WindowBuilder;
+use winit_input_helper::WinitInputHelper;
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+const BOX_SIZE: i16 = 64;
+const BRUSH_RADIUS: i16 = 2;
+
+/// Representation of the application state. In this example, a box will move across the screen
+/// and wrap around its edges, over a background that can be painted with the mouse.
+struct World {
+    box_x: i16,
+    box_y: i16,
+    velocity_x: i16,
+    velocity_y: i16,
+    painted: Vec<bool>,
+}
+
+fn main() -> Result<(), Error> {
+    env_logger::init();
+    let event_loop = EventLoop::new().unwrap();
+    let mut input = WinitInputHelper::new();
+    let window = {
+        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
+        WindowBuilder::new()
+            .with_title("Wrapping World")
+            .with_inner_size(size)
+            .with_min_inner_size(size)
+            .build(&event_loop)
+            .unwrap()
+    };
+
+    let mut pixels = {
+        let surface_texture = SurfaceTexture::new_auto(&window);
+        Pixels::new(WIDTH, HEIGHT, surface_texture)?
+    };
+    let mut world = World::new();
+
+    let res = event_loop.run(|event, elwt| {
+        // Draw the current frame
+        if let Event::WindowEvent {
+            event: WindowEvent::RedrawRequested,
+            ..
+        } = event
+        {
+            world.draw(&mut pixels);
+            if let Err(err) = pixels.render() {
+                log_error("pixels.render", err);
+                elwt.exit();
+                return;
+            }
+        }
+
+        // Handle input events
+        if input.update(&event) {
+            // Close events
+            if input.key_pressed(KeyCode::Escape) || input.close_requested() {
+                elwt.exit();
+                return;
+            }
+
+            if input.key_pressed(KeyCode::Space) {
+                world.clear();
+            }
+
+            // Convert the cursor position on the window to a pixel position. The cursor is
+            // ignored while it is in the border around the pixel buffer.
+            let cursor = input
+                .cursor()
+                .and_then(|pos| pixels.window_pos_to_pixel(pos).ok())
+                .map(|(x, y)| (x as i16, y as i16));
+            if let Some((x, y)) = cursor {
+                if input.mouse_held(0) {
+                    world.paint(x, y);
+                }
+                if input.mouse_held(1) {
+                    world.move_box(x, y);
+                }
+            }
+
+            // Resize the window
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    log_error("pixels.resize_surface", err);
+                    elwt.exit();
+                    return;
+                }
+            }
+
+            // Update internal state and request a redraw
+            world.update();
+            window.request_redraw();
+        }
+    });
+    res.map_err(|e| Error::UserDefined(Box::new(e)))
+}
+
+fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
+    error!("{method_name}() failed: {err}");
+    for source in err.sources().skip(1) {
+        error!("  Caused by: {source}");
+    }
+}
+
+impl World {
+    /// Create a new `World` instance that can draw a moving box.
+    fn new() -> Self {
+        Self {
+            box_x: 24,
+            box_y: 16,
+            velocity_x: 1,
+            velocity_y: 1,
+            painted: vec![false; (WIDTH * HEIGHT) as usize],
+        }
+    }
+
+    /// Update the `World` internal state; move the box, wrapping around the screen edges.
+    fn update(&mut self) {
+        self.box_x = (self.box_x + self.velocity_x).rem_euclid(WIDTH as i16);
+        self.box_y = (self.box_y + self.velocity_y).rem_euclid(HEIGHT as i16);
+    }
+
+    /// Center the box on a pixel position.
+    fn move_box(&mut self, x: i16, y: i16) {
+        self.box_x = (x - BOX_SIZE / 2).rem_euclid(WIDTH as i16);
+        self.box_y = (y - BOX_SIZE / 2).rem_euclid(HEIGHT as i16);
+    }
+
+    /// Paint a square brush centered on a pixel position. The brush wraps around the screen edges.
+    fn paint(&mut self, x: i16, y: i16) {
+        for dy in -BRUSH_RADIUS..=BRUSH_RADIUS {
+            for dx in -BRUSH_RADIUS..=BRUSH_RADIUS {
+                let x = (x + dx).rem_euclid(WIDTH as i16) as usize;
+                let y = (y + dy).rem_euclid(HEIGHT as i16) as usize;
+                self.painted[x + y * WIDTH as usize] = true;
+            }
+        }
+    }
+
+    /// Erase the painting.
+    fn clear(&mut self) {
+        self.painted.fill(false);
+    }
+
+    /// Draw the `World` state to the frame buffer.
+    ///
+    /// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
+    fn draw(&self, pixels: &mut Pixels) {
+        for (x, y, pixel) in pixels.frame_chunks_mut() {
+            // The distance from the box origin wraps around, so the box can span the screen edges
+            let dx = (x as i16 - self.box_x).rem_euclid(WIDTH as i16);
+            let dy = (y as i16 - self.box_y).rem_euclid(HEIGHT as i16);
+            let inside_the_box = dx < BOX_SIZE && dy < BOX_SIZE;
+
+            let rgba = if inside_the_box {
+                [0x5e, 0x48, 0xe8, 0xff]
+            } else if self.painted[(x + y * WIDTH) as usize] {
+                [0xff, 0xff, 0xff, 0xff]
+            } else {
+                [0x48, 0xb2, 0xe8, 0xff]
+            };
+
+            *pixel = rgba;
+        }
+    }
+}
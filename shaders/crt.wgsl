@@ -0,0 +1,54 @@
+// Vertex shader bindings
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(position, 0.0, 1.0);
+    return out;
+}
+
+// Fragment shader bindings
+
+@group(0) @binding(0) var r_tex_color: texture_2d<f32>;
+@group(0) @binding(1) var r_tex_sampler: sampler;
+struct Locals {
+    // The scaled pixel buffer within the surface: x, y, width, height
+    clip_rect: vec4<f32>,
+    surface_size: vec2<f32>,
+    buffer_height: f32,
+    curvature: f32,
+    scanline_intensity: f32,
+}
+@group(0) @binding(2) var<uniform> r_locals: Locals;
+
+const tau = 6.283185307179586476925286766559;
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    // Position within the clipping rectangle, from -1 to 1 on both axes
+    let clip_coord = (position.xy - r_locals.clip_rect.xy) / r_locals.clip_rect.zw;
+    let centered = fma(clip_coord, vec2<f32>(2.0), vec2<f32>(-1.0));
+
+    // Barrel distortion pushes the edges outward, more so near the corners
+    let distorted = centered * (1.0 + r_locals.curvature * dot(centered, centered));
+    let uv = fma(distorted, vec2<f32>(0.5), vec2<f32>(0.5));
+
+    let tex_coord = (r_locals.clip_rect.xy + uv * r_locals.clip_rect.zw) / r_locals.surface_size;
+    let sampled_color = textureSample(r_tex_color, r_tex_sampler, tex_coord);
+
+    // Darken the edges between rows of the pixel buffer
+    let row = fract(uv.y * r_locals.buffer_height);
+    let scanline = 1.0 - r_locals.scanline_intensity * fma(cos(row * tau), 0.5, 0.5);
+
+    // The corners are pushed off of the screen
+    let inside = all(abs(distorted) <= vec2<f32>(1.0));
+    let black = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+
+    return select(black, vec4<f32>(sampled_color.rgb * scanline, sampled_color.a), inside);
+}
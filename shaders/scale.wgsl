@@ -7,6 +7,8 @@ struct VertexOutput {
 
 struct Locals {
     transform: mat4x4<f32>,
+    // The source rectangle within the texture: x, y, width, height in texture coordinates
+    source_rect: vec4<f32>,
 }
 @group(0) @binding(2) var<uniform> r_locals: Locals;
 
@@ -15,7 +17,8 @@ fn vs_main(
     @location(0) position: vec2<f32>,
 ) -> VertexOutput {
     var out: VertexOutput;
-    out.tex_coord = fma(position, vec2<f32>(0.5, -0.5), vec2<f32>(0.5, 0.5));
+    let uv = fma(position, vec2<f32>(0.5, -0.5), vec2<f32>(0.5, 0.5));
+    out.tex_coord = fma(uv, r_locals.source_rect.zw, r_locals.source_rect.xy);
     out.position = r_locals.transform * vec4<f32>(position, 0.0, 1.0);
     return out;
 }
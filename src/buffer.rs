@@ -0,0 +1,174 @@
+//! CPU operations on the bytes of the pixel buffer.
+//!
+//! These are the parts of the [`Pixels`](crate::Pixels) methods that do not need a GPU.
+
+/// Copy the rectangular region `src_rect` of a frame to the top-left corner `dst` in the same
+/// frame. Rows of the frame are `stride` bytes long.
+///
+/// The regions may overlap in any direction. Both regions must fit within the frame.
+pub(crate) fn copy_within(
+    frame: &mut [u8],
+    stride: usize,
+    bytes_per_pixel: usize,
+    src_rect: (u32, u32, u32, u32),
+    dst: (u32, u32),
+) {
+    let (src_x, src_y, width, height) = src_rect;
+    let (dst_x, dst_y) = dst;
+    let len = width as usize * bytes_per_pixel;
+    let offset = |x: u32, y: u32| y as usize * stride + x as usize * bytes_per_pixel;
+
+    // Moving down copies the bottom row first, so overlapping rows are read before they are
+    // overwritten. Overlap within a row is handled by `copy_within`.
+    let copy_row = |row: u32| {
+        let src = offset(src_x, src_y + row);
+        frame.copy_within(src..src + len, offset(dst_x, dst_y + row));
+    };
+    if dst_y > src_y {
+        (0..height).rev().for_each(copy_row);
+    } else {
+        (0..height).for_each(copy_row);
+    }
+}
+
+/// Copy the overlapping top-left region of the `src` frame into the `dst` frame. Rows of the
+/// frames are `src_stride` and `dst_stride` bytes long.
+///
+/// The rest of `dst` is left untouched, and the rest of `src` is cropped.
+pub(crate) fn copy_overlap(dst: &mut [u8], dst_stride: usize, src: &[u8], src_stride: usize) {
+    let len = src_stride.min(dst_stride);
+    let rows = dst
+        .chunks_exact_mut(dst_stride)
+        .zip(src.chunks_exact(src_stride));
+    for (dst, src) in rows {
+        dst[..len].copy_from_slice(&src[..len]);
+    }
+}
+
+/// View a frame with 4 bytes per pixel as one `u32` per pixel, in native byte order.
+///
+/// # Panics
+///
+/// Panics if the frame is not aligned to 4 bytes in memory.
+pub(crate) fn words_mut(frame: &mut [u8]) -> &mut [u32] {
+    bytemuck::try_cast_slice_mut(frame).expect("The pixel buffer must be aligned to 4 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 8;
+    const STRIDE: usize = WIDTH as usize * 4;
+
+    /// A frame with distinct values in every channel.
+    fn pattern() -> Vec<u8> {
+        (0..WIDTH * HEIGHT)
+            .flat_map(|i| {
+                let (x, y) = (i % WIDTH, i / WIDTH);
+                [x as u8 * 32, y as u8 * 32, (x ^ y) as u8 * 32, 0xff]
+            })
+            .collect()
+    }
+
+    fn row(frame: &[u8], y: u32) -> &[u8] {
+        &frame[y as usize * STRIDE..][..STRIDE]
+    }
+
+    #[test]
+    fn test_copy_overlap() {
+        const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+
+        // An 8x8 frame with a pixel near the bottom-right corner
+        let mut src = vec![0; STRIDE * HEIGHT as usize];
+        let i = (6 + 5 * WIDTH as usize) * 4;
+        src[i..i + 4].copy_from_slice(&RED);
+        let pixel = |frame: &[u8], width: usize, x: usize, y: usize| {
+            let i = (x + y * width) * 4;
+            <[u8; 4]>::try_from(&frame[i..i + 4]).unwrap()
+        };
+
+        // Growing keeps the pixel in place, and the newly exposed area is zeroed
+        let mut dst = vec![0; 16 * 11 * 4];
+        copy_overlap(&mut dst, 16 * 4, &src, STRIDE);
+        assert_eq!(pixel(&dst, 16, 6, 5), RED);
+        assert_eq!(dst.iter().filter(|&&byte| byte != 0).count(), 2);
+
+        // Shrinking keeps the pixel while it is inside the new size
+        let mut dst = vec![0; 7 * 6 * 4];
+        copy_overlap(&mut dst, 7 * 4, &src, STRIDE);
+        assert_eq!(pixel(&dst, 7, 6, 5), RED);
+
+        // And crops it otherwise
+        let mut dst = vec![0; 6 * 6 * 4];
+        copy_overlap(&mut dst, 6 * 4, &src, STRIDE);
+        assert!(dst.iter().all(|&byte| byte == 0));
+        let mut dst = vec![0; 8 * 5 * 4];
+        copy_overlap(&mut dst, STRIDE, &src, STRIDE);
+        assert!(dst.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_words_mut() {
+        // Allocate as `u32` to guarantee alignment
+        let mut storage = vec![0_u32; (WIDTH * HEIGHT) as usize];
+        let frame: &mut [u8] = bytemuck::cast_slice_mut(&mut storage);
+
+        // Packed pixels are in native byte order
+        let rgba = [0x12, 0x34, 0x56, 0x78];
+        let words = words_mut(frame);
+        assert_eq!(words.len(), (WIDTH * HEIGHT) as usize);
+        words[1] = u32::from_ne_bytes(rgba);
+        assert_eq!(frame[4..8], rgba);
+    }
+
+    #[test]
+    fn test_copy_within_rows() {
+        // Scrolling up by two rows, with the regions overlapping
+        let mut frame = pattern();
+        let mut expected = frame.clone();
+        expected.copy_within(2 * STRIDE.., 0);
+        copy_within(&mut frame, STRIDE, 4, (0, 2, WIDTH, HEIGHT - 2), (0, 0));
+        assert_eq!(frame, expected);
+
+        // Scrolling down by three rows
+        let before = pattern();
+        let mut frame = before.clone();
+        copy_within(&mut frame, STRIDE, 4, (0, 0, WIDTH, HEIGHT - 3), (0, 3));
+        for y in 0..3 {
+            assert_eq!(row(&frame, y), row(&before, y), "row {y} is untouched");
+        }
+        for y in 3..HEIGHT {
+            assert_eq!(row(&frame, y), row(&before, y - 3), "row {y} is shifted");
+        }
+    }
+
+    #[test]
+    fn test_copy_within_region() {
+        // Scrolling a partial region diagonally leaves the rest of the rows untouched
+        let original = pattern();
+        let mut frame = original.clone();
+        copy_within(&mut frame, STRIDE, 4, (1, 1, 4, 4), (2, 3));
+        for (i, (pixel, old)) in frame
+            .chunks_exact(4)
+            .zip(original.chunks_exact(4))
+            .enumerate()
+        {
+            let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+            let expected = if (2..6).contains(&x) && (3..7).contains(&y) {
+                let (sx, sy) = (x - 1, y - 2);
+                &original[((sx + sy * WIDTH) * 4) as usize..][..4]
+            } else {
+                old
+            };
+            assert_eq!(pixel, expected, "pixel ({x}, {y})");
+        }
+
+        // Moving a region left within the same rows
+        let mut frame = pattern();
+        copy_within(&mut frame, STRIDE, 4, (2, 0, 6, 1), (0, 0));
+        assert_eq!(frame[..6 * 4], original[2 * 4..8 * 4]);
+        assert_eq!(frame[6 * 4..STRIDE], original[6 * 4..STRIDE]);
+    }
+}
@@ -1,21 +1,46 @@
-use crate::renderers::{ScalingMatrix, ScalingRenderer};
-use crate::{Error, Pixels, PixelsContext, SurfaceSize, SurfaceTexture, TextureError};
+#[cfg(feature = "cpu-fallback")]
+use crate::cpu::{self, CpuPixels, FallbackPixels};
+use crate::mipmaps::{mip_level_count, MipmapGenerator};
+use crate::renderers::{ScalingMatrix, ScalingMode, ScalingRenderer};
+use crate::{
+    Error, Pixels, PixelsContext, RenderTarget, SurfaceSize, SurfaceTexture, TextureError,
+};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A builder to help create customized pixel buffers.
 pub struct PixelsBuilder<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> {
     request_adapter_options: Option<wgpu::RequestAdapterOptions<'req, 'win>>,
     device_descriptor: Option<wgpu::DeviceDescriptor<'dev>>,
+    device_timeout: Option<Duration>,
+    limits_preset: LimitsPreset,
+    device_features: wgpu::Features,
     backend: wgpu::Backends,
+    instance: Option<Arc<wgpu::Instance>>,
+    device: Option<SharedDevice>,
     width: u32,
     height: u32,
-    _pixel_aspect_ratio: f64,
+    pixel_aspect_ratio: f64,
+    scaling_mode: ScalingMode,
     present_mode: wgpu::PresentMode,
-    surface_texture: SurfaceTexture<W>,
+    window: Option<W>,
+    surface_size: SurfaceSize,
     texture_format: wgpu::TextureFormat,
+    texture_usage: wgpu::TextureUsages,
+    view_formats: Vec<wgpu::TextureFormat>,
+    generate_mipmaps: bool,
+    label: String,
+    premultiply_alpha: bool,
     render_texture_format: Option<wgpu::TextureFormat>,
     surface_texture_format: Option<wgpu::TextureFormat>,
     clear_color: wgpu::Color,
+    initial_buffer_color: Option<Vec<u8>>,
     blend_state: wgpu::BlendState,
+    color_write_mask: wgpu::ColorWrites,
+    auto_reconfigure: bool,
 }
 
 impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, 'win, W> {
@@ -47,20 +72,37 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         assert!(width > 0);
         assert!(height > 0);
 
+        let SurfaceTexture { window, size } = surface_texture;
+
         Self {
             request_adapter_options: None,
             device_descriptor: None,
+            device_timeout: None,
+            limits_preset: LimitsPreset::default(),
+            device_features: wgpu::Features::empty(),
             backend: wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all),
+            instance: None,
+            device: None,
             width,
             height,
-            _pixel_aspect_ratio: 1.0,
+            pixel_aspect_ratio: 1.0,
+            scaling_mode: ScalingMode::default(),
             present_mode: wgpu::PresentMode::AutoVsync,
-            surface_texture,
-            texture_format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            window: Some(window),
+            surface_size: size,
+            texture_format: DEFAULT_TEXTURE_FORMAT,
+            texture_usage: wgpu::TextureUsages::empty(),
+            view_formats: Vec::new(),
+            generate_mipmaps: false,
+            label: "pixels".to_owned(),
+            premultiply_alpha: false,
             render_texture_format: None,
             surface_texture_format: None,
             clear_color: wgpu::Color::BLACK,
+            initial_buffer_color: None,
             blend_state: wgpu::BlendState::ALPHA_BLENDING,
+            color_write_mask: wgpu::ColorWrites::ALL,
+            auto_reconfigure: true,
         }
     }
 
@@ -73,12 +115,114 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         self
     }
 
+    /// Set the power preference for requesting a [`wgpu::Adapter`].
+    ///
+    /// This is a shortcut for [`request_adapter_options`](PixelsBuilder::request_adapter_options)
+    /// that only changes the power preference, and keeps any other options that were already set.
+    /// The surface is still used to find a compatible adapter.
+    ///
+    /// The `WGPU_POWER_PREF` environment variable is ignored when a power preference is set here.
+    /// `WGPU_ADAPTER_NAME` still takes precedence, see the
+    /// [crate docs](crate#environment-variables).
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::PowerPreference;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .power_preference(PowerPreference::HighPerformance)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.request_adapter_options
+            .get_or_insert_with(wgpu::RequestAdapterOptions::default)
+            .power_preference = power_preference;
+        self
+    }
+
     /// Add options for requesting a [`wgpu::Device`].
     pub fn device_descriptor(mut self, device_descriptor: wgpu::DeviceDescriptor<'dev>) -> Self {
         self.device_descriptor = Some(device_descriptor);
         self
     }
 
+    /// Give up on requesting a [`wgpu::Device`] after the given duration.
+    ///
+    /// Building the pixel buffer returns [`Error::Timeout`] instead of blocking forever when the
+    /// driver does not respond, e.g. on unreliable CI machines. There is no timeout by default.
+    ///
+    /// The device is requested on a separate thread, which is left running when the request
+    /// times out. The timeout is ignored on Web targets.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .device_timeout(Duration::from_secs(10))
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn device_timeout(mut self, timeout: Duration) -> Self {
+        self.device_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the [`wgpu::Limits`] required from the [`wgpu::Device`].
+    ///
+    /// The default requires all of the limits supported by the adapter, which can fail on some
+    /// platforms, e.g. browsers with WebGL2. The texture size limits always match the adapter, so
+    /// the surface can be as large as the adapter supports.
+    ///
+    /// This is ignored when a [`device_descriptor`](PixelsBuilder::device_descriptor) is given.
+    ///
+    /// ```no_run
+    /// use pixels::LimitsPreset;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .limits_preset(LimitsPreset::DownlevelWebGL2)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn limits_preset(mut self, limits_preset: LimitsPreset) -> Self {
+        self.limits_preset = limits_preset;
+        self
+    }
+
+    /// Require optional [`wgpu::Features`] from the [`wgpu::Device`], e.g. for custom render
+    /// passes with push constants.
+    ///
+    /// The features are added to the [`device_descriptor`](PixelsBuilder::device_descriptor), if
+    /// one is given. See [`Pixels::features`] for the features of the device that was created.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::Features;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .device_features(Features::PUSH_CONSTANTS)
+    ///     .build()?;
+    /// assert!(pixels.features().contains(Features::PUSH_CONSTANTS));
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// Building the pixel buffer fails with [`Error::UnsupportedFeatures`] when the adapter (or the
+    /// [shared device](PixelsBuilder::with_device)) does not support all of the features.
+    pub fn device_features(mut self, device_features: wgpu::Features) -> Self {
+        self.device_features = device_features;
+        self
+    }
+
     /// Set which backends wgpu will attempt to use.
     ///
     /// The default enables all backends, including the backends with "best effort" support in wgpu.
@@ -87,25 +231,112 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         self
     }
 
+    /// Use an existing [`wgpu::Instance`] instead of creating a new one.
+    ///
+    /// The window surface is created with this instance, and the adapter is requested from it. The
+    /// [`wgpu_backend`](PixelsBuilder::wgpu_backend) setting is ignored. An instance given to
+    /// [`PixelsBuilder::with_device`] takes precedence.
+    ///
+    /// The instance of an existing pixel buffer is available from [`Pixels::instance`].
+    pub fn with_instance(mut self, instance: Arc<wgpu::Instance>) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Use an existing [`wgpu::Instance`], [`wgpu::Adapter`], [`wgpu::Device`], and
+    /// [`wgpu::Queue`] instead of requesting new ones.
+    ///
+    /// This allows multiple pixel buffers (e.g. one per window) to share a single device, so they
+    /// can share GPU resources without the memory overhead of a device per pixel buffer. The
+    /// [`request_adapter_options`](PixelsBuilder::request_adapter_options) and
+    /// [`device_descriptor`](PixelsBuilder::device_descriptor) settings are ignored. The instance
+    /// must be the one that created the adapter.
+    ///
+    /// These resources are reference counted, so they live until the last pixel buffer (or any
+    /// other owner) holding them is dropped. They are available from an existing pixel buffer with
+    /// [`Pixels::instance`], [`Pixels::adapter`], and [`Pixels::context`].
+    ///
+    /// ```no_run
+    /// # use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
+    /// # let window = pixels_mocks::Window;
+    /// # let other_window = pixels_mocks::Window;
+    /// let pixels = Pixels::new(320, 240, SurfaceTexture::new(320, 240, &window))?;
+    ///
+    /// // Create a second pixel buffer on the same device
+    /// let context = pixels.context();
+    /// let surface_texture = SurfaceTexture::new(320, 240, &other_window);
+    /// let other_pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .with_device(
+    ///         pixels.instance().clone(),
+    ///         pixels.adapter().clone(),
+    ///         context.device.clone(),
+    ///         context.queue.clone(),
+    ///     )
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn with_device(
+        mut self,
+        instance: Arc<wgpu::Instance>,
+        adapter: Arc<wgpu::Adapter>,
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+    ) -> Self {
+        self.device = Some(SharedDevice {
+            instance,
+            adapter,
+            device,
+            queue,
+        });
+        self
+    }
+
     /// Set the pixel aspect ratio to simulate non-square pixels.
     ///
-    /// This setting enables a render pass that horizontally scales the pixel buffer by the given
-    /// factor.
+    /// The pixel buffer is scaled horizontally by the given factor before it is fit onto the
+    /// surface. E.g. set this to `8.0 / 7.0` for an 8:7 pixel aspect ratio, the shape of pixels on
+    /// a 256x224 console output to a 4:3 TV.
     ///
-    /// E.g. set this to `8.0 / 7.0` for an 8:7 pixel aspect ratio.
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(900, 700, &window);
+    /// let mut pixels = PixelsBuilder::new(256, 224, surface_texture)
+    ///     .pixel_aspect_ratio(8.0 / 7.0)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
     ///
     /// # Panics
     ///
     /// The aspect ratio must be > 0.
-    ///
-    /// # Warning
-    ///
-    /// This documentation is hidden because support for pixel aspect ratio is incomplete.
-    #[doc(hidden)]
     pub fn pixel_aspect_ratio(mut self, pixel_aspect_ratio: f64) -> Self {
         assert!(pixel_aspect_ratio > 0.0);
 
-        self._pixel_aspect_ratio = pixel_aspect_ratio;
+        self.pixel_aspect_ratio = pixel_aspect_ratio;
+        self
+    }
+
+    /// Set how the pixel buffer is scaled to fit the surface.
+    ///
+    /// The default is [`ScalingMode::Integer`].
+    ///
+    /// ```no_run
+    /// use pixels::{PixelsBuilder, ScaleKind, ScalingMode};
+    ///
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// // Stretch horizontally, but keep integer scaling on the vertical axis
+    /// let mut pixels = PixelsBuilder::new(256, 240, surface_texture)
+    ///     .scaling_mode(ScalingMode::AxisIndependent {
+    ///         x: ScaleKind::Stretch,
+    ///         y: ScaleKind::Integer,
+    ///     })
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn scaling_mode(mut self, scaling_mode: ScalingMode) -> Self {
+        self.scaling_mode = scaling_mode;
         self
     }
 
@@ -128,7 +359,8 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     /// Set the `wgpu` present mode.
     ///
     /// This differs from [`PixelsBuilder::enable_vsync`] by allowing the present mode to be set to
-    /// any value.
+    /// any value. When the surface does not support the present mode, `AutoVsync` is used instead.
+    /// See [`Pixels::present_mode`] for the present mode in use.
     pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
         self.present_mode = present_mode;
         self
@@ -140,6 +372,10 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     /// sRGB color space. This is typically what you want when you are working with color values
     /// from popular image editing tools or web apps.
     ///
+    /// Web targets cannot present sRGB formats, so the default there is `Rgba8Unorm` instead. The
+    /// bytes are passed through to the canvas unchanged, which treats them as sRGB, so colors look
+    /// the same on both.
+    ///
     /// This is the pixel format of the texture that most applications will interact with directly.
     /// The format influences the structure of byte data that is returned by [`Pixels::frame`].
     pub fn texture_format(mut self, texture_format: wgpu::TextureFormat) -> Self {
@@ -147,6 +383,147 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         self
     }
 
+    /// Set additional usages for the texture.
+    ///
+    /// The texture that the pixel buffer is copied to always has the `TEXTURE_BINDING`,
+    /// `COPY_DST`, and `COPY_SRC` usages. Any usages set here are added to those. This is useful
+    /// for custom shaders that need to do more with the texture than sample it, such as writing to
+    /// it from a compute shader with `STORAGE_BINDING`.
+    ///
+    /// Keep in mind that the [`texture_format`](PixelsBuilder::texture_format) must support the
+    /// requested usages. For instance, sRGB formats cannot be used as storage textures.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::{TextureFormat, TextureUsages};
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .texture_format(TextureFormat::Rgba8Unorm)
+    ///     .texture_usage(TextureUsages::STORAGE_BINDING)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn texture_usage(mut self, texture_usage: wgpu::TextureUsages) -> Self {
+        self.texture_usage = texture_usage;
+        self
+    }
+
+    /// Set additional formats that views of the texture and surface can be created with.
+    ///
+    /// A view can reinterpret a texture in a compatible format without a copy, which only differs
+    /// in the sRGB suffix. E.g. the default `Rgba8UnormSrgb` texture can be sampled as linear
+    /// `Rgba8Unorm` in a custom shader. Each format is added to the
+    /// [`texture_format`](PixelsBuilder::texture_format) and the
+    /// [`surface_texture_format`](PixelsBuilder::surface_texture_format) when it is compatible, so
+    /// one list covers both. The textures can always be viewed in their own format.
+    ///
+    /// Reinterpreting the texture requires [`wgpu::DownlevelFlags::VIEW_FORMATS`], and
+    /// reinterpreting the surface requires [`wgpu::DownlevelFlags::SURFACE_VIEW_FORMATS`]. These
+    /// are missing on some backends, like OpenGL.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::{TextureFormat, TextureViewDescriptor};
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .view_formats(&[TextureFormat::Rgba8Unorm])
+    ///     .build()?;
+    ///
+    /// // View the sRGB pixel buffer texture as linear
+    /// let linear_view = pixels.context().texture.create_view(&TextureViewDescriptor {
+    ///     format: Some(TextureFormat::Rgba8Unorm),
+    ///     ..TextureViewDescriptor::default()
+    /// });
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// Building the pixel buffer returns [`Error::UnsupportedViewFormat`] when a format is not
+    /// compatible with either texture, or when the adapter does not support it.
+    pub fn view_formats(mut self, view_formats: &[wgpu::TextureFormat]) -> Self {
+        self.view_formats = view_formats.to_vec();
+        self
+    }
+
+    /// Generate mipmaps for the texture each time the pixel buffer is rendered.
+    ///
+    /// The [`ScalingRenderer`] always magnifies the pixel buffer with nearest neighbor sampling,
+    /// which is what pixel art needs. But when the pixel buffer is drawn smaller than its size,
+    /// e.g. zoomed out with [`Pixels::set_scaling_matrix`](crate::Pixels::set_scaling_matrix),
+    /// skipping texels causes shimmering and moiré patterns. With mipmaps, minification is
+    /// filtered from a chain of downsampled textures instead.
+    ///
+    /// Each mip level is rendered from the level above it after the pixel buffer is uploaded, or a
+    /// region is written with [`Pixels::write_region`](crate::Pixels::write_region), so this costs
+    /// a few render passes per frame. It is disabled by default.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = PixelsBuilder::new(1920, 1080, surface_texture)
+    ///     .generate_mipmaps(true)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// The [`texture_format`](PixelsBuilder::texture_format) must be renderable. Building the
+    /// pixel buffer returns [`TextureError::UnsupportedFormat`] otherwise.
+    pub fn generate_mipmaps(mut self, enable: bool) -> Self {
+        self.generate_mipmaps = enable;
+        self
+    }
+
+    /// Set the prefix for the labels of the GPU resources created for the pixel buffer.
+    ///
+    /// Labels show up in validation errors and in GPU capture tools like RenderDoc and Xcode. The
+    /// texture and the resources of the [`ScalingRenderer`] are named like
+    /// `"{label}_source_texture"` and `"{label}_scaling_renderer_pipeline"`. The default prefix
+    /// is `"pixels"`. Use a distinct label for each pixel buffer to tell them apart in a capture.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .label("minimap")
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_owned();
+        self
+    }
+
+    /// Premultiply the color of each pixel by its alpha when the pixel buffer is uploaded.
+    ///
+    /// The pixel buffer has straight (non-premultiplied) alpha. Compositors that blend the window
+    /// with [`wgpu::CompositeAlphaMode::PreMultiplied`] show fringes around translucent pixels
+    /// unless the colors are premultiplied. Enable this for transparent windows on those
+    /// platforms. The pixel buffer itself is not modified; the premultiplied copy is made in a
+    /// separate buffer on each upload. It is disabled by default.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .premultiply_alpha(true)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// Only the 8-bit RGBA and BGRA [texture formats](PixelsBuilder::texture_format) are
+    /// premultiplied, other formats are uploaded unchanged. The sRGB formats are premultiplied in
+    /// linear space, see [`color::premultiply_alpha_srgb`](crate::color::premultiply_alpha_srgb).
+    pub fn premultiply_alpha(mut self, enable: bool) -> Self {
+        self.premultiply_alpha = enable;
+        self
+    }
+
     /// Set the render texture format.
     ///
     /// This falls back on [`Pixels::surface_texture_format`] if not set.
@@ -166,6 +543,8 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     ///
     /// The default value is chosen automatically by the surface (if it can) with a fallback to
     /// `Bgra8UnormSrgb` (which is 4 unsigned bytes in `BGRA` order using the sRGB color space).
+    /// On Web targets, a linear format is chosen instead, with a fallback to `Rgba8Unorm`. See
+    /// [`PixelsBuilder::texture_format`].
     /// Setting this format correctly depends on the hardware/platform the pixel buffer is rendered
     /// to. The chosen format can be retrieved later with [`Pixels::render_texture_format`].
     ///
@@ -203,10 +582,40 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         self
     }
 
+    /// Set the color channels written by the scaling renderer.
+    ///
+    /// The default writes all channels. E.g. leave out the alpha channel to preserve the alpha of
+    /// the render target when compositing an overlay.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::ColorWrites;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .color_write_mask(ColorWrites::COLOR)
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `color_write_mask` is empty, which would draw nothing at all.
+    pub fn color_write_mask(mut self, color_write_mask: wgpu::ColorWrites) -> Self {
+        assert!(
+            !color_write_mask.is_empty(),
+            "The color write mask is empty"
+        );
+        self.color_write_mask = color_write_mask;
+        self
+    }
+
     /// Set the clear color.
     ///
     /// Allows customization of the background color and the border drawn for non-integer scale
-    /// values.
+    /// values. The pixel buffer covers everything inside the border, so the clear color is not
+    /// visible there. Use [`PixelsBuilder::initial_buffer_color`] to fill the pixel buffer.
     ///
     /// The default value is pure black.
     ///
@@ -232,6 +641,61 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
         self
     }
 
+    /// Fill the pixel buffer with a color when it is created.
+    ///
+    /// `color` is the value of one pixel in the texture format, e.g. 4 bytes in RGBA order for
+    /// the default [`wgpu::TextureFormat::Rgba8UnormSrgb`]. Only the initial pixel buffer is
+    /// filled; [`Pixels::resize_buffer`] zeroes the new pixel buffer as usual.
+    ///
+    /// The pixel buffer is zeroed by default, which is transparent black for most formats.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .initial_buffer_color(&[0xff, 0x00, 0xff, 0xff])
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Building the pixel buffer panics when the length of `color` is not the size of one pixel
+    /// in the texture format.
+    pub fn initial_buffer_color(mut self, color: &[u8]) -> Self {
+        self.initial_buffer_color = Some(color.to_vec());
+        self
+    }
+
+    /// Enable or disable reconfiguring the surface when acquiring a surface texture fails.
+    ///
+    /// This is enabled by default: when [`wgpu::Surface::get_current_texture`] fails while
+    /// rendering, the surface is reconfigured and the texture is requested once more. This
+    /// recovers from outdated or lost surfaces, but it can also mask a persistent error.
+    ///
+    /// When disabled, [`Pixels::render`] returns the first [`Error::Surface`] as-is, and handling
+    /// it is up to the caller. E.g. with [`Pixels::resize_surface`] or
+    /// [`Pixels::recreate_surface`].
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture)
+    ///     .auto_reconfigure(false)
+    ///     .build()?;
+    ///
+    /// if let Err(pixels::Error::Surface(err)) = pixels.render() {
+    ///     // Handle the surface error
+    /// }
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn auto_reconfigure(mut self, auto_reconfigure: bool) -> Self {
+        self.auto_reconfigure = auto_reconfigure;
+        self
+    }
+
     /// Create a pixel buffer from the options builder.
     ///
     /// This is the private implementation shared by [`PixelsBuilder::build`] and
@@ -241,107 +705,208 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     ///
     /// Returns an error when a [`wgpu::Adapter`] cannot be found.
     async fn build_impl(self) -> Result<Pixels<'win>, Error> {
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: self.backend,
-            ..Default::default()
+        // A shared device must be used with the instance that created it
+        let shared_instance = self.device.as_ref().map(|shared| shared.instance.clone());
+        let instance = shared_instance.or(self.instance).unwrap_or_else(|| {
+            Arc::new(wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends: self.backend,
+                ..Default::default()
+            }))
         });
 
-        // TODO: Use `options.pixel_aspect_ratio` to stretch the scaled texture
-        let surface = instance.create_surface(self.surface_texture.window)?;
-        let compatible_surface = Some(&surface);
-        let request_adapter_options = &self.request_adapter_options;
-        let adapter = match wgpu::util::initialize_adapter_from_env(&instance, compatible_surface) {
-            Some(adapter) => Some(adapter),
+        let surface = self
+            .window
+            .map(|window| instance.create_surface(window))
+            .transpose()?;
+        let compatible_surface = surface.as_ref();
+        let (adapter, device, queue) = match self.device {
+            Some(SharedDevice {
+                adapter,
+                device,
+                queue,
+                ..
+            }) => {
+                // The shared adapter may not be able to present to this window
+                if let Some(surface) = compatible_surface {
+                    if !adapter.is_surface_supported(surface) {
+                        return Err(Error::AdapterNotFound);
+                    }
+                }
+
+                let missing_features = self.device_features - device.features();
+                if !missing_features.is_empty() {
+                    return Err(Error::UnsupportedFeatures(missing_features));
+                }
+
+                (adapter, device, queue)
+            }
             None => {
-                instance
-                    .request_adapter(&request_adapter_options.as_ref().map_or_else(
-                        || wgpu::RequestAdapterOptions {
-                            compatible_surface,
-                            force_fallback_adapter: false,
-                            power_preference:
-                                wgpu::util::power_preference_from_env().unwrap_or_default(),
-                        },
-                        |rao| wgpu::RequestAdapterOptions {
-                            compatible_surface: rao.compatible_surface.or(compatible_surface),
-                            force_fallback_adapter: rao.force_fallback_adapter,
-                            power_preference: rao.power_preference,
-                        },
-                    ))
-                    .await
+                let (adapter, device, queue) = request_device(
+                    &instance,
+                    compatible_surface,
+                    self.request_adapter_options.as_ref(),
+                    self.device_descriptor,
+                    self.limits_preset,
+                    self.device_features,
+                    self.device_timeout,
+                )
+                .await?;
+
+                (adapter, Arc::new(device), Arc::new(queue))
             }
         };
 
-        let adapter = adapter.ok_or(Error::AdapterNotFound)?;
-
-        let device_descriptor = self
-            .device_descriptor
-            .unwrap_or_else(|| wgpu::DeviceDescriptor {
-                required_limits: adapter.limits(),
-                ..wgpu::DeviceDescriptor::default()
-            });
-
-        let (device, queue) = adapter.request_device(&device_descriptor, None).await?;
-
-        let surface_capabilities = surface.get_capabilities(&adapter);
-        let present_mode = self.present_mode;
-        let surface_texture_format = self.surface_texture_format.unwrap_or_else(|| {
-            *surface_capabilities
-                .formats
-                .iter()
-                .find(|format| format.is_srgb())
-                .unwrap_or(&wgpu::TextureFormat::Bgra8UnormSrgb)
-        });
+        let surface_capabilities = surface
+            .as_ref()
+            .map(|surface| surface.get_capabilities(&adapter));
+        let present_mode = match &surface_capabilities {
+            Some(surface_capabilities) => {
+                supported_present_mode(&surface_capabilities.present_modes, self.present_mode)
+            }
+            None => self.present_mode,
+        };
+        let surface_texture_format = self
+            .surface_texture_format
+            .unwrap_or_else(|| default_surface_texture_format(surface_capabilities.as_ref()));
         let render_texture_format = self.render_texture_format.unwrap_or(surface_texture_format);
 
+        // Every view format must apply to at least one of the textures, and the adapter must
+        // support reinterpreting each texture that it applies to
+        let downlevel_flags = adapter.get_downlevel_capabilities().flags;
+        let surface_flag = if surface.is_some() {
+            wgpu::DownlevelFlags::SURFACE_VIEW_FORMATS
+        } else {
+            wgpu::DownlevelFlags::VIEW_FORMATS
+        };
+        for &view_format in &self.view_formats {
+            let texture_compatible =
+                !compatible_view_formats(self.texture_format, &[view_format]).is_empty();
+            let surface_compatible =
+                !compatible_view_formats(surface_texture_format, &[view_format]).is_empty();
+            if !(texture_compatible || surface_compatible)
+                || (texture_compatible
+                    && !downlevel_flags.contains(wgpu::DownlevelFlags::VIEW_FORMATS))
+                || (surface_compatible && !downlevel_flags.contains(surface_flag))
+            {
+                return Err(Error::UnsupportedViewFormat(view_format));
+            }
+        }
+
+        // Mipmaps are rendered into the texture
+        if self.generate_mipmaps
+            && !self
+                .texture_format
+                .guaranteed_format_features(device.features())
+                .allowed_usages
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        {
+            return Err(TextureError::UnsupportedFormat(self.texture_format).into());
+        }
+
         // Create the backing texture
-        let surface_size = self.surface_texture.size;
+        let surface_size = self.surface_size;
         let clear_color = self.clear_color;
         let blend_state = self.blend_state;
-        let (scaling_matrix_inverse, texture_extent, texture, scaling_renderer, pixels_buffer_size) =
-            create_backing_texture(
-                &device,
-                // Backing texture values
-                self.width,
-                self.height,
-                self.texture_format,
-                // Render texture values
-                &surface_size,
-                render_texture_format,
-                // Clear color and blending values
-                clear_color,
-                blend_state,
-            )?;
+        let color_write_mask = self.color_write_mask;
+        let (
+            scaling_matrix_inverse,
+            texture_extent,
+            texture,
+            texture_view,
+            scaling_renderer,
+            pixels_buffer_size,
+        ) = create_backing_texture(
+            &device,
+            // Backing texture values
+            self.width,
+            self.height,
+            self.texture_format,
+            self.texture_usage,
+            &self.view_formats,
+            self.generate_mipmaps,
+            &self.label,
+            // Render texture values
+            &surface_size,
+            render_texture_format,
+            // Clear color and blending values
+            clear_color,
+            blend_state,
+            color_write_mask,
+        )?;
 
         // Create the pixel buffer
         let mut pixels = Vec::with_capacity(pixels_buffer_size);
         pixels.resize_with(pixels_buffer_size, Default::default);
+        if let Some(color) = &self.initial_buffer_color {
+            fill_buffer(&mut pixels, color, self.texture_format);
+        }
+
+        let alpha_mode = surface_capabilities
+            .as_ref()
+            .map_or(wgpu::CompositeAlphaMode::Auto, |caps| caps.alpha_modes[0]);
 
-        let alpha_mode = surface_capabilities.alpha_modes[0];
+        // Offscreen pixel buffers render to a texture instead of a surface
+        let render_target = match surface {
+            Some(surface) => RenderTarget::Surface(surface),
+            None => RenderTarget::Offscreen(create_render_target(
+                &device,
+                &surface_size,
+                surface_texture_format,
+                &self.view_formats,
+            )),
+        };
+
+        let mipmap_generator = self
+            .generate_mipmaps
+            .then(|| MipmapGenerator::new(&device, self.texture_format));
 
         // Instantiate the Pixels struct
         let context = PixelsContext {
             device,
             queue,
-            surface,
+            render_target,
             texture,
+            texture_view,
             texture_extent,
             texture_format: self.texture_format,
             texture_format_size: texture_format_size(self.texture_format),
             scaling_renderer,
         };
 
-        let pixels = Pixels {
+        let mut pixels = Pixels {
             context,
             adapter,
+            instance,
+            pending_frame: Default::default(),
+            render_passes: Default::default(),
+            frame_callback: Default::default(),
+            #[cfg(feature = "render-timing")]
+            last_render_time: Default::default(),
             surface_size,
             present_mode,
             render_texture_format,
             surface_texture_format,
             blend_state,
+            color_write_mask,
+            texture_usage: self.texture_usage,
+            view_formats: self.view_formats,
+            mipmap_generator,
+            label: self.label,
+            premultiply_alpha: self.premultiply_alpha,
+            premultiply_scratch: Default::default(),
             pixels,
             scaling_matrix_inverse,
             alpha_mode,
+            surface_capabilities,
+            mirrors: Vec::new(),
+            auto_reconfigure: self.auto_reconfigure,
         };
+        if self.scaling_mode != ScalingMode::default() {
+            pixels.set_scaling_mode(self.scaling_mode);
+        }
+        if self.pixel_aspect_ratio != 1.0 {
+            pixels.set_pixel_aspect_ratio(self.pixel_aspect_ratio);
+        }
         pixels.reconfigure_surface();
 
         Ok(pixels)
@@ -354,7 +919,8 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     ///
     /// # Errors
     ///
-    /// Returns an error when a [`wgpu::Adapter`] or [`wgpu::Device`] cannot be found.
+    /// Returns an error when a [`wgpu::Adapter`] or [`wgpu::Device`] cannot be found, or when
+    /// requesting the device takes longer than [`PixelsBuilder::device_timeout`].
     #[cfg(not(target_arch = "wasm32"))]
     pub fn build(self) -> Result<Pixels<'win>, Error> {
         pollster::block_on(self.build_impl())
@@ -387,25 +953,358 @@ impl<'req, 'dev, 'win, W: wgpu::WindowHandle + 'win> PixelsBuilder<'req, 'dev, '
     }
 }
 
+#[cfg(all(feature = "cpu-fallback", not(target_arch = "wasm32")))]
+impl<'req, 'dev, 'win, W: wgpu::WindowHandle + Clone + 'win> PixelsBuilder<'req, 'dev, 'win, W> {
+    /// Create a pixel buffer from the options builder, falling back to the CPU when no
+    /// [`wgpu::Adapter`] can be found.
+    ///
+    /// The CPU fallback presents the pixel buffer with [`softbuffer`], and only supports the
+    /// options that make sense without a GPU: the buffer and surface sizes, the clear color, and
+    /// 8-bit RGBA or BGRA texture formats. See [`CpuPixels`].
+    ///
+    /// The window is cloned so it can be given to the fallback after the GPU has been tried. Use a
+    /// reference or an `Arc` to the window.
+    ///
+    /// This method blocks the current thread, making it unusable on Web targets.
+    ///
+    /// ```no_run
+    /// use pixels::cpu::FallbackPixels;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = PixelsBuilder::new(320, 240, surface_texture).build_with_cpu_fallback()?;
+    /// if let FallbackPixels::Cpu(_) = &pixels {
+    ///     println!("No GPU found, rendering on the CPU");
+    /// }
+    ///
+    /// pixels.frame_mut().fill(0xff);
+    /// pixels.render()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from [`PixelsBuilder::build`] other than [`Error::AdapterNotFound`]. When
+    /// no adapter is found, the adapter error is returned for offscreen pixel buffers and texture
+    /// formats that the CPU fallback does not support. Otherwise, returns an error when the
+    /// [`softbuffer`] surface cannot be created.
+    pub fn build_with_cpu_fallback(self) -> Result<FallbackPixels<'win, W>, Error> {
+        let window = self.window.clone();
+        let (width, height) = (self.width, self.height);
+        let surface_size = (self.surface_size.width, self.surface_size.height);
+        let texture_format = self.texture_format;
+        let clear_color = self.clear_color;
+        let initial_buffer_color = self.initial_buffer_color.clone();
+
+        let err = match self.build() {
+            Ok(pixels) => return Ok(FallbackPixels::Gpu(pixels)),
+            Err(err @ Error::AdapterNotFound) => err,
+            Err(err) => return Err(err),
+        };
+
+        // Offscreen pixel buffers have no window to present to, and other formats need a GPU
+        let Some(window) = window.filter(|_| cpu::is_supported_format(texture_format)) else {
+            return Err(err);
+        };
+        let mut pixels = CpuPixels::new(
+            window,
+            width,
+            height,
+            surface_size,
+            texture_format,
+            clear_color,
+        )?;
+        if let Some(color) = &initial_buffer_color {
+            fill_buffer(pixels.frame_mut(), color, texture_format);
+        }
+
+        Ok(FallbackPixels::Cpu(pixels))
+    }
+}
+
+impl<'req, 'dev, 'win> PixelsBuilder<'req, 'dev, 'win, Offscreen> {
+    /// Create a builder for an offscreen pixel buffer, which is not attached to any window.
+    ///
+    /// Offscreen pixel buffers render to a texture of size `surface_width` x `surface_height`
+    /// instead of a window surface. Everything else works the same, including custom shaders with
+    /// [`Pixels::render_with`] and resizing with [`Pixels::resize_surface`]. This is useful for
+    /// tests and tools that run without a display, and in combination with
+    /// [`Pixels::read_frame`].
+    ///
+    /// The surface texture format defaults to `Rgba8UnormSrgb`, since there is no surface to
+    /// choose one.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::RequestAdapterOptions;
+    ///
+    /// # use pixels::PixelsBuilder;
+    /// // Use a software rasterizer when there is no GPU, e.g. on CI
+    /// let mut pixels = PixelsBuilder::new_offscreen(320, 240, 640, 480)
+    ///     .request_adapter_options(RequestAdapterOptions {
+    ///         force_fallback_adapter: true,
+    ///         ..RequestAdapterOptions::default()
+    ///     })
+    ///     .build()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when any of the sizes are 0.
+    pub fn new_offscreen(width: u32, height: u32, surface_width: u32, surface_height: u32) -> Self {
+        let surface_texture = SurfaceTexture::new(surface_width, surface_height, Offscreen);
+
+        Self {
+            window: None,
+            ..Self::new(width, height, surface_texture)
+        }
+    }
+}
+
+/// Request an adapter and device from the instance.
+///
+/// The environment variables documented in the crate root take precedence over the options.
+async fn request_device(
+    instance: &wgpu::Instance,
+    compatible_surface: Option<&wgpu::Surface<'_>>,
+    request_adapter_options: Option<&wgpu::RequestAdapterOptions<'_, '_>>,
+    device_descriptor: Option<wgpu::DeviceDescriptor<'_>>,
+    limits_preset: LimitsPreset,
+    device_features: wgpu::Features,
+    device_timeout: Option<Duration>,
+) -> Result<(Arc<wgpu::Adapter>, wgpu::Device, wgpu::Queue), Error> {
+    let adapter = match wgpu::util::initialize_adapter_from_env(instance, compatible_surface) {
+        Some(adapter) => Some(adapter),
+        None => {
+            instance
+                .request_adapter(&request_adapter_options.map_or_else(
+                    || wgpu::RequestAdapterOptions {
+                        compatible_surface,
+                        force_fallback_adapter: false,
+                        power_preference:
+                            wgpu::util::power_preference_from_env().unwrap_or_default(),
+                    },
+                    |rao| wgpu::RequestAdapterOptions {
+                        compatible_surface: rao.compatible_surface.or(compatible_surface),
+                        force_fallback_adapter: rao.force_fallback_adapter,
+                        power_preference: rao.power_preference,
+                    },
+                ))
+                .await
+        }
+    };
+
+    let adapter = Arc::new(adapter.ok_or(Error::AdapterNotFound)?);
+
+    let missing_features = device_features - adapter.features();
+    if !missing_features.is_empty() {
+        return Err(Error::UnsupportedFeatures(missing_features));
+    }
+
+    let mut device_descriptor = device_descriptor.unwrap_or_else(|| wgpu::DeviceDescriptor {
+        required_limits: limits_preset.limits(&adapter),
+        ..wgpu::DeviceDescriptor::default()
+    });
+    device_descriptor.required_features |= device_features;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(timeout) = device_timeout {
+        let (device, queue) = request_device_timeout(&adapter, &device_descriptor, timeout)?;
+
+        return Ok((adapter, device, queue));
+    }
+
+    // The timeout is ignored on Web targets
+    #[cfg(target_arch = "wasm32")]
+    let _ = device_timeout;
+
+    let (device, queue) = adapter.request_device(&device_descriptor, None).await?;
+
+    Ok((adapter, device, queue))
+}
+
+/// Request a device on another thread, giving up after `timeout`.
+///
+/// See [`PixelsBuilder::device_timeout`].
+#[cfg(not(target_arch = "wasm32"))]
+fn request_device_timeout(
+    adapter: &Arc<wgpu::Adapter>,
+    device_descriptor: &wgpu::DeviceDescriptor<'_>,
+    timeout: Duration,
+) -> Result<(wgpu::Device, wgpu::Queue), Error> {
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+
+    let adapter = Arc::clone(adapter);
+    let device_descriptor = device_descriptor.map_label(|label| label.map(str::to_owned));
+    let (sender, receiver) = channel();
+
+    std::thread::spawn(move || {
+        let device_descriptor = wgpu::DeviceDescriptor {
+            label: device_descriptor.label.as_deref(),
+            required_features: device_descriptor.required_features,
+            required_limits: device_descriptor.required_limits,
+        };
+        let result = pollster::block_on(adapter.request_device(&device_descriptor, None));
+
+        // Nobody is listening when the request has timed out
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Ok(result?),
+        Err(RecvTimeoutError::Timeout) => Err(Error::Timeout),
+        Err(RecvTimeoutError::Disconnected) => panic!("The device request thread panicked"),
+    }
+}
+
+/// The default texture format, see [`PixelsBuilder::texture_format`].
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// The default texture format on Web targets, which cannot present sRGB formats.
+#[cfg(target_arch = "wasm32")]
+const DEFAULT_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Choose the surface texture format when it is not set with
+/// [`PixelsBuilder::surface_texture_format`].
+///
+/// This is the first sRGB format supported by the surface, or the first linear format on Web
+/// targets. Offscreen pixel buffers have no surface, and use the default texture format.
+fn default_surface_texture_format(
+    capabilities: Option<&wgpu::SurfaceCapabilities>,
+) -> wgpu::TextureFormat {
+    let Some(capabilities) = capabilities else {
+        return DEFAULT_TEXTURE_FORMAT;
+    };
+
+    let (srgb, fallback) = if cfg!(target_arch = "wasm32") {
+        (false, wgpu::TextureFormat::Rgba8Unorm)
+    } else {
+        (true, wgpu::TextureFormat::Bgra8UnormSrgb)
+    };
+
+    capabilities
+        .formats
+        .iter()
+        .copied()
+        .find(|format| format.is_srgb() == srgb)
+        .unwrap_or(fallback)
+}
+
+/// Fall back to `AutoVsync` when the present mode is not in the supported `present_modes`.
+///
+/// Configuring a surface with an unsupported present mode panics. The automatic modes are always
+/// supported, because `wgpu` picks a supported mode for them.
+pub(crate) fn supported_present_mode(
+    present_modes: &[wgpu::PresentMode],
+    present_mode: wgpu::PresentMode,
+) -> wgpu::PresentMode {
+    match present_mode {
+        wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync => present_mode,
+        _ if present_modes.contains(&present_mode) => present_mode,
+        _ => wgpu::PresentMode::AutoVsync,
+    }
+}
+
+/// A preset for the limits required from the device, see [`PixelsBuilder::limits_preset`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LimitsPreset {
+    /// The [`wgpu::Limits::default`] limits, supported by most modern native backends.
+    Default,
+    /// The [`wgpu::Limits::downlevel_defaults`] limits, for older native backends.
+    Downlevel,
+    /// The [`wgpu::Limits::downlevel_webgl2_defaults`] limits, for browsers with WebGL2.
+    DownlevelWebGL2,
+    /// All of the limits supported by the adapter. This is the default.
+    #[default]
+    Adapter,
+}
+
+impl LimitsPreset {
+    /// Get the limits for this preset, with texture size limits matching the `adapter`.
+    fn limits(self, adapter: &wgpu::Adapter) -> wgpu::Limits {
+        let limits = match self {
+            Self::Default => wgpu::Limits::default(),
+            Self::Downlevel => wgpu::Limits::downlevel_defaults(),
+            Self::DownlevelWebGL2 => wgpu::Limits::downlevel_webgl2_defaults(),
+            Self::Adapter => return adapter.limits(),
+        };
+
+        limits.using_resolution(adapter.limits())
+    }
+}
+
+/// A device shared with another pixel buffer, see [`PixelsBuilder::with_device`].
+struct SharedDevice {
+    instance: Arc<wgpu::Instance>,
+    adapter: Arc<wgpu::Adapter>,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+}
+
+/// The window type for offscreen pixel buffers, see [`PixelsBuilder::new_offscreen`].
+///
+/// There is no window, so it has no handles to offer.
+#[derive(Debug)]
+pub struct Offscreen;
+
+impl HasWindowHandle for Offscreen {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+impl HasDisplayHandle for Offscreen {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Err(HandleError::NotSupported)
+    }
+}
+
+/// Fill a pixel buffer with copies of one pixel.
+///
+/// # Panics
+///
+/// Panics when the length of `color` is not the size of one pixel in `texture_format`.
+fn fill_buffer(buffer: &mut [u8], color: &[u8], texture_format: wgpu::TextureFormat) {
+    assert_eq!(
+        color.len() as f32,
+        texture_format_size(texture_format),
+        "The initial buffer color must be one pixel in the texture format"
+    );
+
+    for pixel in buffer.chunks_exact_mut(color.len()) {
+        pixel.copy_from_slice(color);
+    }
+}
+
 /// Compare the given size to the limits defined by `device`.
 ///
 /// # Errors
 ///
-/// - [`TextureError::TextureWidth`] when `width` is 0 or greater than GPU texture limits.
-/// - [`TextureError::TextureHeight`] when `height` is 0 or greater than GPU texture limits.
+/// - [`TextureError::TextureWidth`] when `width` is 0.
+/// - [`TextureError::TextureHeight`] when `height` is 0.
+/// - [`TextureError::TooLarge`] when `width` or `height` is greater than GPU texture limits.
 pub fn check_texture_size(
     device: &wgpu::Device,
     width: u32,
     height: u32,
 ) -> Result<(), TextureError> {
-    let limits = device.limits();
-    if width == 0 || width > limits.max_texture_dimension_2d {
+    if width == 0 {
         return Err(TextureError::TextureWidth(width));
     }
-    if height == 0 || height > limits.max_texture_dimension_2d {
+    if height == 0 {
         return Err(TextureError::TextureHeight(height));
     }
 
+    let max = device.limits().max_texture_dimension_2d;
+    if width > max || height > max {
+        return Err(TextureError::TooLarge {
+            requested: width.max(height),
+            max,
+        });
+    }
+
     Ok(())
 }
 
@@ -415,15 +1314,21 @@ pub(crate) fn create_backing_texture(
     width: u32,
     height: u32,
     backing_texture_format: wgpu::TextureFormat,
+    backing_texture_usage: wgpu::TextureUsages,
+    view_formats: &[wgpu::TextureFormat],
+    generate_mipmaps: bool,
+    label: &str,
     surface_size: &SurfaceSize,
     render_texture_format: wgpu::TextureFormat,
     clear_color: wgpu::Color,
     blend_state: wgpu::BlendState,
+    color_write_mask: wgpu::ColorWrites,
 ) -> Result<
     (
         ultraviolet::Mat4,
         wgpu::Extent3d,
         wgpu::Texture,
+        wgpu::TextureView,
         ScalingRenderer,
         usize,
     ),
@@ -434,6 +1339,8 @@ pub(crate) fn create_backing_texture(
     let scaling_matrix_inverse = ScalingMatrix::new(
         (width as f32, height as f32),
         (surface_size.width as f32, surface_size.height as f32),
+        ScalingMode::default(),
+        1.0,
     )
     .transform
     .inversed();
@@ -444,26 +1351,43 @@ pub(crate) fn create_backing_texture(
         depth_or_array_layers: 1,
     };
 
+    // Mip levels are rendered from the level above them
+    let (mip_level_count, mipmap_usage) = if generate_mipmaps {
+        (
+            mip_level_count(width, height),
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        )
+    } else {
+        (1, wgpu::TextureUsages::empty())
+    };
+
     let texture = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("pixels_source_texture"),
+        label: Some(&format!("{label}_source_texture")),
         size: texture_extent,
-        mip_level_count: 1,
+        mip_level_count,
         sample_count: 1,
         dimension: wgpu::TextureDimension::D2,
         format: backing_texture_format,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[],
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC
+            | mipmap_usage
+            | backing_texture_usage,
+        view_formats: &compatible_view_formats(backing_texture_format, view_formats),
     });
     let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
     let scaling_renderer = ScalingRenderer::new(
         device,
+        label,
         &texture_view,
         &texture_extent,
+        mip_level_count,
         surface_size,
         render_texture_format,
         clear_color,
         blend_state,
+        color_write_mask,
     );
 
     let texture_format_size = texture_format_size(backing_texture_format);
@@ -473,14 +1397,70 @@ pub(crate) fn create_backing_texture(
         scaling_matrix_inverse,
         texture_extent,
         texture,
+        texture_view,
         scaling_renderer,
         pixels_buffer_size,
     ))
 }
 
+/// Create the texture that stands in for the window surface of an offscreen pixel buffer.
+pub(crate) fn create_render_target(
+    device: &wgpu::Device,
+    surface_size: &SurfaceSize,
+    surface_texture_format: wgpu::TextureFormat,
+    view_formats: &[wgpu::TextureFormat],
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("pixels_render_target"),
+        size: wgpu::Extent3d {
+            width: surface_size.width,
+            height: surface_size.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: surface_texture_format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &compatible_view_formats(surface_texture_format, view_formats),
+    })
+}
+
+/// Select the view formats that a texture in `format` can be reinterpreted as.
+///
+/// Formats are compatible when they only differ in the sRGB suffix. The format itself is left out,
+/// since it can always be used.
+pub(crate) fn compatible_view_formats(
+    format: wgpu::TextureFormat,
+    view_formats: &[wgpu::TextureFormat],
+) -> Vec<wgpu::TextureFormat> {
+    view_formats
+        .iter()
+        .copied()
+        .filter(|&view_format| {
+            view_format != format && view_format.remove_srgb_suffix() == format.remove_srgb_suffix()
+        })
+        .collect()
+}
+
+/// Get the number of bytes per pixel for a texture format.
+///
+/// Compressed formats may have less than one byte per pixel. Some sizes are estimates, see
+/// [`PixelsContext::texture_format_size`](crate::PixelsContext::texture_format_size).
+///
+/// ```
+/// use pixels::{texture_format_size, wgpu::TextureFormat};
+///
+/// assert_eq!(texture_format_size(TextureFormat::Rgba8UnormSrgb), 4.0);
+/// assert_eq!(texture_format_size(TextureFormat::Bc1RgbaUnorm), 0.5);
+///
+/// // Size a buffer for a 320x240 texture
+/// let size = (320 * 240) as f32 * texture_format_size(TextureFormat::Rgba16Float);
+/// assert_eq!(size as usize, 614_400);
+/// ```
 #[rustfmt::skip]
 #[inline]
-const fn texture_format_size(texture_format: wgpu::TextureFormat) -> f32 {
+pub const fn texture_format_size(texture_format: wgpu::TextureFormat) -> f32 {
     use wgpu::{AstcBlock::*, TextureFormat::*};
 
     // TODO: Use constant arithmetic when supported.
@@ -631,3 +1611,106 @@ const fn texture_format_size(texture_format: wgpu::TextureFormat) -> f32 {
         NV12 => 1.5, // (8.0 + 16.0 / 2.0 / 2.0) / 8.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::{PowerPreference, PresentMode};
+
+    #[test]
+    fn test_default_surface_texture_format() {
+        use wgpu::TextureFormat;
+
+        // The first sRGB format is preferred
+        let capabilities = wgpu::SurfaceCapabilities {
+            formats: vec![
+                TextureFormat::Bgra8Unorm,
+                TextureFormat::Rgba8UnormSrgb,
+                TextureFormat::Bgra8UnormSrgb,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            default_surface_texture_format(Some(&capabilities)),
+            TextureFormat::Rgba8UnormSrgb
+        );
+
+        let capabilities = wgpu::SurfaceCapabilities {
+            formats: vec![TextureFormat::Rgba16Float],
+            ..Default::default()
+        };
+        assert_eq!(
+            default_surface_texture_format(Some(&capabilities)),
+            TextureFormat::Bgra8UnormSrgb
+        );
+        assert_eq!(default_surface_texture_format(None), DEFAULT_TEXTURE_FORMAT);
+    }
+
+    #[test]
+    fn test_supported_present_mode() {
+        let present_modes = [PresentMode::Fifo, PresentMode::Immediate];
+
+        // Supported and automatic modes are kept
+        for present_mode in [
+            PresentMode::Fifo,
+            PresentMode::Immediate,
+            PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync,
+        ] {
+            assert_eq!(
+                supported_present_mode(&present_modes, present_mode),
+                present_mode
+            );
+        }
+
+        // Unsupported modes fall back to Vsync
+        for present_mode in [PresentMode::Mailbox, PresentMode::FifoRelaxed] {
+            assert_eq!(
+                supported_present_mode(&present_modes, present_mode),
+                PresentMode::AutoVsync
+            );
+        }
+    }
+
+    #[test]
+    fn test_power_preference() {
+        let builder = PixelsBuilder::new_offscreen(8, 8, 8, 8)
+            .power_preference(PowerPreference::HighPerformance);
+        let options = builder.request_adapter_options.as_ref().unwrap();
+        assert_eq!(options.power_preference, PowerPreference::HighPerformance);
+        assert!(!options.force_fallback_adapter);
+
+        // Other adapter options are kept
+        let builder = builder
+            .request_adapter_options(wgpu::RequestAdapterOptions {
+                force_fallback_adapter: true,
+                ..wgpu::RequestAdapterOptions::default()
+            })
+            .power_preference(PowerPreference::LowPower);
+        let options = builder.request_adapter_options.as_ref().unwrap();
+        assert_eq!(options.power_preference, PowerPreference::LowPower);
+        assert!(options.force_fallback_adapter);
+    }
+
+    #[test]
+    fn test_label() {
+        let builder = PixelsBuilder::new_offscreen(8, 8, 8, 8)
+            .request_adapter_options(wgpu::RequestAdapterOptions {
+                force_fallback_adapter: true,
+                ..wgpu::RequestAdapterOptions::default()
+            })
+            .label("minimap");
+        let pixels = match builder.build() {
+            Ok(pixels) => pixels,
+            // Skipped without an adapter, like the snapshot tests
+            Err(Error::AdapterNotFound) if std::env::var_os("PIXELS_REQUIRE_ADAPTER").is_none() => {
+                return
+            }
+            Err(err) => panic!("Unable to create offscreen pixel buffer: {err}"),
+        };
+
+        // The prefix reaches the texture and the scaling renderer resources
+        assert_eq!(pixels.label, "minimap");
+        assert_eq!(pixels.context().scaling_renderer.label, "minimap");
+    }
+}
@@ -0,0 +1,347 @@
+//! Color space helpers for drawing into the pixel buffer on the CPU.
+//!
+//! The default texture format is [`wgpu::TextureFormat::Rgba8UnormSrgb`], which means the bytes in
+//! [`Pixels::frame_mut`](crate::Pixels::frame_mut) are sRGB encoded. Blending two sRGB colors by
+//! mixing their bytes directly produces results that are too dark, because the sRGB transfer
+//! function is not linear. [`blend_srgb`] decodes both colors to linear light, blends them, and
+//! encodes the result back to sRGB.
+//!
+//! Use these helpers when compositing colors _inside_ the pixel buffer; e.g. drawing translucent
+//! sprites or particles into the frame. The GPU blend state set by
+//! [`PixelsBuilder::blend_state`](crate::PixelsBuilder::blend_state) is unrelated; it only
+//! controls how the whole pixel buffer is blended onto the surface, and the GPU always blends in
+//! linear space.
+//!
+//! When the pixel buffer uses a linear format like [`wgpu::TextureFormat::Rgba8Unorm`], naive
+//! per-channel blending is already correct and these helpers should not be used.
+//!
+//! # Channel order
+//!
+//! The bytes in the pixel buffer are in the channel order of the
+//! [texture format](crate::PixelsBuilder::texture_format), which is not necessarily RGBA. With a
+//! BGRA format like [`wgpu::TextureFormat::Bgra8UnormSrgb`], writing RGBA colors swaps red and
+//! blue on screen. Use [`ChannelOrder`] (see
+//! [`Pixels::format_channel_order`](crate::Pixels::format_channel_order)) to convert colors, or
+//! [`swap_rb`] to convert a whole frame.
+
+/// The order of the color channels in each pixel of a texture format.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChannelOrder {
+    /// Red, green, blue, alpha. This is the order of the default texture format.
+    Rgba,
+    /// Blue, green, red, alpha.
+    Bgra,
+}
+
+impl ChannelOrder {
+    /// Get the channel order of a texture format.
+    ///
+    /// Only the BGRA formats are [`ChannelOrder::Bgra`]. Everything else is
+    /// [`ChannelOrder::Rgba`], including formats with fewer than four channels (their channels
+    /// are still in RGBA order, there are just fewer of them).
+    pub fn from_format(format: wgpu::TextureFormat) -> Self {
+        match format {
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => Self::Bgra,
+            _ => Self::Rgba,
+        }
+    }
+
+    /// Convert an RGBA color to this channel order.
+    ///
+    /// ```
+    /// use pixels::color::ChannelOrder;
+    ///
+    /// let red = [0xff, 0x00, 0x00, 0xff];
+    /// assert_eq!(ChannelOrder::Rgba.from_rgba(red), red);
+    /// assert_eq!(ChannelOrder::Bgra.from_rgba(red), [0x00, 0x00, 0xff, 0xff]);
+    /// ```
+    pub fn from_rgba(self, rgba: [u8; 4]) -> [u8; 4] {
+        match self {
+            Self::Rgba => rgba,
+            Self::Bgra => [rgba[2], rgba[1], rgba[0], rgba[3]],
+        }
+    }
+
+    /// Convert a color in this channel order to RGBA.
+    pub fn to_rgba(self, color: [u8; 4]) -> [u8; 4] {
+        // Swapping red and blue is its own inverse
+        self.from_rgba(color)
+    }
+}
+
+/// Swap the red and blue channels of every 4-byte pixel in `frame`, converting between RGBA and
+/// BGRA.
+///
+/// Any trailing bytes that do not make up a whole pixel are left untouched.
+pub fn swap_rb(frame: &mut [u8]) {
+    for pixel in frame.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+/// Multiply the color channels of every 4-byte pixel in `frame` by its alpha channel.
+///
+/// The alpha channel must be last, like in RGBA and BGRA. This is for linear formats like
+/// [`wgpu::TextureFormat::Rgba8Unorm`], use [`premultiply_alpha_srgb`] for sRGB formats. Any
+/// trailing bytes that do not make up a whole pixel are left untouched.
+///
+/// ```
+/// use pixels::color::premultiply_alpha;
+///
+/// let mut frame = [0xff, 0x80, 0x00, 0x80];
+/// premultiply_alpha(&mut frame);
+/// assert_eq!(frame, [0x80, 0x40, 0x00, 0x80]);
+/// ```
+pub fn premultiply_alpha(frame: &mut [u8]) {
+    for pixel in frame.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+}
+
+/// Multiply the color channels of every 4-byte pixel in `frame` by its alpha channel, where the
+/// color channels are sRGB encoded.
+///
+/// This is like [`premultiply_alpha`] for sRGB formats like
+/// [`wgpu::TextureFormat::Rgba8UnormSrgb`]. The colors are multiplied in linear space, so they
+/// decode to the premultiplied linear colors that the GPU expects.
+pub fn premultiply_alpha_srgb(frame: &mut [u8]) {
+    for pixel in frame.chunks_exact_mut(4) {
+        let alpha = pixel[3];
+        if alpha == 0xff {
+            continue;
+        }
+
+        let alpha = alpha as f32 / 255.0;
+        for channel in &mut pixel[..3] {
+            *channel = linear_to_srgb(srgb_to_linear(*channel) * alpha);
+        }
+    }
+}
+
+/// Blend `src` over `dst`, where both are sRGB encoded RGBA colors with non-premultiplied alpha.
+///
+/// The color channels are blended in linear space, and the alpha channels use the standard "over"
+/// operator.
+///
+/// ```
+/// use pixels::color::blend_srgb;
+///
+/// // Half-transparent white over opaque black is a mid-gray in linear light
+/// let gray = blend_srgb([0x00, 0x00, 0x00, 0xff], [0xff, 0xff, 0xff, 0x80]);
+/// assert_eq!(gray, [0xbc, 0xbc, 0xbc, 0xff]);
+/// ```
+pub fn blend_srgb(dst: [u8; 4], src: [u8; 4]) -> [u8; 4] {
+    let src_alpha = src[3] as f32 / 255.0;
+    let dst_alpha = dst[3] as f32 / 255.0 * (1.0 - src_alpha);
+    let alpha = src_alpha + dst_alpha;
+    if alpha <= 0.0 {
+        return [0; 4];
+    }
+
+    let mut output = [0; 4];
+    for (out, (&dst, &src)) in output.iter_mut().zip(dst.iter().zip(&src)).take(3) {
+        let linear = srgb_to_linear(src) * src_alpha + srgb_to_linear(dst) * dst_alpha;
+        *out = linear_to_srgb(linear / alpha);
+    }
+    output[3] = (alpha * 255.0).round() as u8;
+
+    output
+}
+
+/// Decode an sRGB color channel to linear light in the range `0.0..=1.0`.
+pub fn srgb_to_linear(channel: u8) -> f32 {
+    SRGB_TO_LINEAR[channel as usize]
+}
+
+/// Encode a color channel in linear light to sRGB.
+///
+/// The input is clamped to the range `0.0..=1.0`, and `NaN` is treated as `0.0`. The result is the
+/// sRGB value nearest to `linear`, so it round-trips exactly with [`srgb_to_linear`].
+pub fn linear_to_srgb(linear: f32) -> u8 {
+    let linear = if linear.is_nan() {
+        0.0
+    } else {
+        linear.clamp(0.0, 1.0)
+    };
+
+    // Index of the first table entry that is not less than `linear`
+    let i = SRGB_TO_LINEAR.partition_point(|&x| x < linear);
+    if i == 0 {
+        return 0;
+    }
+
+    let lower = SRGB_TO_LINEAR[i - 1];
+    let upper = SRGB_TO_LINEAR[i];
+    if linear - lower < upper - linear {
+        (i - 1) as u8
+    } else {
+        i as u8
+    }
+}
+
+/// Precomputed sRGB EOTF for each 8-bit channel value.
+#[rustfmt::skip]
+const SRGB_TO_LINEAR: [f32; 256] = [
+    0.0, 0.000303527, 0.000607054, 0.000910581,
+    0.001214108, 0.001517635, 0.001821162, 0.0021246888,
+    0.002428216, 0.0027317428, 0.00303527, 0.0033465358,
+    0.0036765074, 0.004024717, 0.004391442, 0.0047769533,
+    0.0051815165, 0.0056053917, 0.006048833, 0.0065120906,
+    0.00699541, 0.007499032, 0.008023193, 0.008568126,
+    0.009134059, 0.009721218, 0.010329823, 0.010960094,
+    0.011612245, 0.012286488, 0.0129830325, 0.013702083,
+    0.014443844, 0.015208514, 0.015996294, 0.016807375,
+    0.017641954, 0.01850022, 0.019382361, 0.020288562,
+    0.02121901, 0.022173885, 0.023153367, 0.024157632,
+    0.02518686, 0.026241222, 0.027320892, 0.02842604,
+    0.029556835, 0.030713445, 0.031896032, 0.033104766,
+    0.034339808, 0.035601314, 0.03688945, 0.038204372,
+    0.039546236, 0.0409152, 0.04231141, 0.04373503,
+    0.045186203, 0.046665087, 0.048171826, 0.049706567,
+    0.051269457, 0.052860647, 0.054480277, 0.05612849,
+    0.05780543, 0.059511237, 0.061246052, 0.063010015,
+    0.064803265, 0.06662594, 0.06847817, 0.070360094,
+    0.07227185, 0.07421357, 0.07618538, 0.07818742,
+    0.08021982, 0.08228271, 0.08437621, 0.08650046,
+    0.08865558, 0.09084171, 0.093058966, 0.09530747,
+    0.09758735, 0.099898726, 0.10224173, 0.104616486,
+    0.107023105, 0.10946171, 0.11193243, 0.114435375,
+    0.116970666, 0.11953843, 0.122138776, 0.12477182,
+    0.12743768, 0.13013647, 0.13286832, 0.13563333,
+    0.13843161, 0.14126329, 0.14412847, 0.14702727,
+    0.14995979, 0.15292615, 0.15592647, 0.15896083,
+    0.16202937, 0.1651322, 0.1682694, 0.17144111,
+    0.1746474, 0.17788842, 0.18116425, 0.18447499,
+    0.18782078, 0.19120169, 0.19461784, 0.19806932,
+    0.20155625, 0.20507874, 0.20863687, 0.21223076,
+    0.2158605, 0.2195262, 0.22322796, 0.22696587,
+    0.23074006, 0.23455058, 0.23839757, 0.24228112,
+    0.24620132, 0.25015828, 0.2541521, 0.25818285,
+    0.26225066, 0.2663556, 0.2704978, 0.2746773,
+    0.27889428, 0.28314874, 0.28744084, 0.29177064,
+    0.29613826, 0.30054379, 0.3049873, 0.30946892,
+    0.31398872, 0.31854677, 0.3231432, 0.3277781,
+    0.33245152, 0.33716363, 0.34191442, 0.34670407,
+    0.3515326, 0.35640013, 0.3613068, 0.3662526,
+    0.3712377, 0.37626213, 0.38132602, 0.38642943,
+    0.39157248, 0.39675522, 0.40197778, 0.4072402,
+    0.4125426, 0.41788507, 0.42326766, 0.4286905,
+    0.43415365, 0.43965718, 0.4452012, 0.4507858,
+    0.45641103, 0.462077, 0.4677838, 0.47353148,
+    0.47932017, 0.48514995, 0.49102086, 0.49693298,
+    0.5028865, 0.50888133, 0.5149177, 0.52099556,
+    0.5271151, 0.5332764, 0.5394795, 0.54572445,
+    0.55201143, 0.5583404, 0.5647115, 0.57112485,
+    0.57758045, 0.58407843, 0.59061885, 0.59720176,
+    0.60382736, 0.61049557, 0.6172066, 0.6239604,
+    0.63075715, 0.63759685, 0.6444797, 0.65140563,
+    0.65837485, 0.6653873, 0.67244315, 0.6795425,
+    0.6866853, 0.69387174, 0.7011019, 0.70837575,
+    0.7156935, 0.7230551, 0.73046076, 0.7379104,
+    0.7454042, 0.7529422, 0.7605245, 0.76815116,
+    0.7758222, 0.7835378, 0.7912979, 0.7991027,
+    0.80695224, 0.8148466, 0.82278574, 0.8307699,
+    0.838799, 0.8468732, 0.8549926, 0.8631572,
+    0.8713671, 0.8796224, 0.8879231, 0.8962694,
+    0.9046612, 0.91309863, 0.92158186, 0.9301109,
+    0.9386857, 0.9473065, 0.9559733, 0.9646863,
+    0.9734453, 0.9822506, 0.9911021, 1.0,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srgb_round_trip() {
+        for channel in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(channel)), channel);
+        }
+
+        assert_eq!(linear_to_srgb(-1.0), 0);
+        assert_eq!(linear_to_srgb(2.0), 255);
+        assert_eq!(linear_to_srgb(f32::NAN), 0);
+    }
+
+    #[test]
+    fn test_blend_srgb() {
+        // Reference values computed with the exact sRGB transfer functions
+        let red = [0xff, 0x00, 0x00, 0x80];
+        let blue = [0x00, 0x00, 0xff, 0xff];
+        assert_eq!(blend_srgb(blue, red), [188, 0, 187, 255]);
+
+        // Opaque sources replace the destination, transparent sources leave it unchanged
+        assert_eq!(
+            blend_srgb(blue, [0x12, 0x34, 0x56, 0xff]),
+            [0x12, 0x34, 0x56, 0xff]
+        );
+        assert_eq!(blend_srgb(blue, [0x12, 0x34, 0x56, 0x00]), blue);
+        assert_eq!(blend_srgb([0; 4], [0; 4]), [0; 4]);
+    }
+
+    #[test]
+    fn test_premultiply_alpha() {
+        let mut frame = [
+            0xff, 0xff, 0xff, 0xff, // Opaque
+            0xff, 0x80, 0x00, 0x80, // Translucent
+            0x12, 0x34, 0x56, 0x00, // Transparent
+            0xff, 0xff, // Not a whole pixel
+        ];
+        let mut srgb = frame;
+
+        premultiply_alpha(&mut frame);
+        assert_eq!(
+            frame,
+            [
+                0xff, 0xff, 0xff, 0xff, //
+                0x80, 0x40, 0x00, 0x80, //
+                0x00, 0x00, 0x00, 0x00, //
+                0xff, 0xff,
+            ]
+        );
+
+        // Half of linear white is brighter than half of the sRGB byte
+        premultiply_alpha_srgb(&mut srgb);
+        assert_eq!(
+            srgb,
+            [
+                0xff, 0xff, 0xff, 0xff, //
+                0xbc, 0x5d, 0x00, 0x80, //
+                0x00, 0x00, 0x00, 0x00, //
+                0xff, 0xff,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_channel_order() {
+        use wgpu::TextureFormat;
+
+        assert_eq!(
+            ChannelOrder::from_format(TextureFormat::Bgra8UnormSrgb),
+            ChannelOrder::Bgra
+        );
+        assert_eq!(
+            ChannelOrder::from_format(TextureFormat::Rgba8UnormSrgb),
+            ChannelOrder::Rgba
+        );
+        assert_eq!(
+            ChannelOrder::from_format(TextureFormat::R8Unorm),
+            ChannelOrder::Rgba
+        );
+
+        let color = [0x12, 0x34, 0x56, 0x78];
+        let bgra = ChannelOrder::Bgra.from_rgba(color);
+        assert_eq!(ChannelOrder::Bgra.to_rgba(bgra), color);
+
+        let mut frame = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0xff];
+        swap_rb(&mut frame);
+        assert_eq!(
+            frame,
+            [0x56, 0x34, 0x12, 0x78, 0xde, 0xbc, 0x9a, 0xf0, 0xff]
+        );
+    }
+}
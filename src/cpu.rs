@@ -0,0 +1,335 @@
+//! A CPU fallback for systems without a usable GPU.
+//!
+//! See [`PixelsBuilder::build_with_cpu_fallback`](crate::PixelsBuilder::build_with_cpu_fallback).
+
+use crate::color::{linear_to_srgb, ChannelOrder};
+use crate::{Error, Pixels, TextureError};
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use std::cell::{Cell, RefCell};
+use std::num::NonZeroU32;
+
+/// A pixel buffer that is either rendered on the GPU, or scaled and presented on the CPU.
+///
+/// Created by [`PixelsBuilder::build_with_cpu_fallback`]. The most common methods of [`Pixels`]
+/// are available here, and forward to whichever implementation is in use. Match on the variants
+/// to use anything else.
+///
+/// [`PixelsBuilder::build_with_cpu_fallback`]: crate::PixelsBuilder::build_with_cpu_fallback
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum FallbackPixels<'win, W> {
+    /// The pixel buffer is rendered on the GPU.
+    Gpu(Pixels<'win>),
+    /// No GPU adapter was found, so the pixel buffer is rendered on the CPU.
+    Cpu(CpuPixels<W>),
+}
+
+/// A pixel buffer that is scaled on the CPU and presented with [`softbuffer`].
+///
+/// The pixel buffer is scaled by the largest integer that fits within the surface and centered,
+/// like the default [`ScalingMode`](crate::ScalingMode) of [`Pixels`]. Custom shaders, render
+/// passes, and other scaling modes require a GPU.
+pub struct CpuPixels<W> {
+    surface: RefCell<softbuffer::Surface<W, W>>,
+    // The size that `surface` was last resized to
+    surface_configured: Cell<(u32, u32)>,
+    surface_size: (u32, u32),
+    width: u32,
+    height: u32,
+    channel_order: ChannelOrder,
+    clear_color: u32,
+    pixels: Vec<u8>,
+}
+
+impl<W: HasDisplayHandle + HasWindowHandle> CpuPixels<W> {
+    /// Create a CPU pixel buffer presenting to `window`.
+    ///
+    /// The texture format must be supported, see [`is_supported_format`].
+    pub(crate) fn new(
+        window: W,
+        width: u32,
+        height: u32,
+        surface_size: (u32, u32),
+        texture_format: wgpu::TextureFormat,
+        clear_color: wgpu::Color,
+    ) -> Result<Self, softbuffer::SoftBufferError>
+    where
+        W: Clone,
+    {
+        debug_assert!(is_supported_format(texture_format));
+
+        let context = softbuffer::Context::new(window.clone())?;
+        let surface = softbuffer::Surface::new(&context, window)?;
+
+        // The clear color is in linear light, like the GPU clear color
+        let [r, g, b] = [clear_color.r, clear_color.g, clear_color.b]
+            .map(|channel| linear_to_srgb(channel as f32) as u32);
+
+        Ok(Self {
+            surface: RefCell::new(surface),
+            surface_configured: Cell::new((0, 0)),
+            surface_size,
+            width,
+            height,
+            channel_order: ChannelOrder::from_format(texture_format),
+            clear_color: (r << 16) | (g << 8) | b,
+            pixels: vec![0; width as usize * height as usize * 4],
+        })
+    }
+
+    /// Get a byte slice for the pixel buffer, see [`Pixels::frame`].
+    pub fn frame(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Get a mutable byte slice for the pixel buffer, see [`Pixels::frame_mut`].
+    pub fn frame_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    /// Get the pixel buffer size, as `(width, height)`.
+    pub fn buffer_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Resize the pixel buffer and zero its contents, see [`Pixels::resize_buffer`].
+    ///
+    /// # Errors
+    ///
+    /// - [`TextureError::TextureWidth`] when `width` is 0.
+    /// - [`TextureError::TextureHeight`] when `height` is 0.
+    pub fn resize_buffer(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        if width == 0 {
+            return Err(TextureError::TextureWidth(width));
+        }
+        if height == 0 {
+            return Err(TextureError::TextureHeight(height));
+        }
+
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![0; width as usize * height as usize * 4];
+
+        Ok(())
+    }
+
+    /// Resize the surface, see [`Pixels::resize_surface`].
+    ///
+    /// A size of zero is allowed, e.g. while the window is minimized. Nothing is presented until
+    /// the surface is resized again.
+    pub fn resize_surface(&mut self, width: u32, height: u32) {
+        self.surface_size = (width, height);
+    }
+
+    /// Scale the pixel buffer and present it to the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the window surface cannot be resized or presented.
+    pub fn render(&self) -> Result<(), Error> {
+        let (Some(surface_width), Some(surface_height)) = (
+            NonZeroU32::new(self.surface_size.0),
+            NonZeroU32::new(self.surface_size.1),
+        ) else {
+            // The window is minimized
+            return Ok(());
+        };
+
+        let mut surface = self.surface.borrow_mut();
+        if self.surface_configured.get() != self.surface_size {
+            surface.resize(surface_width, surface_height)?;
+            self.surface_configured.set(self.surface_size);
+        }
+
+        let mut buffer = surface.buffer_mut()?;
+        let (x, y, scale) = self.scaled_rect();
+        let surface_width = self.surface_size.0 as usize;
+        for (row, dst) in buffer.chunks_exact_mut(surface_width).enumerate() {
+            // Rows are mapped back to the pixel buffer, and the border is cleared
+            let src_y = (row as i64 - y).div_euclid(scale);
+            if !(0..self.height as i64).contains(&src_y) {
+                dst.fill(self.clear_color);
+                continue;
+            }
+
+            let src_row = src_y as usize * self.width as usize * 4;
+            for (col, dst) in dst.iter_mut().enumerate() {
+                let src_x = (col as i64 - x).div_euclid(scale);
+                *dst = if (0..self.width as i64).contains(&src_x) {
+                    let i = src_row + src_x as usize * 4;
+                    let pixel = self.pixels[i..i + 4].try_into().unwrap();
+                    let [r, g, b, _] = self.channel_order.to_rgba(pixel).map(u32::from);
+
+                    (r << 16) | (g << 8) | b
+                } else {
+                    self.clear_color
+                };
+            }
+        }
+
+        buffer.present()?;
+
+        Ok(())
+    }
+
+    /// Calculate the pixel location from a physical location on the window, see
+    /// [`Pixels::window_pos_to_pixel`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the pixel coordinates outside of the pixel buffer when the position is outside of
+    /// the drawing area.
+    pub fn window_pos_to_pixel(
+        &self,
+        physical_position: (f32, f32),
+    ) -> Result<(usize, usize), (isize, isize)> {
+        let (x, y, scale) = self.scaled_rect();
+        let pixel_x = ((physical_position.0 - x as f32) / scale as f32).floor() as isize;
+        let pixel_y = ((physical_position.1 - y as f32) / scale as f32).floor() as isize;
+
+        if pixel_x < 0
+            || pixel_x >= self.width as isize
+            || pixel_y < 0
+            || pixel_y >= self.height as isize
+        {
+            Err((pixel_x, pixel_y))
+        } else {
+            Ok((pixel_x as usize, pixel_y as usize))
+        }
+    }
+
+    fn scaled_rect(&self) -> (i64, i64, i64) {
+        scaled_rect(self.surface_size, (self.width, self.height))
+    }
+}
+
+impl<W> std::fmt::Debug for CpuPixels<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CpuPixels")
+            .field("surface_size", &self.surface_size)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("channel_order", &self.channel_order)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'win, W: HasDisplayHandle + HasWindowHandle> FallbackPixels<'win, W> {
+    /// Get a byte slice for the pixel buffer, see [`Pixels::frame`].
+    pub fn frame(&self) -> &[u8] {
+        match self {
+            Self::Gpu(pixels) => pixels.frame(),
+            Self::Cpu(pixels) => pixels.frame(),
+        }
+    }
+
+    /// Get a mutable byte slice for the pixel buffer, see [`Pixels::frame_mut`].
+    pub fn frame_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Gpu(pixels) => pixels.frame_mut(),
+            Self::Cpu(pixels) => pixels.frame_mut(),
+        }
+    }
+
+    /// Get the pixel buffer size, as `(width, height)`.
+    pub fn buffer_size(&self) -> (u32, u32) {
+        match self {
+            Self::Gpu(pixels) => pixels.buffer_size(),
+            Self::Cpu(pixels) => pixels.buffer_size(),
+        }
+    }
+
+    /// Resize the pixel buffer and zero its contents, see [`Pixels::resize_buffer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the size is 0 or greater than GPU texture limits.
+    pub fn resize_buffer(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        match self {
+            Self::Gpu(pixels) => pixels.resize_buffer(width, height),
+            Self::Cpu(pixels) => pixels.resize_buffer(width, height),
+        }
+    }
+
+    /// Resize the surface, see [`Pixels::resize_surface`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the size is greater than GPU texture limits.
+    pub fn resize_surface(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        match self {
+            Self::Gpu(pixels) => pixels.resize_surface(width, height),
+            Self::Cpu(pixels) => {
+                pixels.resize_surface(width, height);
+                Ok(())
+            }
+        }
+    }
+
+    /// Draw the pixel buffer to the window, see [`Pixels::render`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the surface cannot be rendered or presented.
+    pub fn render(&self) -> Result<(), Error> {
+        match self {
+            Self::Gpu(pixels) => pixels.render(),
+            Self::Cpu(pixels) => pixels.render(),
+        }
+    }
+
+    /// Calculate the pixel location from a physical location on the window, see
+    /// [`Pixels::window_pos_to_pixel`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the pixel coordinates outside of the pixel buffer when the position is outside of
+    /// the drawing area.
+    pub fn window_pos_to_pixel(
+        &self,
+        physical_position: (f32, f32),
+    ) -> Result<(usize, usize), (isize, isize)> {
+        match self {
+            Self::Gpu(pixels) => pixels.window_pos_to_pixel(physical_position),
+            Self::Cpu(pixels) => pixels.window_pos_to_pixel(physical_position),
+        }
+    }
+}
+
+/// Check if the CPU fallback can present a texture format as it is. Only 8-bit formats with all
+/// four channels are supported.
+pub(crate) fn is_supported_format(texture_format: wgpu::TextureFormat) -> bool {
+    matches!(
+        texture_format,
+        wgpu::TextureFormat::Rgba8Unorm
+            | wgpu::TextureFormat::Rgba8UnormSrgb
+            | wgpu::TextureFormat::Bgra8Unorm
+            | wgpu::TextureFormat::Bgra8UnormSrgb
+    )
+}
+
+/// The position of the scaled pixel buffer on the surface, and the integer scale.
+///
+/// The position is negative when the surface is smaller than the pixel buffer, which crops it.
+fn scaled_rect(surface_size: (u32, u32), buffer_size: (u32, u32)) -> (i64, i64, i64) {
+    let (surface_width, surface_height) = surface_size;
+    let (width, height) = buffer_size;
+    let scale = (surface_width / width).min(surface_height / height).max(1) as i64;
+    let x = (surface_width as i64 - width as i64 * scale).div_euclid(2);
+    let y = (surface_height as i64 - height as i64 * scale).div_euclid(2);
+
+    (x, y, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_rect() {
+        assert_eq!(scaled_rect((320, 240), (320, 240)), (0, 0, 1));
+        assert_eq!(scaled_rect((700, 500), (320, 240)), (30, 10, 2));
+        assert_eq!(scaled_rect((300, 240), (320, 240)), (-10, 0, 1));
+        assert_eq!(scaled_rect((0, 0), (320, 240)), (-160, -120, 1));
+    }
+}
@@ -0,0 +1,273 @@
+//! Simple shape drawing on the pixel buffer.
+//!
+//! These functions draw directly into a frame like the one returned by [`Pixels::frame_mut`],
+//! without needing a 2D graphics library. Each pixel is assumed to be 4 bytes (e.g. the default
+//! [`wgpu::TextureFormat::Rgba8UnormSrgb`]), and the `color` is written as-is; it is _not_ blended
+//! with the existing pixels. See [`color::blend_srgb`] for blending.
+//!
+//! Coordinates are signed, and anything that falls outside of the frame is clipped.
+//!
+//! The same primitives are available as methods on any byte slice with the [`Canvas`] trait. They
+//! only depend on `core`, so software renderers can share them whether or not they use `std`.
+//!
+//! This module requires the `draw` feature.
+//!
+//! ```no_run
+//! use pixels::draw::{draw_circle, draw_line};
+//!
+//! # use pixels::Pixels;
+//! # let window = pixels_mocks::Window;
+//! # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+//! let mut pixels = Pixels::new(320, 240, surface_texture)?;
+//! let (width, _) = pixels.buffer_size();
+//! let frame = pixels.frame_mut();
+//!
+//! draw_line(frame, width, (0, 0), (319, 239), [0xff, 0xff, 0xff, 0xff]);
+//! draw_circle(frame, width, (160, 120), 50, [0xff, 0x00, 0x00, 0xff]);
+//! # Ok::<(), pixels::Error>(())
+//! ```
+//!
+//! [`Pixels::frame_mut`]: crate::Pixels::frame_mut
+//! [`color::blend_srgb`]: crate::color::blend_srgb
+
+use crate::color::ChannelOrder;
+use crate::Sprite;
+
+/// The drawing primitives in this module as methods on a frame with 4 bytes per pixel.
+///
+/// Every method takes the `width` of the frame in pixels; the height is derived from the length
+/// of the frame.
+///
+/// ```no_run
+/// use pixels::{draw::Canvas, Sprite};
+///
+/// # use pixels::Pixels;
+/// # let window = pixels_mocks::Window;
+/// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+/// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+/// let (width, _) = pixels.buffer_size();
+/// let sprite = Sprite::from_rgba(1, 1, vec![0xff, 0xff, 0x00, 0xff]);
+///
+/// let frame = pixels.frame_mut();
+/// frame.fill_rect(width, (0, 0), (320, 240), [0x00, 0x00, 0x00, 0xff]);
+/// frame.line(width, (0, 0), (319, 239), [0xff, 0xff, 0xff, 0xff]);
+/// frame.blit(width, (160, 120), &sprite);
+/// # Ok::<(), pixels::Error>(())
+/// ```
+pub trait Canvas {
+    /// Draw a line between two points (inclusive), see [`draw_line`].
+    fn line(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]);
+
+    /// Draw the outline of a rectangle, see [`draw_rect`].
+    fn rect(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]);
+
+    /// Fill a rectangle with a solid color, see [`fill_rect`].
+    fn fill_rect(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]);
+
+    /// Draw the outline of a circle, see [`draw_circle`].
+    fn circle(&mut self, width: u32, center: (i32, i32), radius: u32, color: [u8; 4]);
+
+    /// Draw a sprite with its top-left corner at `pos`, clipping it to the frame.
+    ///
+    /// Fully transparent sprite pixels are skipped, and the rest are copied as-is. Use
+    /// [`Pixels::blit`](crate::Pixels::blit) to match the channel order of the texture format.
+    fn blit(&mut self, width: u32, pos: (i32, i32), sprite: &Sprite);
+}
+
+impl Canvas for [u8] {
+    fn line(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+        draw_line(self, width, p1, p2, color);
+    }
+
+    fn rect(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+        draw_rect(self, width, p1, p2, color);
+    }
+
+    fn fill_rect(&mut self, width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+        fill_rect(self, width, p1, p2, color);
+    }
+
+    fn circle(&mut self, width: u32, center: (i32, i32), radius: u32, color: [u8; 4]) {
+        draw_circle(self, width, center, radius, color);
+    }
+
+    fn blit(&mut self, width: u32, pos: (i32, i32), sprite: &Sprite) {
+        sprite.blit(self, width, pos, ChannelOrder::Rgba);
+    }
+}
+
+/// Draw a line between two points (inclusive) using Bresenham's algorithm.
+pub fn draw_line(frame: &mut [u8], width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+    let (mut x, mut y) = p1;
+    let dx = (p2.0 - x).abs();
+    let dy = -(p2.1 - y).abs();
+    let sx = if x < p2.0 { 1 } else { -1 };
+    let sy = if y < p2.1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        put_pixel(frame, width, x, y, color);
+        if (x, y) == p2 {
+            break;
+        }
+
+        let e2 = err * 2;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Draw the outline of a rectangle.
+///
+/// `p1` is the top-left corner (inclusive) and `p2` is the bottom-right corner (exclusive). Nothing
+/// is drawn when the rectangle is empty.
+pub fn draw_rect(frame: &mut [u8], width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+    if p1.0 >= p2.0 || p1.1 >= p2.1 {
+        return;
+    }
+
+    let (right, bottom) = (p2.0 - 1, p2.1 - 1);
+    draw_line(frame, width, p1, (right, p1.1), color);
+    draw_line(frame, width, (p1.0, bottom), (right, bottom), color);
+    draw_line(frame, width, p1, (p1.0, bottom), color);
+    draw_line(frame, width, (right, p1.1), (right, bottom), color);
+}
+
+/// Fill a rectangle with a solid color.
+///
+/// `p1` is the top-left corner (inclusive) and `p2` is the bottom-right corner (exclusive). Nothing
+/// is drawn when the rectangle is empty.
+pub fn fill_rect(frame: &mut [u8], width: u32, p1: (i32, i32), p2: (i32, i32), color: [u8; 4]) {
+    let height = frame_height(frame, width);
+    let clamp_x = |x: i32| x.clamp(0, width as i32) as usize;
+    let clamp_y = |y: i32| y.clamp(0, height as i32) as usize;
+    let (left, right) = (clamp_x(p1.0), clamp_x(p2.0));
+    let (top, bottom) = (clamp_y(p1.1), clamp_y(p2.1));
+    if left >= right {
+        return;
+    }
+
+    let stride = width as usize * 4;
+    for y in top..bottom {
+        let row = &mut frame[y * stride + left * 4..y * stride + right * 4];
+        for pixel in row.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
+
+/// Draw the outline of a circle using the midpoint circle algorithm.
+pub fn draw_circle(frame: &mut [u8], width: u32, center: (i32, i32), radius: u32, color: [u8; 4]) {
+    let (cx, cy) = center;
+    let mut x = radius as i32;
+    let mut y = 0;
+    let mut err = 1 - x;
+
+    while x >= y {
+        // Plot all eight octants
+        for (dx, dy) in [
+            (x, y),
+            (y, x),
+            (-y, x),
+            (-x, y),
+            (-x, -y),
+            (-y, -x),
+            (y, -x),
+            (x, -y),
+        ] {
+            put_pixel(frame, width, cx + dx, cy + dy, color);
+        }
+
+        y += 1;
+        if err < 0 {
+            err += y * 2 + 1;
+        } else {
+            x -= 1;
+            err += (y - x) * 2 + 1;
+        }
+    }
+}
+
+/// Write a single pixel, if it is inside the frame.
+fn put_pixel(frame: &mut [u8], width: u32, x: i32, y: i32, color: [u8; 4]) {
+    let height = frame_height(frame, width);
+    if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+        return;
+    }
+
+    let i = (x as usize + y as usize * width as usize) * 4;
+    frame[i..i + 4].copy_from_slice(&color);
+}
+
+/// Compute the frame height from its length and width.
+fn frame_height(frame: &[u8], width: u32) -> u32 {
+    if width == 0 {
+        0
+    } else {
+        (frame.len() / (width as usize * 4)) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [0xff; 4];
+
+    fn pixel(frame: &[u8], width: u32, x: usize, y: usize) -> &[u8] {
+        let i = (x + y * width as usize) * 4;
+
+        &frame[i..i + 4]
+    }
+
+    #[test]
+    fn test_draw_clipping() {
+        let mut frame = vec![0; 8 * 4 * 4];
+
+        // Lines crossing the edges draw the visible part
+        draw_line(&mut frame, 8, (-4, -4), (10, 10), WHITE);
+        assert_eq!(pixel(&frame, 8, 0, 0), WHITE);
+        assert_eq!(pixel(&frame, 8, 3, 3), WHITE);
+        assert_eq!(pixel(&frame, 8, 4, 3), [0; 4]);
+
+        // Filled rects are clamped to the frame
+        let mut frame = vec![0; 8 * 4 * 4];
+        fill_rect(&mut frame, 8, (6, 2), (100, 100), WHITE);
+        let filled = frame.chunks_exact(4).filter(|&p| p == WHITE).count();
+        assert_eq!(filled, 4);
+
+        // Circles are clipped to the frame
+        let mut frame = vec![0; 8 * 4 * 4];
+        draw_circle(&mut frame, 8, (0, 0), 2, WHITE);
+        assert_eq!(pixel(&frame, 8, 2, 0), WHITE);
+        assert_eq!(pixel(&frame, 8, 0, 2), WHITE);
+        assert_eq!(pixel(&frame, 8, 0, 0), [0; 4]);
+    }
+
+    #[test]
+    fn test_canvas() {
+        // The methods draw the same as the functions
+        let mut expected = vec![0; 8 * 4 * 4];
+        draw_line(&mut expected, 8, (0, 0), (7, 3), WHITE);
+        draw_rect(&mut expected, 8, (1, 1), (4, 4), WHITE);
+        draw_circle(&mut expected, 8, (6, 2), 1, WHITE);
+        let mut frame = vec![0; 8 * 4 * 4];
+        frame.line(8, (0, 0), (7, 3), WHITE);
+        frame.rect(8, (1, 1), (4, 4), WHITE);
+        frame.circle(8, (6, 2), 1, WHITE);
+        assert_eq!(frame, expected);
+
+        // Sprites are copied as RGBA
+        let red = [0xff, 0x00, 0x00, 0xff];
+        let sprite = Sprite::from_rgba(1, 1, red.to_vec());
+        frame.fill_rect(8, (0, 0), (8, 4), [0; 4]);
+        frame.blit(8, (7, 3), &sprite);
+        assert_eq!(pixel(&frame, 8, 7, 3), red);
+    }
+}
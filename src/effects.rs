@@ -0,0 +1,342 @@
+//! Built-in post-processing effects.
+//!
+//! Effects draw the pixel buffer with the [`ScalingRenderer`](crate::ScalingRenderer) into an
+//! intermediate texture, and then draw that texture to the render target with a shader applied.
+//! They are used with [`Pixels::render_with`](crate::Pixels::render_with).
+
+use crate::{check_texture_size, Pixels, PixelsContext, TextureError};
+use wgpu::util::DeviceExt;
+
+/// Size of the `Locals` uniform in `crt.wgsl`, including padding.
+const CRT_LOCALS_SIZE: usize = 12;
+
+/// A CRT monitor effect with scanlines and a slight barrel distortion.
+///
+/// The intermediate texture must be the same size as the surface. Call [`Crt::resize`] whenever
+/// the surface is resized.
+///
+/// # Example
+///
+/// ```no_run
+/// use pixels::effects::Crt;
+/// # use pixels::Pixels;
+/// # let window = pixels_mocks::Window;
+/// # let surface_texture = pixels::SurfaceTexture::new(640, 480, &window);
+/// let pixels = Pixels::new(320, 240, surface_texture)?;
+/// let crt = Crt::new(&pixels, 640, 480)?;
+///
+/// pixels.render_with(|encoder, render_target, context| {
+///     crt.render(encoder, render_target, context);
+///     Ok(())
+/// })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct Crt {
+    texture_view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    render_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    vertex_buffer: wgpu::Buffer,
+    surface_size: (u32, u32),
+    curvature: f32,
+    scanline_intensity: f32,
+}
+
+impl Crt {
+    /// Create a CRT effect for a surface that is `width` by `height` pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the surface size is invalid for a texture.
+    pub fn new(pixels: &Pixels, width: u32, height: u32) -> Result<Self, TextureError> {
+        let device = pixels.device();
+        let shader = wgpu::include_wgsl!("../shaders/crt.wgsl");
+        let module = device.create_shader_module(shader);
+
+        // The scaling renderer draws into this texture
+        let texture_view = create_texture_view(pixels, width, height)?;
+
+        // Create a texture sampler with bilinear filtering, to smooth out the distortion
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("pixels_crt_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 1.0,
+            compare: None,
+            anisotropy_clamp: 1,
+            border_color: None,
+        });
+
+        // Create vertex buffer; array-of-array of position coordinates
+        let vertex_data: [[f32; 2]; 3] = [
+            // One full-screen triangle
+            // See: https://github.com/parasyte/pixels/issues/180
+            [-1.0, -1.0],
+            [3.0, -1.0],
+            [-1.0, 3.0],
+        ];
+        let vertex_data_slice = bytemuck::cast_slice(&vertex_data);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("pixels_crt_vertex_buffer"),
+            contents: vertex_data_slice,
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let vertex_buffer_layout = wgpu::VertexBufferLayout {
+            array_stride: (vertex_data_slice.len() / vertex_data.len()) as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[wgpu::VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 0,
+            }],
+        };
+
+        // Create uniform buffer; it is written before each render
+        let locals_size = (CRT_LOCALS_SIZE * std::mem::size_of::<f32>()) as u64;
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("pixels_crt_uniform_buffer"),
+            size: locals_size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Create bind group
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("pixels_crt_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(locals_size),
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            &texture_view,
+            &sampler,
+            &uniform_buffer,
+        );
+
+        // Create pipeline
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("pixels_crt_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("pixels_crt_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[vertex_buffer_layout],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: pixels.render_texture_format(),
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        Ok(Self {
+            texture_view,
+            sampler,
+            bind_group_layout,
+            bind_group,
+            render_pipeline,
+            uniform_buffer,
+            vertex_buffer,
+            surface_size: (width, height),
+            curvature: 0.03,
+            scanline_intensity: 0.25,
+        })
+    }
+
+    /// Resize the intermediate texture to match the surface size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the surface size is invalid for a texture.
+    pub fn resize(&mut self, pixels: &Pixels, width: u32, height: u32) -> Result<(), TextureError> {
+        self.texture_view = create_texture_view(pixels, width, height)?;
+        self.bind_group = create_bind_group(
+            pixels.device(),
+            &self.bind_group_layout,
+            &self.texture_view,
+            &self.sampler,
+            &self.uniform_buffer,
+        );
+        self.surface_size = (width, height);
+
+        Ok(())
+    }
+
+    /// Set the strength of the barrel distortion. `0.0` disables it; the default is `0.03`.
+    pub fn set_curvature(&mut self, curvature: f32) {
+        self.curvature = curvature;
+    }
+
+    /// Set how much the scanlines darken the image, from `0.0` (no scanlines) to `1.0` (black
+    /// gaps between rows). The default is `0.25`.
+    pub fn set_scanline_intensity(&mut self, intensity: f32) {
+        self.scanline_intensity = intensity.clamp(0.0, 1.0);
+    }
+
+    /// Draw the pixel buffer to the render target with the CRT effect.
+    ///
+    /// The arguments are the same as the ones given to the
+    /// [`Pixels::render_with`](crate::Pixels::render_with) closure. There is one scanline for
+    /// each row of the pixel buffer.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        render_target: &wgpu::TextureView,
+        context: &PixelsContext,
+    ) {
+        let scaling_renderer = &context.scaling_renderer;
+        scaling_renderer.render(encoder, &self.texture_view);
+
+        let clip_rect = scaling_renderer.clip_rect();
+        let locals: [f32; CRT_LOCALS_SIZE] = [
+            clip_rect.0 as f32,
+            clip_rect.1 as f32,
+            clip_rect.2 as f32,
+            clip_rect.3 as f32,
+            self.surface_size.0 as f32,
+            self.surface_size.1 as f32,
+            context.texture_extent.height as f32,
+            self.curvature,
+            self.scanline_intensity,
+            0.0,
+            0.0,
+            0.0,
+        ];
+        context
+            .queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&locals));
+
+        // Like the scaling renderer, only the viewport is drawn when one has been set
+        let load = match scaling_renderer.viewport {
+            Some(_) => wgpu::LoadOp::Load,
+            None => wgpu::LoadOp::Clear(scaling_renderer.clear_color),
+        };
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("pixels_crt_render_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: render_target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        // Nothing to draw when the pixel buffer is entirely outside of the render target
+        if clip_rect.2 == 0 || clip_rect.3 == 0 {
+            return;
+        }
+
+        rpass.set_pipeline(&self.render_pipeline);
+        rpass.set_bind_group(0, &self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        rpass.set_scissor_rect(clip_rect.0, clip_rect.1, clip_rect.2, clip_rect.3);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+fn create_texture_view(
+    pixels: &Pixels,
+    width: u32,
+    height: u32,
+) -> Result<wgpu::TextureView, TextureError> {
+    let device = pixels.device();
+    check_texture_size(device, width, height)?;
+    let texture_descriptor = wgpu::TextureDescriptor {
+        label: Some("pixels_crt_texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: pixels.render_texture_format(),
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    };
+
+    Ok(device
+        .create_texture(&texture_descriptor)
+        .create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    texture_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    uniform_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("pixels_crt_bind_group"),
+        layout: bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
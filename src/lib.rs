@@ -32,14 +32,40 @@
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
-pub use crate::builder::{check_texture_size, PixelsBuilder};
-pub use crate::renderers::ScalingRenderer;
+pub use crate::builder::{
+    check_texture_size, texture_format_size, LimitsPreset, Offscreen, PixelsBuilder,
+};
+use crate::mipmaps::MipmapGenerator;
+use crate::mirror::Mirror;
+pub use crate::region::RegionMut;
+pub use crate::renderers::{ScaleKind, ScalingMode, ScalingRenderer};
+pub use crate::sprite::Sprite;
+#[cfg(feature = "render-timing")]
+use crate::timing::Stopwatch;
 pub use raw_window_handle;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use thiserror::Error;
+pub use ultraviolet;
 pub use wgpu;
 
+mod buffer;
 mod builder;
+pub mod color;
+#[cfg(feature = "cpu-fallback")]
+pub mod cpu;
+#[cfg(feature = "draw")]
+pub mod draw;
+#[cfg(feature = "crt")]
+pub mod effects;
+mod mipmaps;
+mod mirror;
+mod region;
 mod renderers;
+mod sprite;
+pub mod time;
+#[cfg(feature = "render-timing")]
+mod timing;
 
 /// A logical texture for a window surface.
 #[derive(Debug)]
@@ -55,6 +81,15 @@ struct SurfaceSize {
     height: u32,
 }
 
+/// The final destination for the rendered pixel buffer.
+#[derive(Debug)]
+enum RenderTarget<'win> {
+    /// A window surface.
+    Surface(wgpu::Surface<'win>),
+    /// A texture standing in for the window surface, see [`PixelsBuilder::new_offscreen`].
+    Offscreen(wgpu::Texture),
+}
+
 /// Provides the internal state for custom shaders.
 ///
 /// A reference to this struct is given to the `render_function` closure when using
@@ -62,17 +97,19 @@ struct SurfaceSize {
 #[derive(Debug)]
 pub struct PixelsContext<'win> {
     /// The `Device` allows creating GPU resources.
-    pub device: wgpu::Device,
+    pub device: Arc<wgpu::Device>,
 
     /// The `Queue` provides access to the GPU command queue.
-    pub queue: wgpu::Queue,
+    pub queue: Arc<wgpu::Queue>,
 
-    surface: wgpu::Surface<'win>,
+    render_target: RenderTarget<'win>,
 
     /// This is the texture that your raw data is copied to by [`Pixels::render`] or
     /// [`Pixels::render_with`].
     pub texture: wgpu::Texture,
 
+    texture_view: wgpu::TextureView,
+
     /// Provides access to the texture size.
     pub texture_extent: wgpu::Extent3d,
     pub texture_format: wgpu::TextureFormat,
@@ -98,8 +135,41 @@ pub struct Pixels<'win> {
     render_texture_format: wgpu::TextureFormat,
     surface_texture_format: wgpu::TextureFormat,
     blend_state: wgpu::BlendState,
+    color_write_mask: wgpu::ColorWrites,
+    texture_usage: wgpu::TextureUsages,
+    view_formats: Vec<wgpu::TextureFormat>,
     alpha_mode: wgpu::CompositeAlphaMode,
-    adapter: wgpu::Adapter,
+    surface_capabilities: Option<wgpu::SurfaceCapabilities>,
+    auto_reconfigure: bool,
+
+    // Surfaces added with `add_mirror_surface`
+    mirrors: Vec<Mirror<'win>>,
+    adapter: Arc<wgpu::Adapter>,
+    instance: Arc<wgpu::Instance>,
+
+    // The surface texture acquired by `encode_with`, waiting for `present`
+    pending_frame: Mutex<Option<wgpu::SurfaceTexture>>,
+
+    // Render functions registered with `add_render_pass`
+    render_passes: RenderPasses<'win>,
+
+    // Called with the pixel buffer on each upload, see `set_frame_callback`
+    frame_callback: FrameCallbackSlot<'win>,
+
+    // Enabled with `PixelsBuilder::generate_mipmaps`
+    mipmap_generator: Option<MipmapGenerator>,
+
+    // The prefix for GPU resource labels, see `PixelsBuilder::label`
+    label: String,
+
+    // Enabled with `PixelsBuilder::premultiply_alpha`, the premultiplied copy of the uploaded
+    // bytes is kept in the scratch buffer so the pixel buffer itself is not modified
+    premultiply_alpha: bool,
+    premultiply_scratch: Mutex<Vec<u8>>,
+
+    // CPU time spent in the last render, see `last_render_time`
+    #[cfg(feature = "render-timing")]
+    last_render_time: Mutex<std::time::Duration>,
 
     // Pixel buffer
     pixels: Vec<u8>,
@@ -119,6 +189,9 @@ pub enum Error {
     /// Equivalent to [`wgpu::RequestDeviceError`]
     #[error("No wgpu::Device found.")]
     DeviceNotFound(#[from] wgpu::RequestDeviceError),
+    /// Requesting a [`wgpu::Device`] took longer than [`PixelsBuilder::device_timeout`]
+    #[error("Timed out while requesting a wgpu::Device.")]
+    Timeout,
     /// Equivalent to [`wgpu::SurfaceError`]
     #[error("The GPU failed to acquire a surface frame.")]
     Surface(#[from] wgpu::SurfaceError),
@@ -128,6 +201,32 @@ pub enum Error {
     /// Equivalent to [`TextureError`]
     #[error("Texture creation failed: {0}")]
     InvalidTexture(#[from] TextureError),
+    /// Equivalent to [`wgpu::BufferAsyncError`]
+    #[error("Unable to map a buffer for reading.")]
+    BufferMap(#[from] wgpu::BufferAsyncError),
+    /// The adapter does not support the [`wgpu::Features`] required with
+    /// [`PixelsBuilder::device_features`], contains the missing features
+    #[error("The adapter does not support the required features: {0:?}")]
+    UnsupportedFeatures(wgpu::Features),
+    /// A mirror surface does not support the surface texture format, see
+    /// [`Pixels::add_mirror_surface`]
+    #[error("The surface does not support the texture format: {0:?}")]
+    UnsupportedSurfaceFormat(wgpu::TextureFormat),
+    /// The surface does not support the present mode, see [`Pixels::set_present_mode`]
+    #[error("The surface does not support the present mode: {0:?}")]
+    UnsupportedPresentMode(wgpu::PresentMode),
+    /// A view format is not compatible with the texture format or the surface texture format, or
+    /// the adapter does not support reinterpreting them, see [`PixelsBuilder::view_formats`]
+    #[error("The view format is not supported: {0:?}")]
+    UnsupportedViewFormat(wgpu::TextureFormat),
+    /// Equivalent to [`softbuffer::SoftBufferError`]
+    #[cfg(feature = "cpu-fallback")]
+    #[error("The CPU fallback failed to present: {0}")]
+    Softbuffer(#[from] softbuffer::SoftBufferError),
+    /// No render function is registered with the ID given to [`Pixels::render_with_pass`],
+    /// contains the ID
+    #[error("No render pass is registered as {0:?}")]
+    UnknownRenderPass(String),
     /// User-defined error from custom render function
     #[error("User-defined error.")]
     UserDefined(#[from] DynError),
@@ -135,16 +234,138 @@ pub enum Error {
 
 type DynError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
+/// A render function registered with [`Pixels::add_render_pass`].
+///
+/// It has the same arguments as the render function given to [`Pixels::render_with`].
+#[cfg(not(target_arch = "wasm32"))]
+pub type RenderPass<'win> = Box<
+    dyn Fn(&mut wgpu::CommandEncoder, &wgpu::TextureView, &PixelsContext) -> Result<(), DynError>
+        + Send
+        + Sync
+        + 'win,
+>;
+
+/// A render function registered with [`Pixels::add_render_pass`].
+///
+/// It has the same arguments as the render function given to [`Pixels::render_with`].
+#[cfg(target_arch = "wasm32")]
+pub type RenderPass<'win> = Box<
+    dyn Fn(&mut wgpu::CommandEncoder, &wgpu::TextureView, &PixelsContext) -> Result<(), DynError>
+        + 'win,
+>;
+
+/// The render passes registered with [`Pixels::add_render_pass`], by ID.
+#[derive(Default)]
+struct RenderPasses<'win>(HashMap<String, RenderPass<'win>>);
+
+impl std::fmt::Debug for RenderPasses<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// A function registered with [`Pixels::set_frame_callback`].
+///
+/// It is called with the pixel buffer, and its width and height in pixels.
+#[cfg(not(target_arch = "wasm32"))]
+pub type FrameCallback<'win> = Box<dyn FnMut(&[u8], u32, u32) + Send + 'win>;
+
+/// A function registered with [`Pixels::set_frame_callback`].
+///
+/// It is called with the pixel buffer, and its width and height in pixels.
+#[cfg(target_arch = "wasm32")]
+pub type FrameCallback<'win> = Box<dyn FnMut(&[u8], u32, u32) + 'win>;
+
+/// The function registered with [`Pixels::set_frame_callback`], if any.
+#[derive(Default)]
+struct FrameCallbackSlot<'win>(Mutex<Option<FrameCallback<'win>>>);
+
+impl std::fmt::Debug for FrameCallbackSlot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let is_set = self.0.lock().is_ok_and(|callback| callback.is_some());
+        f.debug_tuple("FrameCallbackSlot").field(&is_set).finish()
+    }
+}
+
+/// A future that resolves when the GPU has finished the work submitted to the queue.
+///
+/// See [`Pixels::render_async`].
+struct SubmittedWorkDone<'a> {
+    device: &'a wgpu::Device,
+    state: Arc<Mutex<WorkDoneState>>,
+}
+
+#[derive(Default)]
+struct WorkDoneState {
+    done: bool,
+    waker: Option<std::task::Waker>,
+}
+
+impl<'a> SubmittedWorkDone<'a> {
+    fn new(context: &'a PixelsContext) -> Self {
+        let state = Arc::new(Mutex::new(WorkDoneState::default()));
+        let callback_state = Arc::clone(&state);
+        context.queue.on_submitted_work_done(move || {
+            let mut state = callback_state.lock().unwrap();
+            state.done = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        Self {
+            device: &context.device,
+            state,
+        }
+    }
+}
+
+impl std::future::Future for SubmittedWorkDone<'_> {
+    type Output = ();
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        // Native devices only run callbacks when they are polled. The browser runs them on Web.
+        #[cfg(not(target_arch = "wasm32"))]
+        self.device.poll(wgpu::Maintain::Wait);
+        #[cfg(target_arch = "wasm32")]
+        let _ = self.device;
+
+        let mut state = self.state.lock().unwrap();
+        if state.done {
+            std::task::Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            std::task::Poll::Pending
+        }
+    }
+}
+
 /// All the ways in which creating a texture can fail.
 #[derive(Error, Debug)]
 #[non_exhaustive]
 pub enum TextureError {
-    /// Unable to create a backing texture; Width is either 0 or greater than GPU limits
+    /// Unable to create a backing texture; Width is 0
     #[error("Texture width is invalid: {0}")]
     TextureWidth(u32),
-    /// Unable to create a backing texture; Height is either 0 or greater than GPU limits
+    /// Unable to create a backing texture; Height is 0
     #[error("Texture height is invalid: {0}")]
     TextureHeight(u32),
+    /// Unable to create a backing texture; Width or height is greater than the GPU limit in
+    /// [`wgpu::Limits::max_texture_dimension_2d`]
+    #[error("Texture size {requested} is greater than the GPU limit: {max}")]
+    TooLarge {
+        /// The width or height that was requested.
+        requested: u32,
+        /// The maximum width and height supported by the device.
+        max: u32,
+    },
+    /// Unable to create a backing texture; The format cannot be written from the pixel buffer and
+    /// sampled with filtering, e.g. compressed, depth, and integer formats
+    #[error("Texture format is not supported for the pixel buffer: {0:?}")]
+    UnsupportedFormat(wgpu::TextureFormat),
 }
 
 impl<W: wgpu::WindowHandle> SurfaceTexture<W> {
@@ -179,6 +400,100 @@ impl<W: wgpu::WindowHandle> SurfaceTexture<W> {
 
         Self { window, size }
     }
+
+    /// Get the surface size as `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        (self.size.width, self.size.height)
+    }
+
+    /// Change the surface size before building the pixel buffer.
+    ///
+    /// ```no_run
+    /// # use pixels::SurfaceTexture;
+    /// # let window = pixels_mocks::Window;
+    /// let mut surface_texture = SurfaceTexture::new(8192, 8192, &window);
+    ///
+    /// // Clamp the surface to a smaller size
+    /// let (width, height) = surface_texture.size();
+    /// surface_texture.set_size(width.min(4096), height.min(4096));
+    /// assert_eq!(surface_texture.size(), (4096, 4096));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `width` or `height` are 0.
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        assert!(width > 0);
+        assert!(height > 0);
+
+        self.size = SurfaceSize { width, height };
+    }
+}
+
+#[cfg(feature = "winit")]
+impl<W> SurfaceTexture<W>
+where
+    W: wgpu::WindowHandle + std::ops::Deref<Target = winit::window::Window>,
+{
+    /// Create a logical texture for a `winit` window, using the window's physical inner size.
+    ///
+    /// This is equivalent to calling [`SurfaceTexture::new`] with the size from
+    /// [`Window::inner_size`](winit::window::Window::inner_size). It accepts anything that
+    /// dereferences to a window, such as `&Window` or `Arc<Window>`.
+    ///
+    /// This method requires the `winit` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pixels::SurfaceTexture;
+    /// use winit::event_loop::EventLoop;
+    /// use winit::window::Window;
+    ///
+    /// let event_loop = EventLoop::new().unwrap();
+    /// let window = Window::new(&event_loop).unwrap();
+    ///
+    /// let surface_texture = SurfaceTexture::new_auto(&window);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when the window's inner width or height are 0, e.g. when it is minimized.
+    pub fn new_auto(window: W) -> Self {
+        let size = window.inner_size();
+
+        Self::new(size.width, size.height, window)
+    }
+}
+
+impl PixelsContext<'_> {
+    /// Get the clipping rectangle for the scaling renderer.
+    ///
+    /// This rectangle defines the inner bounds of the surface texture, without the border. Custom
+    /// renderers can use it to set the scissor rect for their own render passes.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// pixels.render_with(|encoder, render_target, context| {
+    ///     let (x, y, width, height) = context.clip_rect();
+    ///     // Use the clipping rectangle with `set_scissor_rect` in a custom render pass...
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn clip_rect(&self) -> (u32, u32, u32, u32) {
+        self.scaling_renderer.clip_rect()
+    }
+
+    /// Get a view of the source texture, see [`Pixels::texture_view`].
+    pub fn texture_view(&self) -> &wgpu::TextureView {
+        &self.texture_view
+    }
 }
 
 impl<'win> Pixels<'win> {
@@ -289,6 +604,67 @@ impl<'win> Pixels<'win> {
     /// ```
     pub fn clear_color(&mut self, color: wgpu::Color) {
         self.context.scaling_renderer.clear_color = color;
+        for mirror in &mut self.mirrors {
+            mirror.scaling_renderer.clear_color = color;
+        }
+    }
+
+    /// Change the color channels written by the scaling renderer.
+    ///
+    /// This rebuilds the render pipeline, so avoid calling it on every frame. See
+    /// [`PixelsBuilder::color_write_mask`].
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::ColorWrites;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Preserve the alpha channel of the render target
+    /// pixels.set_color_write_mask(ColorWrites::COLOR);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `color_write_mask` is empty, which would draw nothing at all.
+    pub fn set_color_write_mask(&mut self, color_write_mask: wgpu::ColorWrites) {
+        assert!(
+            !color_write_mask.is_empty(),
+            "The color write mask is empty"
+        );
+        self.color_write_mask = color_write_mask;
+        self.context
+            .scaling_renderer
+            .set_color_write_mask(&self.context.device, color_write_mask);
+        for mirror in &mut self.mirrors {
+            mirror
+                .scaling_renderer
+                .set_color_write_mask(&self.context.device, color_write_mask);
+        }
+    }
+
+    /// Get the color channels written by the scaling renderer.
+    pub fn color_write_mask(&self) -> wgpu::ColorWrites {
+        self.color_write_mask
+    }
+
+    /// Enable or disable premultiplying the color of each pixel by its alpha when the pixel buffer
+    /// is uploaded. See [`PixelsBuilder::premultiply_alpha`].
+    pub fn set_premultiply_alpha(&mut self, enable: bool) {
+        self.premultiply_alpha = enable;
+        if !enable {
+            // Release the scratch buffer
+            *self.premultiply_scratch.get_mut().unwrap() = Vec::new();
+        }
+    }
+
+    /// Check if the color of each pixel is premultiplied by its alpha when the pixel buffer is
+    /// uploaded.
+    pub fn premultiply_alpha(&self) -> bool {
+        self.premultiply_alpha
     }
 
     /// Returns a reference of the `wgpu` adapter used by the crate.
@@ -305,10 +681,49 @@ impl<'win> Pixels<'win> {
     /// // Do something with the adapter.
     /// # Ok::<(), pixels::Error>(())
     /// ```
-    pub fn adapter(&self) -> &wgpu::Adapter {
+    ///
+    /// The adapter can also be shared with another pixel buffer, see
+    /// [`PixelsBuilder::with_device`].
+    pub fn adapter(&self) -> &Arc<wgpu::Adapter> {
         &self.adapter
     }
 
+    /// Returns a reference of the `wgpu` instance used by the crate.
+    ///
+    /// The instance can be shared with another pixel buffer, see
+    /// [`PixelsBuilder::with_instance`] and [`PixelsBuilder::with_device`].
+    pub fn instance(&self) -> &Arc<wgpu::Instance> {
+        &self.instance
+    }
+
+    /// Get the [`wgpu::Features`] enabled on the device, see [`PixelsBuilder::device_features`].
+    pub fn features(&self) -> wgpu::Features {
+        self.context.device.features()
+    }
+
+    /// Get the capabilities of the surface with the adapter in use.
+    ///
+    /// Lists the texture formats, present modes, and alpha modes that the surface supports, e.g.
+    /// for building a settings menu. Returns `None` for offscreen pixel buffers, which have no
+    /// surface.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// if let Some(capabilities) = pixels.surface_capabilities() {
+    ///     for present_mode in &capabilities.present_modes {
+    ///         println!("Supported present mode: {present_mode:?}");
+    ///     }
+    /// }
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn surface_capabilities(&self) -> Option<&wgpu::SurfaceCapabilities> {
+        self.surface_capabilities.as_ref()
+    }
+
     /// Resize the pixel buffer and zero its contents.
     ///
     /// This does not resize the surface upon which the pixel buffer texture is rendered. Use
@@ -323,28 +738,131 @@ impl<'win> Pixels<'win> {
     ///
     /// # Errors
     ///
-    /// - [`TextureError::TextureWidth`] when `width` is 0 or greater than GPU texture limits.
-    /// - [`TextureError::TextureHeight`] when `height` is 0 or greater than GPU texture limits.
+    /// - [`TextureError::TextureWidth`] when `width` is 0.
+    /// - [`TextureError::TextureHeight`] when `height` is 0.
+    /// - [`TextureError::TooLarge`] when `width` or `height` is greater than GPU texture limits.
     pub fn resize_buffer(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        self.recreate_backing_texture(width, height, self.context.texture_format)
+    }
+
+    /// Change the texture format of the pixel buffer and zero its contents.
+    ///
+    /// This recreates the backing texture and the scaling renderer, like
+    /// [`Pixels::resize_buffer`], and resizes the pixel buffer to fit the new format. E.g.
+    /// switching to `Rgba16Float` for an HDR mode doubles the size of [`Pixels::frame`]. The size
+    /// of the pixel buffer in pixels, and the scaling settings, are kept.
+    ///
+    /// Any slices previously borrowed from [`Pixels::frame`] or [`Pixels::frame_mut`] cannot be
+    /// used after this call, and the byte layout of the pixel buffer changes with the format. Does
+    /// nothing when `texture_format` is already in use.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::TextureFormat;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// pixels.set_texture_format(TextureFormat::Rgba16Float)?;
+    /// assert_eq!(pixels.frame().len(), 320 * 240 * 8);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - [`TextureError::UnsupportedFormat`] when the format cannot be used for the pixel buffer.
+    ///   The format must be uncompressed, and filterable without additional device features. E.g.
+    ///   compressed, depth, and integer formats are not supported. With
+    ///   [`PixelsBuilder::generate_mipmaps`], the format must also be renderable.
+    ///
+    /// The pixel buffer and texture format are left unchanged on error.
+    pub fn set_texture_format(
+        &mut self,
+        texture_format: wgpu::TextureFormat,
+    ) -> Result<(), TextureError> {
+        if texture_format == self.context.texture_format {
+            return Ok(());
+        }
+
+        let features = texture_format.guaranteed_format_features(self.context.device.features());
+        let mut usages = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+        if self.mipmap_generator.is_some() {
+            usages |= wgpu::TextureUsages::RENDER_ATTACHMENT;
+        }
+        if texture_format.is_compressed()
+            || !features.allowed_usages.contains(usages)
+            || !features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+        {
+            return Err(TextureError::UnsupportedFormat(texture_format));
+        }
+
+        let extent = self.context.texture_extent;
+        self.recreate_backing_texture(extent.width, extent.height, texture_format)?;
+
+        // The old contents have a different byte layout
+        self.pixels.fill(0);
+
+        Ok(())
+    }
+
+    /// Recreate the backing texture and scaling renderers, and resize the pixel buffer to match.
+    fn recreate_backing_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        texture_format: wgpu::TextureFormat,
+    ) -> Result<(), TextureError> {
         // Recreate the backing texture
-        let (scaling_matrix_inverse, texture_extent, texture, scaling_renderer, pixels_buffer_size) =
+        let (_, texture_extent, texture, texture_view, scaling_renderer, pixels_buffer_size) =
             builder::create_backing_texture(
                 &self.context.device,
                 // Backing texture values
                 width,
                 height,
-                self.context.texture_format,
+                texture_format,
+                self.texture_usage,
+                &self.view_formats,
+                self.mipmap_generator.is_some(),
+                &self.label,
                 // Render texture values
                 &self.surface_size,
                 self.render_texture_format,
                 self.context.scaling_renderer.clear_color,
                 self.blend_state,
+                self.color_write_mask,
             )?;
 
-        self.scaling_matrix_inverse = scaling_matrix_inverse;
+        // Keep the viewport, scaling mode, and pixel aspect ratio of the old renderer
+        let old_renderer = std::mem::replace(&mut self.context.scaling_renderer, scaling_renderer);
+        self.context
+            .scaling_renderer
+            .copy_settings(&self.context.queue, &old_renderer);
         self.context.texture_extent = texture_extent;
         self.context.texture = texture;
-        self.context.scaling_renderer = scaling_renderer;
+        self.context.texture_view = texture_view;
+        self.context.texture_format = texture_format;
+        self.context.texture_format_size = texture_format_size(texture_format);
+        self.update_scaling_matrix_inverse();
+
+        // The mipmap pipeline renders to the texture format
+        if let Some(mipmap_generator) = &mut self.mipmap_generator {
+            if mipmap_generator.texture_format() != texture_format {
+                *mipmap_generator = MipmapGenerator::new(&self.context.device, texture_format);
+            }
+        }
+
+        // The mirrors draw the old texture until their renderers are recreated
+        let renderers: Vec<_> = self
+            .mirrors
+            .iter()
+            .map(|mirror| self.create_mirror_renderer(&mirror.size()))
+            .collect();
+        for (mirror, renderer) in self.mirrors.iter_mut().zip(renderers) {
+            mirror.scaling_renderer = renderer;
+        }
 
         // Resize the pixel buffer
         self.pixels
@@ -353,91 +871,1441 @@ impl<'win> Pixels<'win> {
         Ok(())
     }
 
-    /// Resize the surface upon which the pixel buffer texture is rendered.
+    /// Resize the pixel buffer, preserving the contents of the overlapping region.
     ///
-    /// This does not resize the pixel buffer. Use [`Pixels::resize_buffer`] to change the size of
-    /// the pixel buffer.
+    /// This is like [`Pixels::resize_buffer`], but the top-left corner of the pixel buffer is kept
+    /// instead of being discarded. E.g. growing a canvas in an image editor. Any newly exposed
+    /// area is zeroed, and any area outside of the new size is cropped.
     ///
-    /// The pixel buffer texture will be fit onto the surface texture as best as possible by scaling
-    /// to the nearest integer, e.g. 2x, 3x, 4x, etc. A border will be added around the pixel buffer
-    /// texture for non-integer scaling ratios.
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    /// pixels.frame_mut()[..4].copy_from_slice(&[0xff, 0x00, 0x00, 0xff]);
     ///
-    /// Call this method in response to a resize event from your window manager. The size expected
-    /// is in physical pixel units. Does nothing when `width` or `height` are 0.
+    /// pixels.resize_buffer_preserve(640, 480)?;
+    /// assert_eq!(pixels.frame()[..4], [0xff, 0x00, 0x00, 0xff]);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// - [`TextureError::TextureWidth`] when `width` is 0 or greater than GPU texture limits.
-    /// - [`TextureError::TextureHeight`] when `height` is 0 or greater than GPU texture limits.
-    pub fn resize_surface(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
-        check_texture_size(&self.context.device, width, height)?;
-
-        // Update SurfaceTexture dimensions
-        self.surface_size.width = width;
-        self.surface_size.height = height;
-
-        // Update ScalingMatrix for mouse transformation
-        self.scaling_matrix_inverse = renderers::ScalingMatrix::new(
-            (
-                self.context.texture_extent.width as f32,
-                self.context.texture_extent.height as f32,
-            ),
-            (width as f32, height as f32),
-        )
-        .transform
-        .inversed();
+    /// - [`TextureError::TextureWidth`] when `width` is 0.
+    /// - [`TextureError::TextureHeight`] when `height` is 0.
+    /// - [`TextureError::TooLarge`] when `width` or `height` is greater than GPU texture limits.
+    ///
+    /// The pixel buffer is left unchanged on error.
+    pub fn resize_buffer_preserve(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        let old_width = self.context.texture_extent.width;
+        let old_pixels = std::mem::take(&mut self.pixels);
 
-        // Reconfigure the surface
-        self.reconfigure_surface();
+        // The pixel buffer is empty, so the resized buffer is entirely zeroed
+        if let Err(err) = self.resize_buffer(width, height) {
+            self.pixels = old_pixels;
+            return Err(err);
+        }
 
-        // Update state for all render passes
-        self.context
-            .scaling_renderer
-            .resize(&self.context.queue, width, height);
+        // Copy the overlapping rows
+        let bytes_per_row = |width: u32| (width as f32 * self.context.texture_format_size) as usize;
+        let old_stride = bytes_per_row(old_width);
+        let new_stride = bytes_per_row(width);
+        buffer::copy_overlap(&mut self.pixels, new_stride, &old_pixels, old_stride);
 
         Ok(())
     }
 
-    /// Enable or disable Vsync.
+    /// Draw the pixel buffer into a region of the surface, instead of the whole surface.
     ///
-    /// Vsync is enabled by default. It cannot be disabled on Web targets.
+    /// The pixel buffer is scaled to fit into the `width` by `height` region at `x`, `y` (in
+    /// physical units), just like it is normally fit onto the whole surface. This is useful for
+    /// embedding the pixel buffer in a larger layout, e.g. next to a GUI.
     ///
-    /// The `wgpu` present mode will be set to `AutoVsync` when Vsync is enabled, or `AutoNoVsync`
-    /// when Vsync is disabled. To set the present mode to `Mailbox` or another value, use the
-    /// [`Pixels::set_present_mode`] method.
-    pub fn enable_vsync(&mut self, enable_vsync: bool) {
-        self.present_mode = if enable_vsync {
-            wgpu::PresentMode::AutoVsync
-        } else {
-            wgpu::PresentMode::AutoNoVsync
-        };
-        self.reconfigure_surface();
+    /// While a viewport is set, [`ScalingRenderer::render`] does not clear the surface. Only the
+    /// scaled pixel buffer is drawn, and the rest of the surface (including the border around the
+    /// pixel buffer within the viewport) is left for the application to draw. The viewport is kept
+    /// across calls to [`Pixels::resize_surface`] and [`Pixels::resize_buffer`], and it is clamped
+    /// to the surface. [`Pixels::window_pos_to_pixel`] accounts for the viewport offset.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(800, 600, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Leave room for a 160px side panel on the left
+    /// pixels.set_viewport(160, 0, 640, 600);
+    /// assert_eq!(pixels.context().clip_rect(), (160, 60, 640, 480));
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `width` or `height` are 0.
+    pub fn set_viewport(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        assert!(width > 0);
+        assert!(height > 0);
+
+        self.set_viewport_impl(Some((x, y, width, height)));
     }
 
-    /// Get the `wgpu` present mode.
+    /// Draw the pixel buffer onto the whole surface again, undoing [`Pixels::set_viewport`].
     ///
-    /// Returns the present mode currently in use by the surface, which can be changed through
-    /// [`Pixels::enable_vsync`] or [`Pixels::set_present_mode`].
-    pub fn present_mode(&self) -> wgpu::PresentMode {
-        self.present_mode
+    /// This is the default.
+    pub fn reset_viewport(&mut self) {
+        self.set_viewport_impl(None);
     }
 
-    /// Set the `wgpu` present mode.
+    /// Get the region of the surface that the pixel buffer is fit into, as
+    /// `(x, y, width, height)`. See [`Pixels::set_viewport`].
+    pub fn viewport(&self) -> (u32, u32, u32, u32) {
+        self.context.scaling_renderer.viewport()
+    }
+
+    /// Set how the pixel buffer is scaled to fit the surface.
     ///
-    /// This differs from [`Pixels::enable_vsync`] by allowing the present mode to be set to
-    /// any value.
-    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
-        self.present_mode = present_mode;
-        self.reconfigure_surface();
+    /// See [`PixelsBuilder::scaling_mode`].
+    pub fn set_scaling_mode(&mut self, scaling_mode: ScalingMode) {
+        self.context
+            .scaling_renderer
+            .set_scaling_mode(&self.context.queue, scaling_mode);
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
     }
 
-    /// Draw this pixel buffer to the configured [`SurfaceTexture`].
+    /// Get how the pixel buffer is scaled to fit the surface.
+    pub fn scaling_mode(&self) -> ScalingMode {
+        self.context.scaling_renderer.scaling_mode
+    }
+
+    /// Set the pixel aspect ratio to simulate non-square pixels.
+    ///
+    /// See [`PixelsBuilder::pixel_aspect_ratio`].
+    ///
+    /// # Panics
+    ///
+    /// The aspect ratio must be > 0.
+    pub fn set_pixel_aspect_ratio(&mut self, pixel_aspect_ratio: f64) {
+        assert!(pixel_aspect_ratio > 0.0);
+
+        self.context
+            .scaling_renderer
+            .set_pixel_aspect_ratio(&self.context.queue, pixel_aspect_ratio);
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
+    }
+
+    /// Get the pixel aspect ratio, see [`Pixels::set_pixel_aspect_ratio`].
+    pub fn pixel_aspect_ratio(&self) -> f64 {
+        self.context.scaling_renderer.pixel_aspect_ratio
+    }
+
+    /// Replace the transformation that fits the pixel buffer onto the surface, e.g. to pan and zoom
+    /// like a magnifier.
+    ///
+    /// The pixel buffer is a quad spanning `-1.0..=1.0` on both axes, with Y pointing up. The
+    /// transform maps it into the normalized device coordinates of the
+    /// [viewport](Pixels::set_viewport). The identity matrix stretches the pixel buffer over the
+    /// whole viewport. The [`clip_rect`](PixelsContext::clip_rect) is the whole viewport, and
+    /// [`Pixels::window_pos_to_pixel`] uses the inverse transform.
+    ///
+    /// The custom transform replaces the scaling mode and pixel aspect ratio, and is kept when
+    /// resizing, until it is removed with [`Pixels::reset_scaling_matrix`].
+    ///
+    /// ```no_run
+    /// use pixels::ultraviolet::{Mat4, Vec3};
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Zoom in 2x on the center of the pixel buffer
+    /// pixels.set_scaling_matrix(Mat4::from_scale(2.0));
+    /// assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((80, 60)));
+    ///
+    /// // Pan to the top-left quarter
+    /// let pan = Mat4::from_translation(Vec3::new(0.5, -0.5, 0.0));
+    /// pixels.set_scaling_matrix(Mat4::from_scale(2.0) * pan);
+    /// assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((0, 0)));
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `transform` is not invertible.
+    pub fn set_scaling_matrix(&mut self, transform: ultraviolet::Mat4) {
+        assert!(
+            transform.determinant() != 0.0,
+            "The scaling matrix must be invertible"
+        );
+
+        self.context
+            .scaling_renderer
+            .set_custom_transform(&self.context.queue, Some(transform));
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
+    }
+
+    /// Go back to fitting the pixel buffer with the scaling mode, undoing
+    /// [`Pixels::set_scaling_matrix`].
+    ///
+    /// This is the default.
+    pub fn reset_scaling_matrix(&mut self) {
+        self.context
+            .scaling_renderer
+            .set_custom_transform(&self.context.queue, None);
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
+    }
+
+    /// Get the transformation that fits the pixel buffer onto the surface, see
+    /// [`Pixels::set_scaling_matrix`].
+    pub fn scaling_matrix(&self) -> ultraviolet::Mat4 {
+        self.context.scaling_renderer.matrix().transform
+    }
+
+    /// Draw only a region of the pixel buffer, scaled to fit the surface, e.g. to zoom into the
+    /// pixel art.
+    ///
+    /// The `width` by `height` region at `x`, `y` (in pixels) is fit onto the surface as if it was
+    /// the whole pixel buffer, with the same scaling mode and pixel aspect ratio.
+    /// [`Pixels::window_pos_to_pixel`] maps window positions back to the whole pixel buffer.
+    ///
+    /// The source rectangle is kept across calls to [`Pixels::resize_surface`], and across calls
+    /// to [`Pixels::resize_buffer`] while it still fits within the pixel buffer.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Zoom in 2x on the bottom-right quarter
+    /// pixels.set_source_rect(160, 120, 160, 120);
+    /// assert_eq!(pixels.scale_factor(), 2.0);
+    /// assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((160, 120)));
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `width` or `height` are 0, or when the region is not within the pixel buffer.
+    pub fn set_source_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let (buffer_width, buffer_height) = self.buffer_size();
+        assert!(width > 0);
+        assert!(height > 0);
+        assert!(
+            x.checked_add(width)
+                .is_some_and(|right| right <= buffer_width)
+                && y.checked_add(height)
+                    .is_some_and(|bottom| bottom <= buffer_height),
+            "The source rectangle must be within the pixel buffer"
+        );
+
+        self.context
+            .scaling_renderer
+            .set_source_rect(&self.context.queue, Some((x, y, width, height)));
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
+    }
+
+    /// Draw the whole pixel buffer again, undoing [`Pixels::set_source_rect`].
+    ///
+    /// This is the default.
+    pub fn reset_source_rect(&mut self) {
+        self.context
+            .scaling_renderer
+            .set_source_rect(&self.context.queue, None);
+        self.update_scaling_matrix_inverse();
+        self.sync_mirrors();
+    }
+
+    /// Get the region of the pixel buffer that is drawn, as `(x, y, width, height)`. See
+    /// [`Pixels::set_source_rect`].
+    pub fn source_rect(&self) -> (u32, u32, u32, u32) {
+        self.context.scaling_renderer.source_rect()
+    }
+
+    fn set_viewport_impl(&mut self, viewport: Option<(u32, u32, u32, u32)>) {
+        self.context
+            .scaling_renderer
+            .set_viewport(&self.context.queue, viewport);
+        self.update_scaling_matrix_inverse();
+    }
+
+    /// Apply the scaling settings of the main surface to the mirror surfaces.
+    fn sync_mirrors(&mut self) {
+        for mirror in &mut self.mirrors {
+            mirror
+                .scaling_renderer
+                .copy_scaling(&self.context.queue, &self.context.scaling_renderer);
+        }
+    }
+
+    /// Create a scaling renderer for a mirror surface, with the settings of the main surface.
+    fn create_mirror_renderer(&self, size: &SurfaceSize) -> ScalingRenderer {
+        let mut renderer = ScalingRenderer::new(
+            &self.context.device,
+            &self.label,
+            &self.context.texture_view,
+            &self.context.texture_extent,
+            self.context.texture.mip_level_count(),
+            size,
+            self.render_texture_format,
+            self.context.scaling_renderer.clear_color,
+            self.blend_state,
+            self.color_write_mask,
+        );
+        renderer.copy_scaling(&self.context.queue, &self.context.scaling_renderer);
+
+        renderer
+    }
+
+    /// Update the inverse scaling matrix for the current viewport and scaling settings.
+    fn update_scaling_matrix_inverse(&mut self) {
+        self.scaling_matrix_inverse = self.context.scaling_renderer.matrix().transform.inversed();
+    }
+
+    /// Resize the surface upon which the pixel buffer texture is rendered.
+    ///
+    /// This does not resize the pixel buffer. Use [`Pixels::resize_buffer`] to change the size of
+    /// the pixel buffer.
+    ///
+    /// The pixel buffer texture will be fit onto the surface texture as best as possible by scaling
+    /// to the nearest integer, e.g. 2x, 3x, 4x, etc. A border will be added around the pixel buffer
+    /// texture for non-integer scaling ratios.
+    ///
+    /// Call this method in response to a resize event from your window manager. The size expected
+    /// is in physical pixel units.
+    ///
+    /// Some platforms resize the window to 0x0 when it is minimized. When `width` or `height` are
+    /// 0, the surface is left as-is and rendering does nothing until the surface is resized again.
+    ///
+    /// # Errors
+    ///
+    /// - [`TextureError::TooLarge`] when `width` or `height` is greater than GPU texture limits.
+    pub fn resize_surface(&mut self, width: u32, height: u32) -> Result<(), TextureError> {
+        // Update SurfaceTexture dimensions
+        if width == 0 || height == 0 {
+            self.surface_size.width = width;
+            self.surface_size.height = height;
+            self.discard_pending_frame();
+
+            return Ok(());
+        }
+        check_texture_size(&self.context.device, width, height)?;
+        self.surface_size.width = width;
+        self.surface_size.height = height;
+
+        // Reconfigure the surface, or recreate the offscreen render target
+        self.discard_pending_frame();
+        self.reconfigure_surface();
+        if let RenderTarget::Offscreen(_) = self.context.render_target {
+            self.context.render_target = RenderTarget::Offscreen(builder::create_render_target(
+                &self.context.device,
+                &self.surface_size,
+                self.surface_texture_format,
+                &self.view_formats,
+            ));
+        }
+
+        // Update state for all render passes
+        self.context
+            .scaling_renderer
+            .resize(&self.context.queue, width, height);
+
+        // Update ScalingMatrix for mouse transformation
+        self.update_scaling_matrix_inverse();
+
+        Ok(())
+    }
+
+    /// Recreate the surface upon which the pixel buffer texture is rendered.
+    ///
+    /// The old surface is dropped and a new one is created for the window in `surface_texture`.
+    /// The device, queue, pixel buffer, and all textures are kept, so the contents of the pixel
+    /// buffer survive the window being destroyed and recreated.
+    ///
+    /// Call this method when the platform destroys the window out from under you, for instance in
+    /// response to a `Resumed` event following a `Suspended` event on Android.
+    ///
+    /// ```no_run
+    /// # use pixels::{Pixels, SurfaceTexture};
+    /// # let window = pixels_mocks::Window;
+    /// # let new_window = pixels_mocks::Window;
+    /// let surface_texture = SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // The window was lost and a new one has been created.
+    /// let surface_texture = SurfaceTexture::new(640, 480, &new_window);
+    /// pixels.recreate_surface(surface_texture)?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::CreateSurface`] when the new surface cannot be created.
+    /// - [`Error::InvalidTexture`] when the surface texture size is greater than GPU texture
+    ///   limits.
+    pub fn recreate_surface<W: wgpu::WindowHandle + 'win>(
+        &mut self,
+        surface_texture: SurfaceTexture<W>,
+    ) -> Result<(), Error> {
+        let SurfaceTexture { window, size } = surface_texture;
+
+        // Replacing the surface drops the old one, which must outlive its textures
+        self.discard_pending_frame();
+        let surface = self.instance.create_surface(window)?;
+        self.surface_capabilities = Some(surface.get_capabilities(&self.adapter));
+        self.context.render_target = RenderTarget::Surface(surface);
+        self.present_mode = self.supported_present_mode(self.present_mode);
+
+        // Configure the new surface and update the scaling state to match its size
+        self.resize_surface(size.width, size.height)?;
+
+        Ok(())
+    }
+
+    /// Draw the pixel buffer on another window, in addition to the main surface.
+    ///
+    /// [`Pixels::render`] draws the pixel buffer on every mirror surface, reusing the texture that
+    /// the pixel buffer is uploaded to. This is cheaper than creating a [`Pixels`] for each window,
+    /// e.g. to show the same output on several monitors. The pixel buffer is fit to each mirror
+    /// surface independently, with the scaling mode, pixel aspect ratio, custom scaling matrix,
+    /// and source rectangle of the main surface. The viewport only applies to the main surface.
+    ///
+    /// Returns the index of the mirror surface, for [`Pixels::resize_mirror_surface`] and
+    /// [`Pixels::remove_mirror_surface`].
+    ///
+    /// ```no_run
+    /// # use pixels::{Pixels, SurfaceTexture};
+    /// # let window = pixels_mocks::Window;
+    /// # let other_window = pixels_mocks::Window;
+    /// let mut pixels = Pixels::new(320, 240, SurfaceTexture::new(640, 480, &window))?;
+    /// let mirror = pixels.add_mirror_surface(SurfaceTexture::new(1920, 1080, &other_window))?;
+    ///
+    /// // Draw to both windows
+    /// pixels.render()?;
+    ///
+    /// // Call this in response to a resize event for the other window
+    /// pixels.resize_mirror_surface(mirror, 1280, 720)?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::CreateSurface`] when the surface cannot be created.
+    /// - [`Error::UnsupportedSurfaceFormat`] when the surface does not support the
+    ///   [surface texture format](Pixels::surface_texture_format).
+    /// - [`Error::InvalidTexture`] when the surface texture size is greater than GPU texture
+    ///   limits.
+    pub fn add_mirror_surface<W: wgpu::WindowHandle + 'win>(
+        &mut self,
+        surface_texture: SurfaceTexture<W>,
+    ) -> Result<usize, Error> {
+        let SurfaceTexture { window, size } = surface_texture;
+        if size.width > 0 && size.height > 0 {
+            check_texture_size(&self.context.device, size.width, size.height)?;
+        }
+
+        let surface = self.instance.create_surface(window)?;
+        let capabilities = surface.get_capabilities(&self.adapter);
+        if !capabilities.formats.contains(&self.surface_texture_format) {
+            return Err(Error::UnsupportedSurfaceFormat(self.surface_texture_format));
+        }
+
+        let mirror = Mirror::new(
+            surface,
+            capabilities,
+            &size,
+            self.surface_texture_format,
+            builder::compatible_view_formats(self.surface_texture_format, &self.view_formats),
+            self.present_mode,
+            self.create_mirror_renderer(&size),
+        );
+        mirror.configure(&self.context.device);
+        self.mirrors.push(mirror);
+
+        Ok(self.mirrors.len() - 1)
+    }
+
+    /// Resize a mirror surface added with [`Pixels::add_mirror_surface`].
+    ///
+    /// This is like [`Pixels::resize_surface`] for the main surface. The size is in physical
+    /// pixel units, and a size of 0 skips drawing the mirror until it is resized again.
+    ///
+    /// # Errors
+    ///
+    /// - [`TextureError::TooLarge`] when `width` or `height` is greater than GPU texture limits.
+    ///
+    /// # Panics
+    ///
+    /// Panics when there is no mirror surface at `index`.
+    pub fn resize_mirror_surface(
+        &mut self,
+        index: usize,
+        width: u32,
+        height: u32,
+    ) -> Result<(), TextureError> {
+        if width > 0 && height > 0 {
+            check_texture_size(&self.context.device, width, height)?;
+        }
+
+        self.mirrors[index].resize(&self.context.device, &self.context.queue, width, height);
+
+        Ok(())
+    }
+
+    /// Stop drawing on a mirror surface added with [`Pixels::add_mirror_surface`].
+    ///
+    /// The indices of the mirror surfaces added after this one are shifted down by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics when there is no mirror surface at `index`.
+    pub fn remove_mirror_surface(&mut self, index: usize) {
+        self.mirrors.remove(index);
+    }
+
+    /// The number of mirror surfaces, see [`Pixels::add_mirror_surface`].
+    pub fn mirror_surface_count(&self) -> usize {
+        self.mirrors.len()
+    }
+
+    /// Enable or disable Vsync.
+    ///
+    /// Vsync is enabled by default. It cannot be disabled on Web targets.
+    ///
+    /// The `wgpu` present mode will be set to `AutoVsync` when Vsync is enabled, or `AutoNoVsync`
+    /// when Vsync is disabled. To set the present mode to `Mailbox` or another value, use the
+    /// [`Pixels::set_present_mode`] method.
+    pub fn enable_vsync(&mut self, enable_vsync: bool) {
+        self.present_mode = if enable_vsync {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        };
+        self.discard_pending_frame();
+        self.reconfigure_surface();
+        for mirror in &mut self.mirrors {
+            mirror.set_present_mode(&self.context.device, self.present_mode);
+        }
+    }
+
+    /// Get the `wgpu` present mode.
+    ///
+    /// Returns the present mode currently in use by the surface, which can be changed through
+    /// [`Pixels::enable_vsync`] or [`Pixels::set_present_mode`].
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.present_mode
+    }
+
+    /// Set the `wgpu` present mode.
+    ///
+    /// This differs from [`Pixels::enable_vsync`] by allowing the present mode to be set to
+    /// any value that the surface supports, see [`Pixels::supported_present_modes`]. Use
+    /// [`Pixels::present_mode`] to get the present mode in use.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::PresentMode;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// if pixels.set_present_mode(PresentMode::Mailbox).is_err() {
+    ///     // Mailbox is not supported on this system
+    ///     pixels.set_present_mode(PresentMode::AutoNoVsync)?;
+    /// }
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedPresentMode`] when the surface does not support the present
+    /// mode. The present mode in use is left unchanged. The automatic modes `AutoVsync` and
+    /// `AutoNoVsync` are always supported.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) -> Result<(), Error> {
+        if self.supported_present_mode(present_mode) != present_mode {
+            return Err(Error::UnsupportedPresentMode(present_mode));
+        }
+
+        self.present_mode = present_mode;
+        self.discard_pending_frame();
+        self.reconfigure_surface();
+        for mirror in &mut self.mirrors {
+            mirror.set_present_mode(&self.context.device, present_mode);
+        }
+
+        Ok(())
+    }
+
+    /// Get the present modes that the surface supports.
+    ///
+    /// This is useful for listing only the valid choices in a settings menu, before calling
+    /// [`Pixels::set_present_mode`]. The automatic modes `AutoVsync` and `AutoNoVsync` are not
+    /// listed, but they are always supported. Offscreen pixel buffers do not present, so they
+    /// support every present mode.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu::PresentMode;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// let has_mailbox = pixels
+    ///     .supported_present_modes()
+    ///     .contains(&PresentMode::Mailbox);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn supported_present_modes(&self) -> &[wgpu::PresentMode] {
+        match &self.surface_capabilities {
+            Some(capabilities) => &capabilities.present_modes,
+            None => &[
+                wgpu::PresentMode::Fifo,
+                wgpu::PresentMode::FifoRelaxed,
+                wgpu::PresentMode::Immediate,
+                wgpu::PresentMode::Mailbox,
+            ],
+        }
+    }
+
+    /// Check the present mode against the capabilities of the surface.
+    ///
+    /// Offscreen pixel buffers do not present, so any present mode is accepted.
+    fn supported_present_mode(&self, present_mode: wgpu::PresentMode) -> wgpu::PresentMode {
+        match &self.surface_capabilities {
+            Some(capabilities) => {
+                builder::supported_present_mode(&capabilities.present_modes, present_mode)
+            }
+            None => present_mode,
+        }
+    }
+
+    /// Draw this pixel buffer to the configured [`SurfaceTexture`].
+    ///
+    /// The pixel buffer is also drawn to every surface added with [`Pixels::add_mirror_surface`].
+    /// It is uploaded to the GPU only once for all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when [`wgpu::Surface::get_current_texture`] fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Clear the pixel buffer
+    /// pixels.clear_black();
+    ///
+    /// // Draw it to the `SurfaceTexture`
+    /// pixels.render()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn render(&self) -> Result<(), Error> {
+        self.timed(|| {
+            let mut command_buffers = Vec::with_capacity(self.mirrors.len() + 1);
+            if !self.is_minimized() {
+                command_buffers.push(self.encode_with(|encoder, render_target, context| {
+                    context.scaling_renderer.render(encoder, render_target);
+
+                    Ok(())
+                })?);
+            } else if !self.mirrors.is_empty() {
+                // The mirrors still need the pixel buffer
+                self.upload_texture();
+            }
+
+            let mut mirror_frames = Vec::with_capacity(self.mirrors.len());
+            for mirror in &self.mirrors {
+                if let Some((frame, command_buffer)) =
+                    mirror.encode(&self.context.device, self.auto_reconfigure)?
+                {
+                    mirror_frames.push(frame);
+                    command_buffers.push(command_buffer);
+                }
+            }
+
+            // There is nothing to draw on while every window is minimized
+            if command_buffers.is_empty() {
+                return Ok(());
+            }
+
+            self.present(command_buffers);
+            for frame in mirror_frames {
+                frame.present();
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Draw this pixel buffer to the configured [`SurfaceTexture`] using a custom user-provided
+    /// render function.
+    ///
+    /// Provides access to a [`wgpu::CommandEncoder`], a [`wgpu::TextureView`] from the surface
+    /// which you can use to render to the screen, and a [`PixelsContext`] with all of the internal
+    /// `wgpu` context.
+    ///
+    /// The render function must return a `Result`. This allows fallible render functions to be
+    /// handled gracefully. The boxed `Error` will be made available in the [`Error::UserDefined`]
+    /// variant returned by `render_with()`.
+    ///
+    /// Only the main surface is drawn. Surfaces added with [`Pixels::add_mirror_surface`] are
+    /// drawn by [`Pixels::render`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either [`wgpu::Surface::get_current_texture`] or the provided render
+    /// function fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Clear the pixel buffer
+    /// pixels.clear_black();
+    ///
+    /// // Draw it to the `SurfaceTexture`
+    /// pixels.render_with(|encoder, render_target, context| {
+    ///     context.scaling_renderer.render(encoder, render_target);
+    ///     // etc...
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn render_with<F>(&self, render_function: F) -> Result<(), Error>
+    where
+        F: FnOnce(
+            &mut wgpu::CommandEncoder,
+            &wgpu::TextureView,
+            &PixelsContext,
+        ) -> Result<(), DynError>,
+    {
+        self.timed(|| {
+            // There is nothing to draw on while the window is minimized
+            if self.is_minimized() {
+                return Ok(());
+            }
+
+            let command_buffer = self.encode_with(render_function)?;
+            self.present(Some(command_buffer));
+
+            Ok(())
+        })
+    }
+
+    /// Draw the texture to the configured [`SurfaceTexture`] with a custom user-provided render
+    /// function, without uploading the pixel buffer first.
+    ///
+    /// This is like [`Pixels::render_with`], for custom pipelines that fill the texture in
+    /// [`PixelsContext::texture`] themselves, e.g. with a compute pass or a copy from another
+    /// texture. Changes made with [`Pixels::frame_mut`] do not appear until the pixel buffer is
+    /// uploaded by [`Pixels::render`]. Mipmaps are not generated either, see
+    /// [`PixelsBuilder::generate_mipmaps`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either [`wgpu::Surface::get_current_texture`] or the provided render
+    /// function fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// pixels.render_with_no_upload(|encoder, render_target, context| {
+    ///     // Fill `context.texture` with a compute pass, etc...
+    ///     context.scaling_renderer.render(encoder, render_target);
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn render_with_no_upload<F>(&self, render_function: F) -> Result<(), Error>
+    where
+        F: FnOnce(
+            &mut wgpu::CommandEncoder,
+            &wgpu::TextureView,
+            &PixelsContext,
+        ) -> Result<(), DynError>,
+    {
+        self.timed(|| {
+            // There is nothing to draw on while the window is minimized
+            if self.is_minimized() {
+                return Ok(());
+            }
+
+            let command_buffer = self.encode(false, render_function)?;
+            self.present(Some(command_buffer));
+
+            Ok(())
+        })
+    }
+
+    /// Draw this pixel buffer to the configured [`SurfaceTexture`], and wait for the GPU to finish.
+    ///
+    /// This is like [`Pixels::render`], but the returned future resolves only once the GPU has
+    /// finished all of the submitted work. It is meant for frame pacing on Web targets, where the
+    /// browser reports the completed work asynchronously. On native targets, awaiting the future
+    /// blocks the current thread until the GPU is done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when [`wgpu::Surface::get_current_texture`] fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn test() -> Result<(), pixels::Error> {
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Draw it to the `SurfaceTexture`, and measure how long the GPU took
+    /// let start = std::time::Instant::now();
+    /// pixels.render_async().await?;
+    /// let elapsed = start.elapsed();
+    /// # Ok::<(), pixels::Error>(())
+    /// # }
+    /// ```
+    pub async fn render_async(&self) -> Result<(), Error> {
+        self.render()?;
+        SubmittedWorkDone::new(&self.context).await;
+
+        Ok(())
+    }
+
+    /// Encode the commands to draw this pixel buffer with a custom user-provided render function,
+    /// without submitting them.
+    ///
+    /// This is the first half of [`Pixels::render_with`]. The returned command buffer must be
+    /// given to [`Pixels::present`], optionally along with any other command buffers sharing the
+    /// queue. This allows interleaving other `wgpu` work, or encoding several passes before
+    /// presenting once.
+    ///
+    /// The surface texture is acquired by the first call, and held until [`Pixels::present`].
+    /// Calling this method again before presenting encodes more commands for the same surface
+    /// texture. Resizing the surface or changing the present mode in between discards the surface
+    /// texture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when either [`wgpu::Surface::get_current_texture`] or the provided render
+    /// function fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// let command_buffer = pixels.encode_with(|encoder, render_target, context| {
+    ///     context.scaling_renderer.render(encoder, render_target);
+    ///     Ok(())
+    /// })?;
+    ///
+    /// // Submit the commands and present the surface texture
+    /// pixels.present([command_buffer]);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn encode_with<F>(&self, render_function: F) -> Result<wgpu::CommandBuffer, Error>
+    where
+        F: FnOnce(
+            &mut wgpu::CommandEncoder,
+            &wgpu::TextureView,
+            &PixelsContext,
+        ) -> Result<(), DynError>,
+    {
+        self.encode(true, render_function)
+    }
+
+    /// Acquire the render target and encode the render function, optionally uploading the pixel
+    /// buffer first.
+    fn encode<F>(&self, upload: bool, render_function: F) -> Result<wgpu::CommandBuffer, Error>
+    where
+        F: FnOnce(
+            &mut wgpu::CommandEncoder,
+            &wgpu::TextureView,
+            &PixelsContext,
+        ) -> Result<(), DynError>,
+    {
+        let view = {
+            let mut frame = self.pending_frame();
+            let texture = match &self.context.render_target {
+                RenderTarget::Surface(surface) => {
+                    if frame.is_none() {
+                        *frame = Some(surface.get_current_texture().or_else(|err| {
+                            if !self.auto_reconfigure {
+                                return Err(err);
+                            }
+
+                            // Reconfigure the surface and retry immediately on any error.
+                            // See https://github.com/parasyte/pixels/issues/121
+                            // See https://github.com/parasyte/pixels/issues/346
+                            self.reconfigure_surface();
+                            surface.get_current_texture()
+                        })?);
+                    }
+
+                    &frame.as_ref().unwrap().texture
+                }
+                RenderTarget::Offscreen(texture) => texture,
+            };
+
+            texture.create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        let mut encoder =
+            self.context
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("pixels_command_encoder"),
+                });
+        if upload {
+            self.upload_texture();
+        }
+
+        // Call the user's render function.
+        (render_function)(&mut encoder, &view, &self.context)?;
+
+        Ok(encoder.finish())
+    }
+
+    /// Queue the pixel buffer to be copied to its texture with the next submission.
+    ///
+    /// Mipmaps are generated from the new contents right away, when they are enabled. The
+    /// callback registered with [`Pixels::set_frame_callback`] is called last.
+    fn upload_texture(&self) {
+        let bytes_per_row =
+            (self.context.texture_extent.width as f32 * self.context.texture_format_size) as u32;
+        let mut scratch = self.premultiply_scratch.lock().unwrap();
+        self.context.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.context.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            self.upload_data(&self.pixels, &mut scratch),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(self.context.texture_extent.height),
+            },
+            self.context.texture_extent,
+        );
+        drop(scratch);
+
+        self.generate_mipmaps();
+
+        // A callback that panicked is still usable
+        let mut frame_callback = self
+            .frame_callback
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(frame_callback) = frame_callback.as_mut() {
+            let extent = self.context.texture_extent;
+            frame_callback(&self.pixels, extent.width, extent.height);
+        }
+    }
+
+    /// Submit command buffers to the queue, and present the surface texture.
+    ///
+    /// This is the second half of [`Pixels::render_with`], see [`Pixels::encode_with`]. Offscreen
+    /// pixel buffers have nothing to present, so the command buffers are only submitted.
+    pub fn present<I>(&self, command_buffers: I)
+    where
+        I: IntoIterator<Item = wgpu::CommandBuffer>,
+    {
+        self.context.queue.submit(command_buffers);
+        if let Some(frame) = self.pending_frame().take() {
+            frame.present();
+        }
+    }
+
+    /// Register a render function that can be selected by `id` with [`Pixels::render_with_pass`].
+    ///
+    /// This allows switching between effects at runtime, while the render functions (and the GPU
+    /// resources they own) are kept by the pixel buffer. A render pass registered with an existing
+    /// `id` replaces the old one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// pixels.add_render_pass(
+    ///     "none",
+    ///     Box::new(|encoder, render_target, context| {
+    ///         context.scaling_renderer.render(encoder, render_target);
+    ///         Ok(())
+    ///     }),
+    /// );
+    /// pixels.add_render_pass(
+    ///     "scanlines",
+    ///     Box::new(|encoder, render_target, context| {
+    ///         context.scaling_renderer.render(encoder, render_target);
+    ///         // Draw scanlines over the scaled pixel buffer...
+    ///         Ok(())
+    ///     }),
+    /// );
+    ///
+    /// // Pick the effect for each frame
+    /// let effect = "scanlines";
+    /// pixels.render_with_pass(effect)?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn add_render_pass(&mut self, id: impl Into<String>, render_pass: RenderPass<'win>) {
+        self.render_passes.0.insert(id.into(), render_pass);
+    }
+
+    /// Unregister the render function added by [`Pixels::add_render_pass`] with the given `id`,
+    /// and return it.
+    pub fn remove_render_pass(&mut self, id: &str) -> Option<RenderPass<'win>> {
+        self.render_passes.0.remove(id)
+    }
+
+    /// Draw this pixel buffer with the render function registered as `id`.
+    ///
+    /// This is the same as calling [`Pixels::render_with`] with the render function given to
+    /// [`Pixels::add_render_pass`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownRenderPass`] when no render function is registered as `id`, or an
+    /// error when either [`wgpu::Surface::get_current_texture`] or the render function fails.
+    pub fn render_with_pass(&self, id: &str) -> Result<(), Error> {
+        let render_pass = self
+            .render_passes
+            .0
+            .get(id)
+            .ok_or_else(|| Error::UnknownRenderPass(id.to_owned()))?;
+
+        self.render_with(render_pass)
+    }
+
+    /// Set a function to be called with the pixel buffer each time it is drawn, or `None` to
+    /// remove it.
+    ///
+    /// The function is called by [`Pixels::render`], [`Pixels::render_with`], and the other
+    /// methods that upload the pixel buffer to its texture, with the same bytes as
+    /// [`Pixels::frame`] and the width and height of the pixel buffer. This allows recording or
+    /// streaming the frames without changing the render loop. [`Pixels::render_with_no_upload`]
+    /// does not call it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// let mut recording = Vec::new();
+    /// pixels.set_frame_callback(Some(Box::new(move |frame, width, height| {
+    ///     // Encode the frame with a video encoder, etc...
+    ///     recording.push((width, height, frame.to_vec()));
+    /// })));
+    ///
+    /// pixels.render()?;
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn set_frame_callback(&mut self, frame_callback: Option<FrameCallback<'win>>) {
+        *self
+            .frame_callback
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = frame_callback;
+    }
+
+    /// Get the CPU time spent by the last call to [`Pixels::render`], [`Pixels::render_with`], or
+    /// the other render methods.
+    ///
+    /// This is the time taken to encode and submit the commands, and present the surface texture.
+    /// It does not include the time that the GPU spends drawing, which is asynchronous. It is zero
+    /// until the first render.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// pixels.render()?;
+    /// println!("Rendered in {:?}", pixels.last_render_time());
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    #[cfg(feature = "render-timing")]
+    pub fn last_render_time(&self) -> std::time::Duration {
+        *self.last_render_time.lock().unwrap()
+    }
+
+    /// Call a render method, and record the time it took for [`Pixels::last_render_time`].
+    #[cfg(feature = "render-timing")]
+    fn timed<T>(&self, render: impl FnOnce() -> T) -> T {
+        let stopwatch = Stopwatch::start();
+        let result = render();
+        *self.last_render_time.lock().unwrap() = stopwatch.elapsed();
+
+        result
+    }
+
+    #[cfg(not(feature = "render-timing"))]
+    fn timed<T>(&self, render: impl FnOnce() -> T) -> T {
+        render()
+    }
+
+    /// Lock the surface texture held between [`Pixels::encode_with`] and [`Pixels::present`].
+    fn pending_frame(&self) -> MutexGuard<'_, Option<wgpu::SurfaceTexture>> {
+        // The lock is never held across user code, so it cannot be poisoned
+        self.pending_frame.lock().unwrap()
+    }
+
+    /// Drop the surface texture acquired by [`Pixels::encode_with`] without presenting it.
+    ///
+    /// The surface cannot be reconfigured while a texture is acquired.
+    fn discard_pending_frame(&mut self) {
+        if let Ok(frame) = self.pending_frame.get_mut() {
+            frame.take();
+        }
+    }
+
+    /// Reconfigure the surface.
+    ///
+    /// Call this when the surface or presentation mode needs to be changed.
+    pub(crate) fn reconfigure_surface(&self) {
+        // A zero-sized surface cannot be configured
+        if self.is_minimized() {
+            return;
+        }
+
+        if let RenderTarget::Surface(surface) = &self.context.render_target {
+            surface.configure(
+                &self.context.device,
+                &wgpu::SurfaceConfiguration {
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    format: self.surface_texture_format,
+                    width: self.surface_size.width,
+                    height: self.surface_size.height,
+                    present_mode: self.present_mode,
+                    desired_maximum_frame_latency: 2,
+                    alpha_mode: self.alpha_mode,
+                    view_formats: builder::compatible_view_formats(
+                        self.surface_texture_format,
+                        &self.view_formats,
+                    ),
+                },
+            );
+        }
+    }
+
+    /// Check if the surface has been resized to zero, e.g. because the window is minimized.
+    fn is_minimized(&self) -> bool {
+        self.surface_size.width == 0 || self.surface_size.height == 0
+    }
+
+    /// Upload a rectangular region directly to the pixel buffer texture.
+    ///
+    /// This is a low-level alternative to [`Pixels::frame_mut`] for callers that track their own
+    /// changes. `data` is the tightly packed region, in the same layout and channel order as
+    /// [`Pixels::frame`]. The upload is queued and happens with the next submission to the GPU.
+    ///
+    /// The pixel buffer is not updated, and [`Pixels::render`] uploads the entire pixel buffer,
+    /// replacing the region. Render with [`Pixels::context`] or a custom render pass to draw only
+    /// what has been written with this method.
+    ///
+    /// With [`PixelsBuilder::generate_mipmaps`], every mip level is regenerated after the region is
+    /// written, like after uploading the whole pixel buffer.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// let pixels = PixelsBuilder::new_offscreen(320, 240, 640, 480).build()?;
+    ///
+    /// // Fill a 4x4 region at (8, 16) with white
+    /// pixels.write_region(8, 16, 4, 4, &[0xff; 4 * 4 * 4]);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when the region does not fit within the pixel buffer, or when `data` is not exactly
+    /// the size of the region.
+    pub fn write_region(&self, x: u32, y: u32, width: u32, height: u32, data: &[u8]) {
+        let extent = self.context.texture_extent;
+        assert!(
+            x.checked_add(width)
+                .is_some_and(|right| right <= extent.width)
+                && y.checked_add(height)
+                    .is_some_and(|bottom| bottom <= extent.height),
+            "region does not fit within the pixel buffer"
+        );
+
+        let bytes_per_row = (width as f32 * self.context.texture_format_size) as u32;
+        assert_eq!(
+            data.len(),
+            bytes_per_row as usize * height as usize,
+            "region size does not match its dimensions"
+        );
+
+        let mut scratch = self.premultiply_scratch.lock().unwrap();
+        self.context.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.context.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            self.upload_data(data, &mut scratch),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        drop(scratch);
+
+        self.generate_mipmaps();
+    }
+
+    /// Regenerate the mip levels of the texture from the uploaded pixel buffer, when enabled with
+    /// [`PixelsBuilder::generate_mipmaps`].
+    ///
+    /// The mipmaps are rendered in a separate submission, after any queued texture writes.
+    fn generate_mipmaps(&self) {
+        if let Some(mipmap_generator) = &self.mipmap_generator {
+            let command_buffer =
+                mipmap_generator.generate(&self.context.device, &self.context.texture);
+            self.context.queue.submit([command_buffer]);
+        }
+    }
+
+    /// Get the bytes to upload to the texture for `data`.
+    ///
+    /// With [`Pixels::set_premultiply_alpha`], this is a premultiplied copy in `scratch`.
+    fn upload_data<'a>(&self, data: &'a [u8], scratch: &'a mut Vec<u8>) -> &'a [u8] {
+        if !self.premultiply_alpha {
+            return data;
+        }
+
+        let premultiply = match self.context.texture_format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Bgra8Unorm => {
+                color::premultiply_alpha
+            }
+            wgpu::TextureFormat::Rgba8UnormSrgb | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                color::premultiply_alpha_srgb
+            }
+            _ => return data,
+        };
+        scratch.clear();
+        scratch.extend_from_slice(data);
+        premultiply(scratch);
+
+        scratch
+    }
+
+    /// Read the pixel buffer texture back from the GPU.
+    ///
+    /// The returned bytes are the contents of the pixel buffer as of the last call to
+    /// [`Pixels::render`] or [`Pixels::render_with`], in the same layout as [`Pixels::frame`].
+    /// This is mostly useful for tests and screenshots, especially with an offscreen pixel buffer
+    /// created by [`PixelsBuilder::new_offscreen`].
+    ///
+    /// This method blocks the current thread until the GPU has finished all submitted work, making
+    /// it unusable on Web targets.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// let mut pixels = PixelsBuilder::new_offscreen(320, 240, 640, 480).build()?;
+    ///
+    /// pixels.clear([0xff, 0x00, 0xff, 0xff]);
+    /// pixels.render()?;
+    ///
+    /// assert_eq!(pixels.read_frame()?, pixels.frame());
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the readback buffer cannot be mapped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_frame(&self) -> Result<Vec<u8>, Error> {
+        self.read_texture(&self.context.texture, self.context.texture_format_size)
+    }
+
+    /// Read the scaled pixel buffer back from the GPU, as it appears on the surface.
+    ///
+    /// Unlike [`Pixels::read_frame`], the returned image is the size of the surface and includes
+    /// the integer scaling and the border around the pixel buffer, matching what the user sees.
+    /// The contents are as of the last call to [`Pixels::render`] or [`Pixels::render_with`].
+    ///
+    /// For an offscreen pixel buffer, this reads the render target, including anything drawn to it
+    /// by [`Pixels::render_with`]. A window surface cannot be read, so the default
+    /// [`ScalingRenderer`] draws to an intermediate texture instead. Custom render passes are not
+    /// included in that case.
+    ///
+    /// The bytes are in the channel order of the render texture format, which is often BGRA for
+    /// window surfaces. See [`Pixels::render_texture_format`] and [`color::ChannelOrder`].
+    ///
+    /// This method blocks the current thread until the GPU has finished all submitted work, making
+    /// it unusable on Web targets. The image is empty while the surface is minimized.
+    ///
+    /// ```no_run
+    /// # use pixels::PixelsBuilder;
+    /// let mut pixels = PixelsBuilder::new_offscreen(320, 240, 640, 480).build()?;
+    /// pixels.render()?;
+    ///
+    /// let screenshot = pixels.read_surface()?;
+    /// assert_eq!(screenshot.len(), 640 * 480 * 4);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error when [`wgpu::Surface::get_current_texture`] fails.
+    /// Returns an error when the readback buffer cannot be mapped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_surface(&self) -> Result<Vec<u8>, Error> {
+        // A zero-sized render target cannot be created
+        if self.is_minimized() {
+            return Ok(Vec::new());
+        }
+
+        match &self.context.render_target {
+            RenderTarget::Offscreen(texture) => {
+                self.read_texture(texture, builder::texture_format_size(texture.format()))
+            }
+            RenderTarget::Surface(_) => {
+                let texture = builder::create_render_target(
+                    &self.context.device,
+                    &self.surface_size,
+                    self.render_texture_format,
+                    &[],
+                );
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+                let mut encoder =
+                    self.context
+                        .device
+                        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                            label: Some("pixels_read_surface_encoder"),
+                        });
+                self.context.scaling_renderer.render(&mut encoder, &view);
+                self.context.queue.submit(Some(encoder.finish()));
+
+                self.read_texture(&texture, builder::texture_format_size(texture.format()))
+            }
+        }
+    }
+
+    /// Copy a texture into a tightly packed byte vector, blocking until the copy is complete.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_texture(&self, texture: &wgpu::Texture, texel_size: f32) -> Result<Vec<u8>, Error> {
+        let device = &self.context.device;
+        let extent = texture.size();
+
+        // Rows in the readback buffer must be padded to the copy alignment
+        let bytes_per_row = (extent.width as f32 * texel_size) as u32;
+        let padded_bytes_per_row =
+            bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("pixels_readback_buffer"),
+            size: padded_bytes_per_row as u64 * extent.height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("pixels_readback_encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(extent.height),
+                },
+            },
+            extent,
+        );
+        self.context.queue.submit(Some(encoder.finish()));
+
+        // Wait for the copy to finish and map the buffer
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        // Strip the row padding
+        let data = slice.get_mapped_range();
+        let mut bytes = Vec::with_capacity(bytes_per_row as usize * extent.height as usize);
+        for row in data.chunks_exact(padded_bytes_per_row as usize) {
+            bytes.extend_from_slice(&row[..bytes_per_row as usize]);
+        }
+        drop(data);
+        buffer.unmap();
+
+        Ok(bytes)
+    }
+
+    /// Get a mutable byte slice for the pixel buffer. The buffer is _not_ cleared for you; it will
+    /// retain the previous frame's contents until you clear it yourself.
+    ///
+    /// # Channel order
     ///
-    /// # Example
+    /// The bytes are in the channel order of the
+    /// [texture format](PixelsBuilder::texture_format). This is RGBA for the default format, but
+    /// it is BGRA for formats like `Bgra8UnormSrgb`. Writing RGBA colors to a BGRA pixel buffer
+    /// swaps red and blue on screen! Check [`Pixels::format_channel_order`] and convert colors with
+    /// [`ChannelOrder::from_rgba`](color::ChannelOrder::from_rgba) if the texture format is
+    /// configurable.
+    pub fn frame_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    /// Get a mutable slice of packed pixels for the pixel buffer, one `u32` per pixel.
+    ///
+    /// Writing whole pixels at once is faster than writing each byte of [`Pixels::frame_mut`].
+    ///
+    /// # Channel order
+    ///
+    /// Each `u32` holds the bytes of one pixel in native byte order. The bytes are in the channel
+    /// order of the texture format, like [`Pixels::frame_mut`]. For the default RGBA format on a
+    /// little-endian CPU, this means that colors are packed as `0xAABBGGRR`. Build the packed
+    /// color with [`u32::from_ne_bytes`] to get it right on any CPU:
     ///
     /// ```no_run
     /// # use pixels::Pixels;
@@ -445,144 +2313,177 @@ impl<'win> Pixels<'win> {
     /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
     /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
     ///
-    /// // Clear the pixel buffer
-    /// let frame = pixels.frame_mut();
-    /// for pixel in frame.chunks_exact_mut(4) {
-    ///     pixel[0] = 0x00; // R
-    ///     pixel[1] = 0x00; // G
-    ///     pixel[2] = 0x00; // B
-    ///     pixel[3] = 0xff; // A
-    /// }
-    ///
-    /// // Draw it to the `SurfaceTexture`
-    /// pixels.render()?;
+    /// let order = pixels.format_channel_order();
+    /// let red = u32::from_ne_bytes(order.from_rgba([0xff, 0x00, 0x00, 0xff]));
+    /// pixels.frame_u32_mut()[0] = red;
     /// # Ok::<(), pixels::Error>(())
     /// ```
-    pub fn render(&self) -> Result<(), Error> {
-        self.render_with(|encoder, render_target, context| {
-            context.scaling_renderer.render(encoder, render_target);
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texture format is not 4 bytes per pixel, or if the pixel buffer is not
+    /// aligned to 4 bytes in memory.
+    pub fn frame_u32_mut(&mut self) -> &mut [u32] {
+        assert_eq!(
+            self.context.texture_format_size, 4.0,
+            "frame_u32_mut requires a texture format with 4 bytes per pixel"
+        );
 
-            Ok(())
-        })
+        buffer::words_mut(&mut self.pixels)
     }
 
-    /// Draw this pixel buffer to the configured [`SurfaceTexture`] using a custom user-provided
-    /// render function.
+    /// Fill the pixel buffer with a single color.
     ///
-    /// Provides access to a [`wgpu::CommandEncoder`], a [`wgpu::TextureView`] from the surface
-    /// which you can use to render to the screen, and a [`PixelsContext`] with all of the internal
-    /// `wgpu` context.
+    /// The `rgba` color is given in RGBA order, and it is swizzled for BGRA texture formats. This
+    /// is unrelated to [`Pixels::clear_color`], which only sets the color of the border around the
+    /// pixel buffer on the surface.
     ///
-    /// The render function must return a `Result`. This allows fallible render functions to be
-    /// handled gracefully. The boxed `Error` will be made available in the [`Error::UserDefined`]
-    /// variant returned by `render_with()`.
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
     ///
-    /// # Errors
+    /// // Fill the pixel buffer with a dark blue
+    /// pixels.clear([0x00, 0x00, 0x40, 0xff]);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
     ///
-    /// Returns an error when either [`wgpu::Surface::get_current_texture`] or the provided render
-    /// function fails.
+    /// # Panics
     ///
-    /// # Example
+    /// Panics if the texture format is not 4 bytes per pixel.
+    pub fn clear(&mut self, rgba: [u8; 4]) {
+        assert_eq!(
+            self.context.texture_format_size, 4.0,
+            "clear requires a texture format with 4 bytes per pixel"
+        );
+
+        let color = self.format_channel_order().from_rgba(rgba);
+
+        // Filling whole words beats copying each pixel (especially in debug builds), but it
+        // requires an aligned buffer
+        match bytemuck::try_cast_slice_mut::<_, u32>(&mut self.pixels) {
+            Ok(words) => words.fill(u32::from_ne_bytes(color)),
+            Err(_) => {
+                for pixel in self.pixels.chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    /// Draw a [`Sprite`] into the pixel buffer with its top-left corner at `x`, `y`.
+    ///
+    /// Sprites that are partially outside of the pixel buffer are clipped. Fully transparent
+    /// sprite pixels (alpha 0) are skipped, and all others replace the pixels in the buffer; use
+    /// [`color::blend_srgb`] for translucency. Colors are converted to the channel order of the
+    /// texture format.
     ///
     /// ```no_run
+    /// use pixels::Sprite;
+    ///
     /// # use pixels::Pixels;
     /// # let window = pixels_mocks::Window;
     /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
     /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    /// let sprite = Sprite::from_rgba(16, 16, vec![0xff; 16 * 16 * 4]);
     ///
-    /// // Clear the pixel buffer
-    /// let frame = pixels.frame_mut();
-    /// for pixel in frame.chunks_exact_mut(4) {
-    ///     pixel[0] = 0x00; // R
-    ///     pixel[1] = 0x00; // G
-    ///     pixel[2] = 0x00; // B
-    ///     pixel[3] = 0xff; // A
-    /// }
-    ///
-    /// // Draw it to the `SurfaceTexture`
-    /// pixels.render_with(|encoder, render_target, context| {
-    ///     context.scaling_renderer.render(encoder, render_target);
-    ///     // etc...
-    ///     Ok(())
-    /// })?;
+    /// // Half of the sprite hangs off the left edge
+    /// pixels.blit(-8, 100, &sprite);
     /// # Ok::<(), pixels::Error>(())
     /// ```
-    pub fn render_with<F>(&self, render_function: F) -> Result<(), Error>
-    where
-        F: FnOnce(
-            &mut wgpu::CommandEncoder,
-            &wgpu::TextureView,
-            &PixelsContext,
-        ) -> Result<(), DynError>,
-    {
-        let frame = self.context.surface.get_current_texture().or_else(|_| {
-            // Reconfigure the surface and retry immediately on any error.
-            // See https://github.com/parasyte/pixels/issues/121
-            // See https://github.com/parasyte/pixels/issues/346
-            self.reconfigure_surface();
-            self.context.surface.get_current_texture()
-        })?;
-        let mut encoder =
-            self.context
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: Some("pixels_command_encoder"),
-                });
-
-        // Update the pixel buffer texture view
-        let bytes_per_row =
-            (self.context.texture_extent.width as f32 * self.context.texture_format_size) as u32;
-        self.context.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &self.context.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                aspect: wgpu::TextureAspect::All,
-            },
-            &self.pixels,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(bytes_per_row),
-                rows_per_image: Some(self.context.texture_extent.height),
-            },
-            self.context.texture_extent,
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texture format is not 4 bytes per pixel.
+    pub fn blit(&mut self, x: i32, y: i32, sprite: &Sprite) {
+        assert_eq!(
+            self.context.texture_format_size, 4.0,
+            "blit requires a texture format with 4 bytes per pixel"
         );
 
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let order = self.format_channel_order();
+        let width = self.context.texture_extent.width;
+        sprite.blit(&mut self.pixels, width, (x, y), order);
+    }
 
-        // Call the user's render function.
-        (render_function)(&mut encoder, &view, &self.context)?;
+    /// Copy a rectangular region of the pixel buffer to another position in the pixel buffer.
+    ///
+    /// `src_rect` is the `(x, y, width, height)` of the region to copy, and `dst` is the top-left
+    /// corner to copy it to. The regions may overlap, e.g. for scrolling the contents of the
+    /// pixel buffer, and the copy is correct in any direction. Pixels outside of the destination
+    /// region are left untouched.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Scroll everything up by one row, leaving the bottom row to be redrawn
+    /// pixels.copy_within_buffer((0, 1, 320, 239), (0, 0));
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when the source or destination region does not fit within the pixel buffer, or if
+    /// the texture format is compressed.
+    pub fn copy_within_buffer(&mut self, src_rect: (u32, u32, u32, u32), dst: (u32, u32)) {
+        let (src_x, src_y, width, height) = src_rect;
+        let (dst_x, dst_y) = dst;
+        let extent = self.context.texture_extent;
+        let fits = |x: u32, y: u32| {
+            x.checked_add(width)
+                .is_some_and(|right| right <= extent.width)
+                && y.checked_add(height)
+                    .is_some_and(|bottom| bottom <= extent.height)
+        };
+        assert!(
+            fits(src_x, src_y),
+            "source region does not fit within the pixel buffer"
+        );
+        assert!(
+            fits(dst_x, dst_y),
+            "destination region does not fit within the pixel buffer"
+        );
 
-        self.context.queue.submit(Some(encoder.finish()));
-        frame.present();
-        Ok(())
+        let bytes_per_pixel = self.context.texture_format_size;
+        assert!(
+            bytes_per_pixel >= 1.0,
+            "copy_within_buffer requires an uncompressed texture format"
+        );
+        let bytes_per_pixel = bytes_per_pixel as usize;
+        let stride = extent.width as usize * bytes_per_pixel;
+        buffer::copy_within(&mut self.pixels, stride, bytes_per_pixel, src_rect, dst);
     }
 
-    /// Reconfigure the surface.
+    /// Get the channel order of the pixel buffer's texture format.
     ///
-    /// Call this when the surface or presentation mode needs to be changed.
-    pub(crate) fn reconfigure_surface(&self) {
-        self.context.surface.configure(
-            &self.context.device,
-            &wgpu::SurfaceConfiguration {
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                format: self.surface_texture_format,
-                width: self.surface_size.width,
-                height: self.surface_size.height,
-                present_mode: self.present_mode,
-                desired_maximum_frame_latency: 2,
-                alpha_mode: self.alpha_mode,
-                view_formats: vec![],
-            },
-        );
+    /// ```no_run
+    /// use pixels::color::ChannelOrder;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    /// let order = pixels.format_channel_order();
+    /// assert_eq!(order, ChannelOrder::Rgba);
+    ///
+    /// // Write an RGBA color in the right order for any texture format
+    /// let red = order.from_rgba([0xff, 0x00, 0x00, 0xff]);
+    /// pixels.frame_mut()[..4].copy_from_slice(&red);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn format_channel_order(&self) -> color::ChannelOrder {
+        color::ChannelOrder::from_format(self.context.texture_format)
     }
 
-    /// Get a mutable byte slice for the pixel buffer. The buffer is _not_ cleared for you; it will
-    /// retain the previous frame's contents until you clear it yourself.
-    pub fn frame_mut(&mut self) -> &mut [u8] {
-        &mut self.pixels
+    /// Fill the pixel buffer with opaque black.
+    ///
+    /// This is shorthand for `pixels.clear([0x00, 0x00, 0x00, 0xff])`. See [`Pixels::clear`].
+    pub fn clear_black(&mut self) {
+        self.clear([0x00, 0x00, 0x00, 0xff]);
     }
 
     /// Get an immutable byte slice for the pixel buffer.
@@ -593,6 +2494,82 @@ impl<'win> Pixels<'win> {
         &self.pixels
     }
 
+    /// Get an iterator over each pixel in the pixel buffer, yielding its `(x, y)` coordinates
+    /// alongside a mutable reference to its bytes.
+    ///
+    /// Pixels are visited in row-major order, just like [`Pixels::frame_mut`]. The buffer is
+    /// _not_ cleared for you.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Draw a horizontal gradient
+    /// for (x, _y, pixel) in pixels.frame_chunks_mut() {
+    ///     let shade = (x * 255 / 319) as u8;
+    ///     *pixel = [shade, shade, shade, 0xff];
+    /// }
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texture format is not 4 bytes per pixel.
+    pub fn frame_chunks_mut(&mut self) -> impl Iterator<Item = (u32, u32, &mut [u8; 4])> {
+        assert_eq!(
+            self.context.texture_format_size, 4.0,
+            "frame_chunks_mut requires a texture format with 4 bytes per pixel"
+        );
+        let width = self.context.texture_extent.width;
+
+        self.pixels
+            .chunks_exact_mut(4)
+            .zip(0..)
+            .map(move |(pixel, i)| (i % width, i / width, pixel.try_into().unwrap()))
+    }
+
+    /// Get a mutable view of the `(x, y, width, height)` region of the pixel buffer.
+    ///
+    /// The [`RegionMut`] only allows drawing within the region, with coordinates relative to its
+    /// top-left corner. This is safer than handing out [`Pixels::frame_mut`] to code that should
+    /// only draw into part of the pixel buffer, like independent widgets composited into one
+    /// frame. The region is clamped to the pixel buffer.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // A status bar along the bottom of the pixel buffer
+    /// let mut status_bar = pixels.frame_region_mut(0, 224, 320, 16);
+    /// for row in status_bar.rows_mut() {
+    ///     row.fill(0x40);
+    /// }
+    /// status_bar.set_pixel(0, 0, &[0xff, 0xff, 0xff, 0xff]);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texture format is compressed.
+    pub fn frame_region_mut(&mut self, x: u32, y: u32, width: u32, height: u32) -> RegionMut<'_> {
+        let bytes_per_pixel = self.context.texture_format_size;
+        assert!(
+            bytes_per_pixel >= 1.0,
+            "frame_region_mut requires an uncompressed texture format"
+        );
+
+        RegionMut::new(
+            &mut self.pixels,
+            self.context.texture_extent.width,
+            bytes_per_pixel as usize,
+            (x, y, width, height),
+        )
+    }
+
     /// Calculate the pixel location from a physical location on the window,
     /// dealing with window resizing, scaling, and margins. Takes a physical
     /// position (x, y) within the window, and returns a pixel position (x, y).
@@ -624,25 +2601,9 @@ impl<'win> Pixels<'win> {
         &self,
         physical_position: (f32, f32),
     ) -> Result<(usize, usize), (isize, isize)> {
-        let physical_width = self.surface_size.width as f32;
-        let physical_height = self.surface_size.height as f32;
-
-        let pixels_width = self.context.texture_extent.width as f32;
-        let pixels_height = self.context.texture_extent.height as f32;
-
-        let pos = ultraviolet::Vec4::new(
-            (physical_position.0 / physical_width - 0.5) * pixels_width,
-            (physical_position.1 / physical_height - 0.5) * pixels_height,
-            0.0,
-            1.0,
-        );
-
-        let pos = self.scaling_matrix_inverse * pos;
-        let offset_width = pixels_width.min(physical_width) / 2.0;
-        let offset_height = pixels_height.min(physical_height) / 2.0;
-
-        let pixel_x = (pos.x / pos.w + offset_width).floor() as isize;
-        let pixel_y = (pos.y / pos.w + offset_height).floor() as isize;
+        let (pixel_x, pixel_y) = self.window_pos_to_buffer_pos(physical_position);
+        let pixel_x = pixel_x.floor() as isize;
+        let pixel_y = pixel_y.floor() as isize;
 
         if pixel_x < 0
             || pixel_x >= self.context.texture_extent.width as isize
@@ -655,6 +2616,68 @@ impl<'win> Pixels<'win> {
         }
     }
 
+    /// Calculate the fractional pixel location from a physical location on the window.
+    ///
+    /// This is like [`Pixels::window_pos_to_pixel`], but keeps the position within the pixel, e.g.
+    /// for smooth brush strokes. The top-left corner of the pixel buffer is `(0.0, 0.0)`, and the
+    /// center of the first pixel is `(0.5, 0.5)`. Returns `None` when the physical position is
+    /// outside of the drawing area.
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(640, 480, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // At 2x scale, the center of a physical pixel is a quarter of the way into a pixel
+    /// assert_eq!(pixels.window_pos_to_pixel_f32((0.5, 0.5)), Some((0.25, 0.25)));
+    /// assert_eq!(pixels.window_pos_to_pixel_f32((-1.0, 0.0)), None);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn window_pos_to_pixel_f32(&self, physical_position: (f32, f32)) -> Option<(f32, f32)> {
+        let (pixel_x, pixel_y) = self.window_pos_to_buffer_pos(physical_position);
+        let width = self.context.texture_extent.width as f32;
+        let height = self.context.texture_extent.height as f32;
+
+        if (0.0..width).contains(&pixel_x) && (0.0..height).contains(&pixel_y) {
+            Some((pixel_x, pixel_y))
+        } else {
+            None
+        }
+    }
+
+    /// Undo the scaling transform, mapping a physical position on the window to a fractional
+    /// position in the pixel buffer. The result may be outside of the pixel buffer.
+    fn window_pos_to_buffer_pos(&self, physical_position: (f32, f32)) -> (f32, f32) {
+        // Positions are relative to the viewport
+        let (x, y, width, height) = self.context.scaling_renderer.viewport();
+        let physical_position = (
+            physical_position.0 - x as f32,
+            physical_position.1 - y as f32,
+        );
+        let physical_width = width.max(1) as f32;
+        let physical_height = height.max(1) as f32;
+
+        let (source_x, source_y, source_width, source_height) =
+            self.context.scaling_renderer.source_rect();
+
+        // Convert to normalized device coordinates, and undo the scaling transform. This inverts
+        // the integer scale, the border, and the pixel aspect ratio.
+        let pos = ultraviolet::Vec4::new(
+            physical_position.0 / physical_width * 2.0 - 1.0,
+            1.0 - physical_position.1 / physical_height * 2.0,
+            0.0,
+            1.0,
+        );
+        let pos = self.scaling_matrix_inverse * pos;
+
+        // The source rectangle spans -1.0..1.0 on both axes, with Y pointing up
+        (
+            (pos.x / pos.w + 1.0) / 2.0 * source_width as f32 + source_x as f32,
+            (1.0 - pos.y / pos.w) / 2.0 * source_height as f32 + source_y as f32,
+        )
+    }
+
     /// Clamp a pixel position to the pixel buffer texture size.
     ///
     /// This can be used to clamp the `Err` value returned by [`Pixels::window_pos_to_pixel`]
@@ -682,6 +2705,56 @@ impl<'win> Pixels<'win> {
         )
     }
 
+    /// Get the integer scale factor used to fit the pixel buffer onto the surface.
+    ///
+    /// This is always a whole number (1x, 2x, 3x, etc.) chosen as the largest scale that fits the
+    /// surface. It is updated by [`Pixels::resize_buffer`] and [`Pixels::resize_surface`].
+    ///
+    /// ```no_run
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(640, 480, &window);
+    /// let mut pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// assert_eq!(pixels.scale_factor(), 2.0);
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn scale_factor(&self) -> f32 {
+        self.context.scaling_renderer.scale_factor()
+    }
+
+    /// Get the size of the pixel buffer as `(width, height)`.
+    pub fn buffer_size(&self) -> (u32, u32) {
+        (
+            self.context.texture_extent.width,
+            self.context.texture_extent.height,
+        )
+    }
+
+    /// Compute a pixel buffer size that tracks the logical size of a surface at 1:1.
+    ///
+    /// The physical `surface` size is divided by the window's `scale_factor` and rounded to the
+    /// nearest whole pixel. Each dimension is at least 1. Use this with [`Pixels::resize_buffer`]
+    /// when the pixel buffer should grow and shrink with the window, without a border.
+    ///
+    /// ```
+    /// use pixels::Pixels;
+    ///
+    /// assert_eq!(Pixels::suggested_buffer_size((1280, 960), 2.0), (640, 480));
+    /// assert_eq!(Pixels::suggested_buffer_size((1001, 750), 1.5), (667, 500));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `scale_factor` is not greater than 0.
+    pub fn suggested_buffer_size(surface: (u32, u32), scale_factor: f64) -> (u32, u32) {
+        assert!(scale_factor > 0.0);
+
+        let logical = |physical: u32| (physical as f64 / scale_factor).round().max(1.0) as u32;
+
+        (logical(surface.0), logical(surface.1))
+    }
+
     /// Provides access to the internal [`wgpu::Device`].
     pub fn device(&self) -> &wgpu::Device {
         &self.context.device
@@ -699,8 +2772,33 @@ impl<'win> Pixels<'win> {
         &self.context.texture
     }
 
+    /// Provides access to a view of the internal source [`wgpu::Texture`].
+    ///
+    /// The view is created once with the default descriptor, and recreated by
+    /// [`Pixels::resize_buffer`]. Custom renderers can bind it to read the pixel buffer without
+    /// creating their own view every frame.
+    ///
+    /// ```no_run
+    /// use pixels::wgpu;
+    ///
+    /// # use pixels::Pixels;
+    /// # let window = pixels_mocks::Window;
+    /// # let surface_texture = pixels::SurfaceTexture::new(320, 240, &window);
+    /// let pixels = Pixels::new(320, 240, surface_texture)?;
+    ///
+    /// // Bind the pixel buffer as the input of a post-processing pass
+    /// let entry = wgpu::BindGroupEntry {
+    ///     binding: 0,
+    ///     resource: wgpu::BindingResource::TextureView(pixels.texture_view()),
+    /// };
+    /// # Ok::<(), pixels::Error>(())
+    /// ```
+    pub fn texture_view(&self) -> &wgpu::TextureView {
+        self.context.texture_view()
+    }
+
     /// Provides access to the internal [`PixelsContext`].
-    pub fn context(&self) -> &PixelsContext {
+    pub fn context(&self) -> &PixelsContext<'_> {
         &self.context
     }
 
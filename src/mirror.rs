@@ -0,0 +1,128 @@
+//! Additional surfaces that show the same pixel buffer, see [`Pixels::add_mirror_surface`].
+//!
+//! [`Pixels::add_mirror_surface`]: crate::Pixels::add_mirror_surface
+
+use crate::builder::supported_present_mode;
+use crate::renderers::ScalingRenderer;
+use crate::SurfaceSize;
+
+/// A window surface that the pixel buffer texture is drawn to, in addition to the main surface.
+///
+/// Each mirror has its own surface configuration and scaling renderer, so the pixel buffer is fit
+/// to the size of each window independently.
+#[derive(Debug)]
+pub(crate) struct Mirror<'win> {
+    surface: wgpu::Surface<'win>,
+    present_modes: Vec<wgpu::PresentMode>,
+    config: wgpu::SurfaceConfiguration,
+    pub(crate) scaling_renderer: ScalingRenderer,
+}
+
+impl<'win> Mirror<'win> {
+    pub(crate) fn new(
+        surface: wgpu::Surface<'win>,
+        capabilities: wgpu::SurfaceCapabilities,
+        size: &SurfaceSize,
+        format: wgpu::TextureFormat,
+        view_formats: Vec<wgpu::TextureFormat>,
+        present_mode: wgpu::PresentMode,
+        scaling_renderer: ScalingRenderer,
+    ) -> Self {
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width,
+            height: size.height,
+            present_mode: supported_present_mode(&capabilities.present_modes, present_mode),
+            desired_maximum_frame_latency: 2,
+            alpha_mode: capabilities.alpha_modes[0],
+            view_formats,
+        };
+
+        Self {
+            surface,
+            present_modes: capabilities.present_modes,
+            config,
+            scaling_renderer,
+        }
+    }
+
+    /// The size of the surface.
+    pub(crate) fn size(&self) -> SurfaceSize {
+        SurfaceSize {
+            width: self.config.width,
+            height: self.config.height,
+        }
+    }
+
+    /// Configure the surface with the current size and present mode.
+    pub(crate) fn configure(&self, device: &wgpu::Device) {
+        // A zero-sized surface cannot be configured
+        if self.is_minimized() {
+            return;
+        }
+
+        self.surface.configure(device, &self.config);
+    }
+
+    /// Use the present mode if the surface supports it, or `AutoVsync` otherwise.
+    pub(crate) fn set_present_mode(
+        &mut self,
+        device: &wgpu::Device,
+        present_mode: wgpu::PresentMode,
+    ) {
+        self.config.present_mode = supported_present_mode(&self.present_modes, present_mode);
+        self.configure(device);
+    }
+
+    pub(crate) fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+    ) {
+        self.config.width = width;
+        self.config.height = height;
+        self.scaling_renderer.resize(queue, width, height);
+        self.configure(device);
+    }
+
+    /// Acquire a surface texture and encode the commands to draw the pixel buffer texture on it.
+    ///
+    /// The surface texture must be presented after the command buffer is submitted. Returns `None`
+    /// while the surface is minimized.
+    pub(crate) fn encode(
+        &self,
+        device: &wgpu::Device,
+        auto_reconfigure: bool,
+    ) -> Result<Option<(wgpu::SurfaceTexture, wgpu::CommandBuffer)>, wgpu::SurfaceError> {
+        if self.is_minimized() {
+            return Ok(None);
+        }
+
+        let frame = self.surface.get_current_texture().or_else(|err| {
+            if !auto_reconfigure {
+                return Err(err);
+            }
+
+            // Reconfigure the surface and retry immediately on any error, like the main surface
+            self.configure(device);
+            self.surface.get_current_texture()
+        })?;
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("pixels_mirror_command_encoder"),
+        });
+        self.scaling_renderer.render(&mut encoder, &view);
+
+        Ok(Some((frame, encoder.finish())))
+    }
+
+    fn is_minimized(&self) -> bool {
+        self.config.width == 0 || self.config.height == 0
+    }
+}
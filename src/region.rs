@@ -0,0 +1,168 @@
+//! A mutable view of a rectangular region of the pixel buffer.
+//!
+//! See [`Pixels::frame_region_mut`](crate::Pixels::frame_region_mut).
+
+/// A rectangular region of the pixel buffer that can be drawn to, without access to the rest of
+/// the pixel buffer, see [`Pixels::frame_region_mut`].
+///
+/// Coordinates are relative to the top-left corner of the region, so independent widgets can
+/// draw into their own part of one pixel buffer without knowing where they are placed, or the
+/// stride of the pixel buffer. Writes outside of the region are ignored.
+///
+/// [`Pixels::frame_region_mut`]: crate::Pixels::frame_region_mut
+#[derive(Debug)]
+pub struct RegionMut<'frame> {
+    frame: &'frame mut [u8],
+    stride: usize,
+    bytes_per_pixel: usize,
+    x: usize,
+    y: usize,
+    width: u32,
+    height: u32,
+}
+
+impl<'frame> RegionMut<'frame> {
+    /// Create a view of the region `(x, y, width, height)` of a frame that is `frame_width`
+    /// pixels wide. The region is clamped to the frame.
+    pub(crate) fn new(
+        frame: &'frame mut [u8],
+        frame_width: u32,
+        bytes_per_pixel: usize,
+        region: (u32, u32, u32, u32),
+    ) -> Self {
+        let stride = frame_width as usize * bytes_per_pixel;
+        let frame_height = (frame.len() / stride.max(1)) as u32;
+        let (x, y, width, height) = region;
+        let x = x.min(frame_width);
+        let y = y.min(frame_height);
+
+        Self {
+            frame,
+            stride,
+            bytes_per_pixel,
+            x: x as usize,
+            y: y as usize,
+            width: width.min(frame_width - x),
+            height: height.min(frame_height - y),
+        }
+    }
+
+    /// Get the region width in pixels.
+    ///
+    /// This is less than the requested width when the region was clamped to the pixel buffer.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the region height in pixels.
+    ///
+    /// This is less than the requested height when the region was clamped to the pixel buffer.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the bytes of the pixel at `(x, y)` within the region, or `None` when it is outside of
+    /// the region.
+    pub fn pixel_mut(&mut self, x: u32, y: u32) -> Option<&mut [u8]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = (self.y + y as usize) * self.stride + (self.x + x as usize) * self.bytes_per_pixel;
+
+        Some(&mut self.frame[i..i + self.bytes_per_pixel])
+    }
+
+    /// Set the pixel at `(x, y)` within the region. Pixels outside of the region are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `pixel` is not the size of one pixel in the texture format.
+    pub fn set_pixel(&mut self, x: u32, y: u32, pixel: &[u8]) {
+        assert_eq!(
+            pixel.len(),
+            self.bytes_per_pixel,
+            "pixel size does not match the texture format"
+        );
+
+        if let Some(dst) = self.pixel_mut(x, y) {
+            dst.copy_from_slice(pixel);
+        }
+    }
+
+    /// Get an iterator over the rows of the region, from top to bottom.
+    ///
+    /// Each row only contains the bytes of the pixels within the region.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let start = self.x * self.bytes_per_pixel;
+        let end = start + self.width as usize * self.bytes_per_pixel;
+
+        self.frame
+            .chunks_exact_mut(self.stride)
+            .skip(self.y)
+            .take(self.height as usize)
+            .map(move |row| &mut row[start..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+
+    #[test]
+    fn test_region_writes() {
+        // A 2x2 region in the middle of a 4x4 frame
+        let mut frame = vec![0; 4 * 4 * 4];
+        let mut region = RegionMut::new(&mut frame, 4, 4, (1, 1, 2, 2));
+        assert_eq!((region.width(), region.height()), (2, 2));
+
+        // Writes outside of the region are ignored, including those that would wrap around
+        for y in 0..8 {
+            for x in 0..8 {
+                region.set_pixel(x, y, &RED);
+            }
+        }
+        for row in region.rows_mut() {
+            assert_eq!(row.len(), 8);
+        }
+        assert!(region.pixel_mut(2, 0).is_none());
+        assert!(region.pixel_mut(u32::MAX, u32::MAX).is_none());
+
+        let drawn: Vec<_> = frame.chunks_exact(4).map(|p| p == RED).collect();
+        assert_eq!(
+            drawn,
+            [
+                false, false, false, false, //
+                false, true, true, false, //
+                false, true, true, false, //
+                false, false, false, false, //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_region_clamping() {
+        // A region hanging off the bottom-right corner is clamped to the frame
+        let mut frame = vec![0; 4 * 4 * 4];
+        let mut region = RegionMut::new(&mut frame, 4, 4, (3, 2, 10, 10));
+        assert_eq!((region.width(), region.height()), (1, 2));
+        for row in region.rows_mut() {
+            row.copy_from_slice(&RED);
+        }
+        assert_eq!(frame.chunks_exact(4).filter(|&p| p == RED).count(), 2);
+        assert_eq!(frame[44..48], RED);
+        assert_eq!(frame[60..64], RED);
+
+        // And a region entirely outside of the frame is empty
+        let mut frame = vec![0; 4 * 4 * 4];
+        let mut region = RegionMut::new(&mut frame, 4, 4, (5, 0, 2, 2));
+        assert_eq!(region.width(), 0);
+        region.set_pixel(0, 0, &RED);
+        for row in region.rows_mut() {
+            assert!(row.is_empty());
+        }
+        assert!(frame.iter().all(|&byte| byte == 0));
+    }
+}
@@ -2,43 +2,110 @@ use crate::SurfaceSize;
 use ultraviolet::Mat4;
 use wgpu::util::DeviceExt;
 
+/// How the pixel buffer is scaled to fit the surface.
+///
+/// See [`PixelsBuilder::scaling_mode`](crate::PixelsBuilder::scaling_mode).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScalingMode {
+    /// Scale both axes by the same integer factor, the largest one that fits the surface.
+    ///
+    /// This is the default. A border is added around the pixel buffer to fill the rest of the
+    /// surface.
+    #[default]
+    Integer,
+
+    /// Scale each axis independently.
+    ///
+    /// E.g. stretch horizontally to fill the surface width, while keeping scanlines sharp with
+    /// integer scaling on the vertical axis. This does not preserve the aspect ratio of the pixel
+    /// buffer.
+    AxisIndependent {
+        /// The scaling rule for the horizontal axis.
+        x: ScaleKind,
+        /// The scaling rule for the vertical axis.
+        y: ScaleKind,
+    },
+}
+
+/// The scaling rule for a single axis, see [`ScalingMode::AxisIndependent`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScaleKind {
+    /// Scale by the largest integer factor that fits the surface, and at least 1x.
+    Integer,
+    /// Scale by any factor to fill the surface exactly.
+    Stretch,
+}
+
+impl ScaleKind {
+    /// Get the scaled length of `texture` pixels on a surface `screen` pixels long.
+    fn fit(self, texture: f32, screen: f32) -> f32 {
+        match self {
+            Self::Integer => texture * (screen / texture).max(1.0).floor(),
+            Self::Stretch => screen,
+        }
+    }
+}
+
 /// The default renderer that scales your frame to the screen size.
 #[derive(Debug)]
 pub struct ScalingRenderer {
+    pub(crate) label: String,
     vertex_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
+    module: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
     render_pipeline: wgpu::RenderPipeline,
+    render_texture_format: wgpu::TextureFormat,
+    blend_state: wgpu::BlendState,
+    color_write_mask: wgpu::ColorWrites,
     pub(crate) clear_color: wgpu::Color,
     width: f32,
     height: f32,
+    surface_size: (u32, u32),
+    pub(crate) viewport: Option<(u32, u32, u32, u32)>,
+    pub(crate) scaling_mode: ScalingMode,
+    pub(crate) pixel_aspect_ratio: f64,
+    pub(crate) custom_transform: Option<Mat4>,
+    pub(crate) source_rect: Option<(u32, u32, u32, u32)>,
     clip_rect: (u32, u32, u32, u32),
+    scale_factor: f32,
 }
 
 impl ScalingRenderer {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         device: &wgpu::Device,
+        label: &str,
         texture_view: &wgpu::TextureView,
         texture_size: &wgpu::Extent3d,
+        mip_level_count: u32,
         surface_size: &SurfaceSize,
         render_texture_format: wgpu::TextureFormat,
         clear_color: wgpu::Color,
         blend_state: wgpu::BlendState,
+        color_write_mask: wgpu::ColorWrites,
     ) -> Self {
         let shader = wgpu::include_wgsl!("../shaders/scale.wgsl");
         let module = device.create_shader_module(shader);
 
-        // Create a texture sampler with nearest neighbor
+        // Create a texture sampler with nearest neighbor. With mipmaps, the pixel buffer is only
+        // filtered when it is drawn smaller than its size; magnification stays sharp.
+        let (min_filter, lod_max_clamp) = if mip_level_count > 1 {
+            (wgpu::FilterMode::Linear, mip_level_count as f32)
+        } else {
+            (wgpu::FilterMode::Nearest, 1.0)
+        };
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("pixels_scaling_renderer_sampler"),
+            label: Some(&format!("{label}_scaling_renderer_sampler")),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
             mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
+            min_filter,
+            mipmap_filter: min_filter,
             lod_min_clamp: 0.0,
-            lod_max_clamp: 1.0,
+            lod_max_clamp,
             compare: None,
             anisotropy_clamp: 1,
             border_color: None,
@@ -54,35 +121,28 @@ impl ScalingRenderer {
         ];
         let vertex_data_slice = bytemuck::cast_slice(&vertex_data);
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("pixels_scaling_renderer_vertex_buffer"),
+            label: Some(&format!("{label}_scaling_renderer_vertex_buffer")),
             contents: vertex_data_slice,
             usage: wgpu::BufferUsages::VERTEX,
         });
-        let vertex_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: (vertex_data_slice.len() / vertex_data.len()) as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[wgpu::VertexAttribute {
-                format: wgpu::VertexFormat::Float32x2,
-                offset: 0,
-                shader_location: 0,
-            }],
-        };
 
         // Create uniform buffer
         let matrix = ScalingMatrix::new(
             (texture_size.width as f32, texture_size.height as f32),
             (surface_size.width as f32, surface_size.height as f32),
+            ScalingMode::default(),
+            1.0,
         );
-        let transform_bytes = matrix.as_bytes();
+        let uniform_bytes = uniform_bytes(&matrix, [0.0, 0.0, 1.0, 1.0]);
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("pixels_scaling_renderer_matrix_uniform_buffer"),
-            contents: transform_bytes,
+            label: Some(&format!("{label}_scaling_renderer_matrix_uniform_buffer")),
+            contents: &uniform_bytes,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
         // Create bind group
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("pixels_scaling_renderer_bind_group_layout"),
+            label: Some(&format!("{label}_scaling_renderer_bind_group_layout")),
             entries: &[
                 wgpu::BindGroupLayoutEntry {
                     binding: 0,
@@ -106,14 +166,14 @@ impl ScalingRenderer {
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
-                        min_binding_size: wgpu::BufferSize::new(transform_bytes.len() as u64),
+                        min_binding_size: wgpu::BufferSize::new(uniform_bytes.len() as u64),
                     },
                     count: None,
                 },
             ],
         });
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("pixels_scaling_renderer_bind_group"),
+            label: Some(&format!("{label}_scaling_renderer_bind_group")),
             layout: &bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
@@ -133,57 +193,66 @@ impl ScalingRenderer {
 
         // Create pipeline
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("pixels_scaling_renderer_pipeline_layout"),
+            label: Some(&format!("{label}_scaling_renderer_pipeline_layout")),
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[],
         });
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("pixels_scaling_renderer_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &module,
-                entry_point: "vs_main",
-                buffers: &[vertex_buffer_layout],
-            },
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            fragment: Some(wgpu::FragmentState {
-                module: &module,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: render_texture_format,
-                    blend: Some(blend_state),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            multiview: None,
-        });
+        let render_pipeline = create_render_pipeline(
+            device,
+            label,
+            &module,
+            &pipeline_layout,
+            render_texture_format,
+            blend_state,
+            color_write_mask,
+        );
 
         // Create clipping rectangle
         let clip_rect = matrix.clip_rect();
+        let scale_factor = matrix.scale;
 
         Self {
+            label: label.to_owned(),
             vertex_buffer,
             uniform_buffer,
             bind_group,
+            module,
+            pipeline_layout,
             render_pipeline,
+            render_texture_format,
+            blend_state,
+            color_write_mask,
             clear_color,
             width: texture_size.width as f32,
             height: texture_size.height as f32,
+            surface_size: (surface_size.width, surface_size.height),
+            viewport: None,
+            scaling_mode: ScalingMode::default(),
+            pixel_aspect_ratio: 1.0,
+            custom_transform: None,
+            source_rect: None,
             clip_rect,
+            scale_factor,
         }
     }
 
     /// Draw the pixel buffer to the render target.
+    ///
+    /// The render target is cleared with the clear color first, unless a viewport has been set
+    /// with [`Pixels::set_viewport`](crate::Pixels::set_viewport). In that case, only the scaled
+    /// pixel buffer is drawn and everything else is left untouched.
     pub fn render(&self, encoder: &mut wgpu::CommandEncoder, render_target: &wgpu::TextureView) {
+        let load = match self.viewport {
+            Some(_) => wgpu::LoadOp::Load,
+            None => wgpu::LoadOp::Clear(self.clear_color),
+        };
         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("pixels_scaling_renderer_render_pass"),
+            label: Some(&format!("{}_scaling_renderer_render_pass", self.label)),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: render_target,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(self.clear_color),
+                    load,
                     store: wgpu::StoreOp::Store,
                 },
             })],
@@ -191,6 +260,14 @@ impl ScalingRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+
+        // Nothing to draw when the viewport is entirely outside of the render target
+        let (x, y, width, height) = self.viewport();
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        rpass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
         rpass.set_pipeline(&self.render_pipeline);
         rpass.set_bind_group(0, &self.bind_group, &[]);
         rpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
@@ -210,42 +287,282 @@ impl ScalingRenderer {
         self.clip_rect
     }
 
+    /// Get the integer scale factor used to fit the pixel buffer onto the surface.
+    ///
+    /// This is always a whole number, and at least `1.0`. With [`ScalingMode::AxisIndependent`],
+    /// this is the smaller of the two axis scales, rounded down.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Get the region of the render target that the pixel buffer is fit into, as
+    /// `(x, y, width, height)`.
+    ///
+    /// This is the whole render target unless a viewport has been set with
+    /// [`Pixels::set_viewport`](crate::Pixels::set_viewport). The viewport is clamped to the
+    /// render target.
+    pub fn viewport(&self) -> (u32, u32, u32, u32) {
+        let (surface_width, surface_height) = self.surface_size;
+
+        match self.viewport {
+            Some((x, y, width, height)) => {
+                let x = x.min(surface_width);
+                let y = y.min(surface_height);
+
+                (
+                    x,
+                    y,
+                    width.min(surface_width - x),
+                    height.min(surface_height - y),
+                )
+            }
+            None => (0, 0, surface_width, surface_height),
+        }
+    }
+
     pub(crate) fn resize(&mut self, queue: &wgpu::Queue, width: u32, height: u32) {
-        let matrix = ScalingMatrix::new((self.width, self.height), (width as f32, height as f32));
-        let transform_bytes = matrix.as_bytes();
-        queue.write_buffer(&self.uniform_buffer, 0, transform_bytes);
+        self.surface_size = (width, height);
+        self.update_matrix(queue);
+    }
+
+    pub(crate) fn set_viewport(
+        &mut self,
+        queue: &wgpu::Queue,
+        viewport: Option<(u32, u32, u32, u32)>,
+    ) {
+        self.viewport = viewport;
+        self.update_matrix(queue);
+    }
+
+    pub(crate) fn set_scaling_mode(&mut self, queue: &wgpu::Queue, scaling_mode: ScalingMode) {
+        self.scaling_mode = scaling_mode;
+        self.update_matrix(queue);
+    }
+
+    pub(crate) fn set_pixel_aspect_ratio(&mut self, queue: &wgpu::Queue, pixel_aspect_ratio: f64) {
+        self.pixel_aspect_ratio = pixel_aspect_ratio;
+        self.update_matrix(queue);
+    }
+
+    pub(crate) fn set_custom_transform(&mut self, queue: &wgpu::Queue, transform: Option<Mat4>) {
+        self.custom_transform = transform;
+        self.update_matrix(queue);
+    }
+
+    pub(crate) fn set_source_rect(
+        &mut self,
+        queue: &wgpu::Queue,
+        source_rect: Option<(u32, u32, u32, u32)>,
+    ) {
+        self.source_rect = source_rect;
+        self.update_matrix(queue);
+    }
+
+    /// Get the region of the pixel buffer that is drawn, as `(x, y, width, height)`.
+    ///
+    /// This is the whole pixel buffer unless a source rectangle has been set with
+    /// [`Pixels::set_source_rect`](crate::Pixels::set_source_rect).
+    pub fn source_rect(&self) -> (u32, u32, u32, u32) {
+        self.source_rect
+            .unwrap_or((0, 0, self.width as u32, self.height as u32))
+    }
+
+    /// Rebuild the render pipeline to write only the given color channels.
+    pub(crate) fn set_color_write_mask(
+        &mut self,
+        device: &wgpu::Device,
+        color_write_mask: wgpu::ColorWrites,
+    ) {
+        self.color_write_mask = color_write_mask;
+        self.render_pipeline = create_render_pipeline(
+            device,
+            &self.label,
+            &self.module,
+            &self.pipeline_layout,
+            self.render_texture_format,
+            self.blend_state,
+            color_write_mask,
+        );
+    }
+
+    /// Take the viewport, scaling mode, pixel aspect ratio, custom transform, and source rectangle
+    /// from another renderer.
+    ///
+    /// The source rectangle is dropped when it does not fit within this renderer's texture.
+    pub(crate) fn copy_settings(&mut self, queue: &wgpu::Queue, other: &Self) {
+        self.viewport = other.viewport;
+        self.copy_scaling(queue, other);
+    }
+
+    /// Like [`ScalingRenderer::copy_settings`], but without the viewport. This is for renderers
+    /// drawing to another surface, where the viewport has no meaning.
+    pub(crate) fn copy_scaling(&mut self, queue: &wgpu::Queue, other: &Self) {
+        self.scaling_mode = other.scaling_mode;
+        self.pixel_aspect_ratio = other.pixel_aspect_ratio;
+        self.custom_transform = other.custom_transform;
+        self.source_rect = other.source_rect.filter(|&(x, y, width, height)| {
+            x + width <= self.width as u32 && y + height <= self.height as u32
+        });
+        self.update_matrix(queue);
+    }
 
-        self.clip_rect = matrix.clip_rect();
+    /// Get the transformation for the current surface size and settings.
+    pub(crate) fn matrix(&self) -> ScalingMatrix {
+        let (_, _, width, height) = self.viewport();
+        let (_, _, source_width, source_height) = self.source_rect();
+
+        // The source rectangle is fit as if it was the whole pixel buffer
+        let mut matrix = ScalingMatrix::new(
+            (source_width as f32, source_height as f32),
+            (width.max(1) as f32, height.max(1) as f32),
+            self.scaling_mode,
+            self.pixel_aspect_ratio as f32,
+        );
+
+        // A custom transform can put the pixel buffer anywhere, so only the viewport clips it
+        if let Some(transform) = self.custom_transform {
+            matrix.transform = transform;
+            matrix.clip_rect = (0, 0, width, height);
+        }
+
+        matrix
+    }
+
+    fn update_matrix(&mut self, queue: &wgpu::Queue) {
+        let (x, y, width, height) = self.viewport();
+        let matrix = self.matrix();
+        let (source_x, source_y, source_width, source_height) = self.source_rect();
+        let source_rect = [
+            source_x as f32 / self.width,
+            source_y as f32 / self.height,
+            source_width as f32 / self.width,
+            source_height as f32 / self.height,
+        ];
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            &uniform_bytes(&matrix, source_rect),
+        );
+
+        // The clipping rectangle is relative to the viewport
+        let clip_rect = matrix.clip_rect();
+        self.clip_rect = (
+            x + clip_rect.0,
+            y + clip_rect.1,
+            clip_rect.2.min(width),
+            clip_rect.3.min(height),
+        );
+        self.scale_factor = matrix.scale;
     }
 }
 
+/// The contents of the `Locals` uniform in `scale.wgsl`.
+fn uniform_bytes(matrix: &ScalingMatrix, source_rect: [f32; 4]) -> Vec<u8> {
+    let mut bytes = matrix.as_bytes().to_vec();
+    bytes.extend_from_slice(bytemuck::cast_slice(&source_rect));
+
+    bytes
+}
+
+/// Create the scaling renderer pipeline for the given blend state and color write mask.
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    label: &str,
+    module: &wgpu::ShaderModule,
+    pipeline_layout: &wgpu::PipelineLayout,
+    render_texture_format: wgpu::TextureFormat,
+    blend_state: wgpu::BlendState,
+    color_write_mask: wgpu::ColorWrites,
+) -> wgpu::RenderPipeline {
+    let vertex_buffer_layout = wgpu::VertexBufferLayout {
+        array_stride: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+        step_mode: wgpu::VertexStepMode::Vertex,
+        attributes: &[wgpu::VertexAttribute {
+            format: wgpu::VertexFormat::Float32x2,
+            offset: 0,
+            shader_location: 0,
+        }],
+    };
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("{label}_scaling_renderer_pipeline")),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module,
+            entry_point: "vs_main",
+            buffers: &[vertex_buffer_layout],
+        },
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: render_texture_format,
+                blend: Some(blend_state),
+                write_mask: color_write_mask,
+            })],
+        }),
+        multiview: None,
+    })
+}
+
 #[derive(Debug)]
 pub(crate) struct ScalingMatrix {
     pub(crate) transform: Mat4,
     clip_rect: (u32, u32, u32, u32),
+    scale: f32,
 }
 
 impl ScalingMatrix {
     // texture_size is the dimensions of the drawing texture
     // screen_size is the dimensions of the surface being drawn to
-    pub(crate) fn new(texture_size: (f32, f32), screen_size: (f32, f32)) -> Self {
+    // pixel_aspect_ratio stretches the texture horizontally before it is fit to the screen
+    pub(crate) fn new(
+        texture_size: (f32, f32),
+        screen_size: (f32, f32),
+        scaling_mode: ScalingMode,
+        pixel_aspect_ratio: f32,
+    ) -> Self {
         let (texture_width, texture_height) = texture_size;
         let (screen_width, screen_height) = screen_size;
 
-        let width_ratio = (screen_width / texture_width).max(1.0);
-        let height_ratio = (screen_height / texture_height).max(1.0);
+        // Non-square pixels are fit as if the texture was wider, rounded to whole pixels
+        let texture_width = (texture_width * pixel_aspect_ratio).round().max(1.0);
 
-        // Get smallest scale size
-        let scale = width_ratio.clamp(1.0, height_ratio).floor();
+        let (scaled_width, scaled_height, scale) = match scaling_mode {
+            ScalingMode::Integer => {
+                let width_ratio = screen_width / texture_width;
+                let height_ratio = screen_height / texture_height;
 
-        let scaled_width = texture_width * scale;
-        let scaled_height = texture_height * scale;
+                // The largest whole scale that fits on both axes, but never smaller than 1x
+                let scale = width_ratio.min(height_ratio).floor().max(1.0);
+
+                (texture_width * scale, texture_height * scale, scale)
+            }
+            ScalingMode::AxisIndependent { x, y } => {
+                let scaled_width = x.fit(texture_width, screen_width);
+                let scaled_height = y.fit(texture_height, screen_height);
+                let scale = (scaled_width / texture_width)
+                    .min(scaled_height / texture_height)
+                    .max(1.0)
+                    .floor();
+
+                (scaled_width, scaled_height, scale)
+            }
+        };
+
+        // Center the scaled texture, rounding the border down to whole pixels. This keeps the
+        // texture aligned to the pixel grid, and in agreement with the clipping rectangle.
+        let x = ((screen_width - scaled_width) / 2.0).floor();
+        let y = ((screen_height - scaled_height) / 2.0).floor();
 
         // Create a transformation matrix
         let sw = scaled_width / screen_width;
         let sh = scaled_height / screen_height;
-        let tx = (screen_width / 2.0).fract() / screen_width;
-        let ty = (screen_height / 2.0).fract() / screen_height;
+        let tx = (x * 2.0 + scaled_width - screen_width) / screen_width;
+        let ty = (screen_height - y * 2.0 - scaled_height) / screen_height;
         #[rustfmt::skip]
         let transform: [f32; 16] = [
             sw,  0.0, 0.0, 0.0,
@@ -254,19 +571,20 @@ impl ScalingMatrix {
             tx,  ty,  0.0, 1.0,
         ];
 
-        // Create a clipping rectangle
+        // Create a clipping rectangle, clamped to the screen when the texture is larger
         let clip_rect = {
-            let scaled_width = scaled_width.min(screen_width);
-            let scaled_height = scaled_height.min(screen_height);
-            let x = ((screen_width - scaled_width) / 2.0) as u32;
-            let y = ((screen_height - scaled_height) / 2.0) as u32;
+            let x = x.max(0.0);
+            let y = y.max(0.0);
+            let width = scaled_width.min(screen_width - x);
+            let height = scaled_height.min(screen_height - y);
 
-            (x, y, scaled_width as u32, scaled_height as u32)
+            (x as u32, y as u32, width as u32, height as u32)
         };
 
         Self {
             transform: Mat4::from(transform),
             clip_rect,
+            scale,
         }
     }
 
@@ -278,3 +596,159 @@ impl ScalingMatrix {
         self.clip_rect
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_rect_bounds() {
+        for texture_width in 1..=17 {
+            for texture_height in [1, 2, 7, 8, 15] {
+                for screen_width in 1..=64 {
+                    for screen_height in [1, 3, 8, 33, 64] {
+                        let matrix = ScalingMatrix::new(
+                            (texture_width as f32, texture_height as f32),
+                            (screen_width as f32, screen_height as f32),
+                            ScalingMode::Integer,
+                            1.0,
+                        );
+                        let (x, y, width, height) = matrix.clip_rect();
+                        let sizes = (texture_width, texture_height, screen_width, screen_height);
+
+                        // The clip rect stays within the screen
+                        assert!(x + width <= screen_width, "{sizes:?}");
+                        assert!(y + height <= screen_height, "{sizes:?}");
+
+                        // The clip rect is centered within 1px
+                        let left = x;
+                        let right = screen_width - x - width;
+                        let top = y;
+                        let bottom = screen_height - y - height;
+                        assert!(left.abs_diff(right) <= 1, "{sizes:?}");
+                        assert!(top.abs_diff(bottom) <= 1, "{sizes:?}");
+
+                        // The scaled texture covers the clip rect exactly when it fits
+                        if width < screen_width || texture_width == screen_width {
+                            let left_ndc = matrix.transform[3][0] - matrix.transform[0][0];
+                            let left_px = (left_ndc + 1.0) / 2.0 * screen_width as f32;
+                            let width_px = matrix.transform[0][0] * screen_width as f32;
+                            assert!((left_px - x as f32).abs() < 1e-3, "{sizes:?}");
+                            assert!((width_px - width as f32).abs() < 1e-3, "{sizes:?}");
+                        }
+                        if height < screen_height || texture_height == screen_height {
+                            let top_ndc = matrix.transform[3][1] + matrix.transform[1][1];
+                            let top_px = (1.0 - top_ndc) / 2.0 * screen_height as f32;
+                            let height_px = matrix.transform[1][1] * screen_height as f32;
+                            assert!((top_px - y as f32).abs() < 1e-3, "{sizes:?}");
+                            assert!((height_px - height as f32).abs() < 1e-3, "{sizes:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_scale_fits() {
+        let sizes = [
+            1, 2, 3, 7, 8, 15, 16, 100, 160, 199, 240, 256, 320, 1000, 1080, 1920,
+        ];
+        for texture_width in sizes {
+            for texture_height in sizes {
+                for screen_width in sizes {
+                    for screen_height in sizes {
+                        let matrix = ScalingMatrix::new(
+                            (texture_width as f32, texture_height as f32),
+                            (screen_width as f32, screen_height as f32),
+                            ScalingMode::Integer,
+                            1.0,
+                        );
+                        let sizes = (texture_width, texture_height, screen_width, screen_height);
+                        let scaled_width = texture_width as f32 * matrix.scale;
+                        let scaled_height = texture_height as f32 * matrix.scale;
+
+                        // The scale is the largest that fits, or 1x when the texture is too large
+                        if texture_width <= screen_width && texture_height <= screen_height {
+                            assert!(scaled_width <= screen_width as f32, "{sizes:?}");
+                            assert!(scaled_height <= screen_height as f32, "{sizes:?}");
+                            assert!(
+                                scaled_width + texture_width as f32 > screen_width as f32
+                                    || scaled_height + texture_height as f32 > screen_height as f32,
+                                "{sizes:?}"
+                            );
+                        } else {
+                            assert_eq!(matrix.scale, 1.0, "{sizes:?}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_axis_independent() {
+        let integer_x = ScalingMode::AxisIndependent {
+            x: ScaleKind::Integer,
+            y: ScaleKind::Stretch,
+        };
+        let integer_y = ScalingMode::AxisIndependent {
+            x: ScaleKind::Stretch,
+            y: ScaleKind::Integer,
+        };
+
+        // The integer axis gets a border, the stretched axis fills the screen
+        let matrix = ScalingMatrix::new((100.0, 100.0), (350.0, 250.0), integer_x, 1.0);
+        assert_eq!(matrix.clip_rect(), (25, 0, 300, 250));
+        assert_eq!(matrix.scale, 2.0);
+        let matrix = ScalingMatrix::new((100.0, 100.0), (350.0, 250.0), integer_y, 1.0);
+        assert_eq!(matrix.clip_rect(), (0, 25, 350, 200));
+        assert_eq!(matrix.scale, 2.0);
+
+        // Stretching both axes fills the screen, even when it shrinks the texture
+        let stretch = ScalingMode::AxisIndependent {
+            x: ScaleKind::Stretch,
+            y: ScaleKind::Stretch,
+        };
+        let matrix = ScalingMatrix::new((100.0, 100.0), (350.0, 50.0), stretch, 1.0);
+        assert_eq!(matrix.clip_rect(), (0, 0, 350, 50));
+        assert_eq!(matrix.transform[0][0], 1.0);
+        assert_eq!(matrix.transform[1][1], 1.0);
+        assert_eq!(matrix.scale, 1.0);
+
+        // Integer on both axes matches the default mode when the ratios agree
+        let integer = ScalingMode::AxisIndependent {
+            x: ScaleKind::Integer,
+            y: ScaleKind::Integer,
+        };
+        let a = ScalingMatrix::new((100.0, 100.0), (350.0, 350.0), integer, 1.0);
+        let b = ScalingMatrix::new((100.0, 100.0), (350.0, 350.0), ScalingMode::Integer, 1.0);
+        assert_eq!(a.transform, b.transform);
+        assert_eq!(a.clip_rect(), b.clip_rect());
+    }
+
+    #[test]
+    fn test_pixel_aspect_ratio() {
+        // An 8:7 pixel aspect ratio widens a 256x224 texture to 293x224 before the integer fit
+        let matrix = ScalingMatrix::new(
+            (256.0, 224.0),
+            (900.0, 700.0),
+            ScalingMode::Integer,
+            8.0 / 7.0,
+        );
+        assert_eq!(matrix.scale, 3.0);
+        assert_eq!(matrix.clip_rect(), (10, 14, 879, 672));
+
+        // Texture pixels are 8/7 as wide as they are tall on the screen
+        let pixel_width = matrix.transform[0][0] * 900.0 / 256.0;
+        let pixel_height = matrix.transform[1][1] * 700.0 / 224.0;
+        assert!((pixel_width / pixel_height - 8.0 / 7.0).abs() < 1e-2);
+
+        // The inverse transform maps the corners of the clip rect back to the texture corners
+        let inverse = matrix.transform.inversed();
+        let left = inverse * ultraviolet::Vec4::new(10.0 / 450.0 - 1.0, 0.0, 0.0, 1.0);
+        let right = inverse * ultraviolet::Vec4::new(889.0 / 450.0 - 1.0, 0.0, 0.0, 1.0);
+        assert!((left.x + 1.0).abs() < 1e-5);
+        assert!((right.x - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,139 @@
+//! A minimal sprite type for blitting images into the pixel buffer.
+//!
+//! See [`Pixels::blit`](crate::Pixels::blit).
+
+use crate::color::ChannelOrder;
+
+/// An RGBA image that can be drawn into the pixel buffer with [`Pixels::blit`].
+///
+/// [`Pixels::blit`]: crate::Pixels::blit
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sprite {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Sprite {
+    /// Create a sprite from RGBA bytes in row-major order.
+    ///
+    /// ```
+    /// use pixels::Sprite;
+    ///
+    /// // A 2x1 sprite with a red pixel and a transparent pixel
+    /// let sprite = Sprite::from_rgba(2, 1, vec![0xff, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00]);
+    /// assert_eq!(sprite.width(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when `pixels` is not exactly `width * height * 4` bytes long.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize * 4,
+            "sprite size does not match its dimensions"
+        );
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Get the sprite width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the sprite height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the RGBA bytes of the sprite.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Draw the sprite into a frame with 4 bytes per pixel, clipping it to the frame bounds.
+    pub(crate) fn blit(&self, frame: &mut [u8], width: u32, pos: (i32, i32), order: ChannelOrder) {
+        let height = if width == 0 {
+            0
+        } else {
+            frame.len() / (width as usize * 4)
+        };
+
+        // Clip the sprite to the frame
+        let (x, y) = (pos.0 as i64, pos.1 as i64);
+        let left = (-x).clamp(0, self.width as i64) as usize;
+        let top = (-y).clamp(0, self.height as i64) as usize;
+        let right = (width as i64 - x).clamp(0, self.width as i64) as usize;
+        let bottom = (height as i64 - y).clamp(0, self.height as i64) as usize;
+        if left >= right {
+            return;
+        }
+
+        // The clipped region is inside of the frame, so these are never negative
+        let dst_x = (x + left as i64) as usize;
+        let len = (right - left) * 4;
+
+        for row in top..bottom {
+            let dst_y = (y + row as i64) as usize;
+            let src = (row * self.width as usize + left) * 4;
+            let dst = (dst_y * width as usize + dst_x) * 4;
+
+            let src = self.pixels[src..src + len].chunks_exact(4);
+            let dst = frame[dst..dst + len].chunks_exact_mut(4);
+            for (dst, src) in dst.zip(src) {
+                // Fully transparent pixels are skipped
+                if src[3] > 0 {
+                    dst.copy_from_slice(&order.from_rgba(src.try_into().unwrap()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+
+    #[test]
+    fn test_blit_clipping() {
+        // A 3x3 sprite with a transparent center
+        let mut pixels = RED.repeat(9);
+        pixels[16..20].copy_from_slice(&[0; 4]);
+        let sprite = Sprite::from_rgba(3, 3, pixels);
+
+        // Drawing across the top-left corner
+        let mut frame = vec![0; 4 * 4 * 4];
+        sprite.blit(&mut frame, 4, (-1, -1), ChannelOrder::Rgba);
+        let drawn: Vec<_> = frame.chunks_exact(4).map(|p| p == RED).collect();
+        assert_eq!(
+            drawn,
+            [
+                false, true, false, false, //
+                true, true, false, false, //
+                false, false, false, false, //
+                false, false, false, false, //
+            ]
+        );
+
+        // Drawing across the bottom-right corner, swizzled to BGRA
+        let mut frame = vec![0; 4 * 4 * 4];
+        sprite.blit(&mut frame, 4, (3, 3), ChannelOrder::Bgra);
+        assert_eq!(frame[60..], [0x00, 0x00, 0xff, 0xff]);
+        assert_eq!(frame.iter().filter(|&&byte| byte != 0).count(), 2);
+
+        // Drawing entirely outside of the frame does nothing
+        let mut frame = vec![0; 4 * 4 * 4];
+        for pos in [(-3, 0), (0, -3), (4, 0), (0, 4), (i32::MIN, i32::MAX)] {
+            sprite.blit(&mut frame, 4, pos, ChannelOrder::Rgba);
+        }
+        assert!(frame.iter().all(|&byte| byte == 0));
+    }
+}
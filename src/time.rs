@@ -0,0 +1,114 @@
+//! Fixed timestep helper for game loops.
+//!
+//! Simulations are easiest to reason about when they are updated at a fixed rate, independent of
+//! how often frames are rendered. [`FixedTimestep`] accumulates the real elapsed time between
+//! frames, and tells the game loop how many fixed updates to run to catch up.
+//!
+//! ```
+//! use pixels::time::FixedTimestep;
+//! use std::time::Duration;
+//!
+//! let mut timestep = FixedTimestep::new(60);
+//!
+//! // A frame took 40ms, so the simulation runs two 60 Hz updates and carries the remainder
+//! for _ in 0..timestep.advance(Duration::from_millis(40)) {
+//!     // Update the game state by one step...
+//! }
+//! ```
+
+use std::time::Duration;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Converts real elapsed time into a whole number of fixed steps.
+///
+/// Time is accumulated exactly, so e.g. 60 steps per second are produced at 60 FPS even though
+/// one step is not a whole number of nanoseconds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixedTimestep {
+    fps: u32,
+    /// Accumulated time that has not been consumed by steps, in units of `1 / (fps * 10^9)`
+    /// seconds.
+    accumulator: u128,
+}
+
+impl FixedTimestep {
+    /// Create a fixed timestep that runs `fps` steps per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `fps` is 0.
+    pub fn new(fps: u32) -> Self {
+        assert!(fps > 0);
+
+        Self {
+            fps,
+            accumulator: 0,
+        }
+    }
+
+    /// The number of steps per second.
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// The duration of one step, rounded down to whole nanoseconds.
+    pub fn step(&self) -> Duration {
+        Duration::from_nanos((NANOS_PER_SEC / self.fps as u128) as u64)
+    }
+
+    /// Add the real time elapsed since the last frame, and get the number of steps to run.
+    ///
+    /// The time left over that is not enough for a whole step is carried to the next call.
+    pub fn advance(&mut self, real_dt: Duration) -> usize {
+        self.accumulator += real_dt.as_nanos() * self.fps as u128;
+
+        let steps = self.accumulator / NANOS_PER_SEC;
+        self.accumulator %= NANOS_PER_SEC;
+
+        steps as usize
+    }
+
+    /// How far the carried time is into the next step, from `0.0` up to (but not including) `1.0`.
+    ///
+    /// This can be used to interpolate between the previous and current state when rendering.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator as f64 / NANOS_PER_SEC as f64
+    }
+
+    /// Discard the carried time, e.g. after the game has been paused.
+    pub fn reset(&mut self) {
+        self.accumulator = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractional_accumulation() {
+        let mut timestep = FixedTimestep::new(60);
+        assert_eq!(timestep.step(), Duration::from_nanos(16_666_666));
+
+        // A step is 16.67ms, so 10ms frames run a step every other frame or so
+        let steps: Vec<_> = (0..5)
+            .map(|_| timestep.advance(Duration::from_millis(10)))
+            .collect();
+        assert_eq!(steps, [0, 1, 0, 1, 1]);
+        assert_eq!(timestep.alpha(), 0.0);
+
+        // Long frames run several steps, and carry the remainder
+        assert_eq!(timestep.advance(Duration::from_millis(40)), 2);
+        assert!((timestep.alpha() - 0.4).abs() < 1e-9);
+        timestep.reset();
+        assert_eq!(timestep.alpha(), 0.0);
+
+        // There is no drift over many frames
+        let mut timestep = FixedTimestep::new(60);
+        let steps: usize = (0..1000)
+            .map(|_| timestep.advance(Duration::from_millis(1)))
+            .sum();
+        assert_eq!(steps, 60);
+    }
+}
@@ -0,0 +1,49 @@
+//! CPU time measurement for [`Pixels::last_render_time`].
+//!
+//! [`Pixels::last_render_time`]: crate::Pixels::last_render_time
+
+use std::time::Duration;
+
+/// Measures the time elapsed since it was started.
+///
+/// `std::time::Instant` panics on Web targets, so the browser's `Performance` clock is used
+/// instead.
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+}
+
+impl Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn start() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn start() -> Self {
+        Self { start: now() }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64((now() - self.start).max(0.0) / 1000.0)
+    }
+}
+
+/// The current time in milliseconds, or 0 when the clock is not available (e.g. in a worker
+/// without a `Window`).
+#[cfg(target_arch = "wasm32")]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
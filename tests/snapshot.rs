@@ -0,0 +1,863 @@
+//! Headless snapshot tests for the upload and render path.
+//!
+//! These tests build an offscreen pixel buffer on the fallback adapter, so they can run on CI
+//! software rasterizers. They are skipped when no fallback adapter is available, unless the
+//! `PIXELS_REQUIRE_ADAPTER` environment variable is set.
+
+use pixels::ultraviolet::{Mat4, Vec3};
+use pixels::{wgpu, Error, Offscreen, Pixels, PixelsBuilder, TextureError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const WIDTH: u32 = 8;
+const HEIGHT: u32 = 8;
+
+type OffscreenBuilder = PixelsBuilder<'static, 'static, 'static, Offscreen>;
+
+/// Create a builder for an offscreen pixel buffer on the fallback adapter.
+fn offscreen_builder(surface_width: u32, surface_height: u32) -> OffscreenBuilder {
+    PixelsBuilder::new_offscreen(WIDTH, HEIGHT, surface_width, surface_height)
+        .request_adapter_options(wgpu::RequestAdapterOptions {
+            force_fallback_adapter: true,
+            ..wgpu::RequestAdapterOptions::default()
+        })
+}
+
+/// Build an offscreen pixel buffer, or `None` if there is no adapter to run on.
+fn build_offscreen(surface_width: u32, surface_height: u32) -> Option<Pixels<'static>> {
+    build_offscreen_with(surface_width, surface_height, |builder| builder)
+}
+
+/// Build an offscreen pixel buffer with the options set by `options`, or `None` if there is no
+/// adapter to run on.
+fn build_offscreen_with(
+    surface_width: u32,
+    surface_height: u32,
+    options: impl FnOnce(OffscreenBuilder) -> OffscreenBuilder,
+) -> Option<Pixels<'static>> {
+    let builder =
+        offscreen_builder(surface_width, surface_height).device_timeout(Duration::from_secs(60));
+
+    match options(builder).build() {
+        Ok(pixels) => Some(pixels),
+        Err(Error::AdapterNotFound) => {
+            // Without this, every test would pass vacuously on a runner that cannot run them
+            if std::env::var_os("PIXELS_REQUIRE_ADAPTER").is_some() {
+                panic!("No fallback adapter available, and PIXELS_REQUIRE_ADAPTER is set");
+            }
+            eprintln!("Skipping snapshot test: no fallback adapter available");
+            None
+        }
+        Err(err) => panic!("Unable to create offscreen pixel buffer: {err}"),
+    }
+}
+
+/// Write a deterministic pattern with distinct values in every channel.
+fn draw_pattern(pixels: &mut Pixels) {
+    for (x, y, pixel) in pixels.frame_chunks_mut() {
+        *pixel = [x as u8 * 32, y as u8 * 32, (x ^ y) as u8 * 32, 0xff];
+    }
+}
+
+#[test]
+fn snapshot_pattern() {
+    // A 3x integer scale with a border on each side
+    let Some(mut pixels) = build_offscreen(WIDTH * 3 + 5, HEIGHT * 3 + 7) else {
+        return;
+    };
+
+    assert_eq!(pixels.scale_factor(), 3.0);
+
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+
+    let frame = pixels.read_frame().unwrap();
+    assert_eq!(frame, include_bytes!("snapshots/pattern_8x8.rgba"));
+
+    // The odd surface size catches off-by-one errors in the scaling transform and the clip rect
+    assert_eq!(pixels.context().clip_rect(), (2, 3, WIDTH * 3, HEIGHT * 3));
+    let surface = pixels.read_surface().unwrap();
+    assert_eq!(surface, include_bytes!("snapshots/pattern_29x31.rgba"));
+}
+
+#[test]
+fn snapshot_after_resize() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+
+    // Resizing recreates the backing texture and the offscreen render target
+    pixels.resize_buffer(WIDTH * 2, HEIGHT * 2).unwrap();
+    pixels.resize_surface(WIDTH * 5, HEIGHT * 3).unwrap();
+    pixels.resize_buffer(WIDTH, HEIGHT).unwrap();
+    assert_eq!(pixels.scale_factor(), 3.0);
+
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+
+    let frame = pixels.read_frame().unwrap();
+    assert_eq!(frame, include_bytes!("snapshots/pattern_8x8.rgba"));
+}
+
+#[test]
+fn minimized_surface() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+
+    // Rendering to a zero-sized surface does nothing, and there is nothing to read
+    pixels.resize_surface(0, 0).unwrap();
+    pixels.render().unwrap();
+    assert!(pixels.read_surface().unwrap().is_empty());
+    pixels.resize_surface(WIDTH * 3, 0).unwrap();
+    pixels.render().unwrap();
+    assert!(pixels.read_surface().unwrap().is_empty());
+
+    // Restoring the surface renders as before
+    pixels.resize_surface(WIDTH * 3, HEIGHT * 3).unwrap();
+    pixels.render().unwrap();
+    assert_eq!(pixels.read_surface().unwrap(), surface);
+}
+
+#[test]
+fn snapshot_render_async() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+
+    // The rendered surface is the same, whether or not the GPU work is awaited
+    pixels.clear_black();
+    pixels.render().unwrap();
+    draw_pattern(&mut pixels);
+    pollster::block_on(pixels.render_async()).unwrap();
+    assert_eq!(pixels.read_surface().unwrap(), surface);
+}
+
+#[test]
+fn color_write_mask() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    pixels.frame_mut().fill(0xff);
+
+    // Only the red channel is written over the clear color
+    pixels.set_color_write_mask(wgpu::ColorWrites::RED);
+    assert_eq!(pixels.color_write_mask(), wgpu::ColorWrites::RED);
+    pixels.clear_color(wgpu::Color::BLUE);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    assert!(surface
+        .chunks_exact(4)
+        .all(|p| p == [0xff, 0x00, 0xff, 0xff]));
+
+    // The mask survives resizing the pixel buffer
+    pixels.resize_buffer(WIDTH, HEIGHT).unwrap();
+    pixels.frame_mut().fill(0xff);
+    pixels.render().unwrap();
+    assert_eq!(pixels.read_surface().unwrap(), surface);
+}
+
+#[test]
+fn snapshot_shared_device() {
+    let Some(pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+
+    // The second pixel buffer renders with the same device as the first
+    let context = pixels.context();
+    let mut other = PixelsBuilder::new_offscreen(WIDTH, HEIGHT, WIDTH * 3, HEIGHT * 3)
+        .with_device(
+            pixels.instance().clone(),
+            pixels.adapter().clone(),
+            context.device.clone(),
+            context.queue.clone(),
+        )
+        .build()
+        .unwrap();
+    assert!(Arc::ptr_eq(&other.context().device, &context.device));
+
+    draw_pattern(&mut other);
+    other.render().unwrap();
+
+    let frame = other.read_frame().unwrap();
+    assert_eq!(frame, include_bytes!("snapshots/pattern_8x8.rgba"));
+}
+
+#[test]
+fn device_features() {
+    let Some(pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    let builder = || offscreen_builder(WIDTH, HEIGHT);
+
+    // Offscreen pixel buffers have no surface
+    assert!(pixels.surface_capabilities().is_none());
+
+    // Everything the adapter supports can be required
+    let supported = pixels.adapter().features();
+    let other = builder().device_features(supported).build().unwrap();
+    assert!(other.features().contains(supported));
+
+    // No adapter supports every feature
+    let all = wgpu::Features::all();
+    match builder().device_features(all).build() {
+        Err(Error::UnsupportedFeatures(missing)) => assert_eq!(missing, all - supported),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("All features are not supported"),
+    }
+}
+
+#[test]
+fn initial_buffer_color() {
+    const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+
+    let Some(pixels) = build_offscreen_with(WIDTH * 2 + 2, HEIGHT * 2 + 2, |builder| {
+        builder
+            .clear_color(wgpu::Color::BLUE)
+            .initial_buffer_color(&RED)
+    }) else {
+        return;
+    };
+    assert!(pixels.frame().chunks_exact(4).all(|pixel| pixel == RED));
+
+    // The clear color only fills the border around the pixel buffer
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let (left, top, width, height) = pixels.context().clip_rect();
+    let surface_width = WIDTH * 2 + 2;
+    for (i, pixel) in surface.chunks_exact(4).enumerate() {
+        let x = i as u32 % surface_width;
+        let y = i as u32 / surface_width;
+        let inside = (left..left + width).contains(&x) && (top..top + height).contains(&y);
+        let expected = if inside {
+            RED
+        } else {
+            [0x00, 0x00, 0xff, 0xff]
+        };
+        assert_eq!(pixel, expected, "surface pixel at ({x}, {y})");
+    }
+}
+
+#[test]
+fn resize_buffer_preserve() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+
+    // The pixel buffer is resized with its contents, see `buffer::copy_overlap` for the cropping
+    const RED: [u8; 4] = [0xff, 0x00, 0x00, 0xff];
+    pixels.frame_mut()[..4].copy_from_slice(&RED);
+    pixels
+        .resize_buffer_preserve(WIDTH * 2, HEIGHT + 3)
+        .unwrap();
+    assert_eq!(pixels.buffer_size(), (WIDTH * 2, HEIGHT + 3));
+    assert_eq!(
+        pixels.frame().len(),
+        (WIDTH * 2 * (HEIGHT + 3) * 4) as usize
+    );
+    assert_eq!(pixels.frame()[..4], RED);
+
+    // The pixel buffer is untouched on error
+    assert!(pixels.resize_buffer_preserve(0, 6).is_err());
+    assert_eq!(pixels.buffer_size(), (WIDTH * 2, HEIGHT + 3));
+    assert_eq!(pixels.frame()[..4], RED);
+}
+
+#[test]
+fn texture_too_large() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+
+    // The error reports the device limit that was exceeded
+    let max = pixels.device().limits().max_texture_dimension_2d;
+    match pixels.resize_buffer(WIDTH, max + 1) {
+        Err(TextureError::TooLarge {
+            requested,
+            max: limit,
+        }) => {
+            assert_eq!(requested, max + 1);
+            assert_eq!(limit, max);
+        }
+        result => panic!("Expected `TextureError::TooLarge`, got {result:?}"),
+    }
+    assert!(matches!(
+        pixels.resize_surface(max + 1, HEIGHT),
+        Err(TextureError::TooLarge { .. })
+    ));
+    assert!(matches!(
+        pixels.resize_buffer(0, HEIGHT),
+        Err(TextureError::TextureWidth(0))
+    ));
+}
+
+#[test]
+fn set_texture_format() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+
+    // Switching to an HDR format resizes the pixel buffer and keeps the scaling settings
+    let viewport = (1, 2, 3, 4);
+    pixels.set_viewport(viewport.0, viewport.1, viewport.2, viewport.3);
+    pixels
+        .set_texture_format(wgpu::TextureFormat::Rgba16Float)
+        .unwrap();
+    assert_eq!(
+        pixels.context().texture_format,
+        wgpu::TextureFormat::Rgba16Float
+    );
+    assert_eq!(pixels.context().texture_format_size, 8.0);
+    assert_eq!(pixels.frame().len(), (WIDTH * HEIGHT * 8) as usize);
+    assert!(pixels.frame().iter().all(|&byte| byte == 0));
+    assert_eq!(pixels.viewport(), viewport);
+    pixels.render().unwrap();
+
+    // Formats that cannot be written from the CPU and sampled are rejected
+    for format in [
+        wgpu::TextureFormat::Bc1RgbaUnorm,
+        wgpu::TextureFormat::Depth32Float,
+        wgpu::TextureFormat::Rgba8Uint,
+    ] {
+        assert!(matches!(
+            pixels.set_texture_format(format),
+            Err(TextureError::UnsupportedFormat(f)) if f == format
+        ));
+    }
+    assert_eq!(pixels.frame().len(), (WIDTH * HEIGHT * 8) as usize);
+
+    // And back again
+    pixels
+        .set_texture_format(wgpu::TextureFormat::Rgba8UnormSrgb)
+        .unwrap();
+    assert_eq!(pixels.frame().len(), (WIDTH * HEIGHT * 4) as usize);
+    pixels.render().unwrap();
+}
+
+#[test]
+fn present_mode() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+
+    // Offscreen pixel buffers support every present mode
+    let present_modes = pixels.supported_present_modes().to_vec();
+    assert!(present_modes.contains(&wgpu::PresentMode::Mailbox));
+    for present_mode in present_modes {
+        pixels.set_present_mode(present_mode).unwrap();
+        assert_eq!(pixels.present_mode(), present_mode);
+    }
+    pixels
+        .set_present_mode(wgpu::PresentMode::AutoNoVsync)
+        .unwrap();
+    assert_eq!(pixels.present_mode(), wgpu::PresentMode::AutoNoVsync);
+    pixels.render().unwrap();
+}
+
+#[test]
+fn view_formats() {
+    let Some(pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    let flags = pixels.adapter().get_downlevel_capabilities().flags;
+    let builder = |view_formats: &[wgpu::TextureFormat]| {
+        offscreen_builder(WIDTH, HEIGHT)
+            .view_formats(view_formats)
+            .build()
+    };
+
+    // Formats that are not compatible with any texture are rejected
+    assert!(matches!(
+        builder(&[wgpu::TextureFormat::Rgba16Float]),
+        Err(Error::UnsupportedViewFormat(
+            wgpu::TextureFormat::Rgba16Float
+        ))
+    ));
+
+    // Adapters that cannot reinterpret textures reject every view format
+    let result = builder(&[wgpu::TextureFormat::Rgba8Unorm]);
+    if !flags.contains(wgpu::DownlevelFlags::VIEW_FORMATS) {
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedViewFormat(
+                wgpu::TextureFormat::Rgba8Unorm
+            ))
+        ));
+        return;
+    }
+
+    // The sRGB texture can be viewed as linear
+    let pixels = result.unwrap();
+    let device = pixels.device();
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    pixels
+        .context()
+        .texture
+        .create_view(&wgpu::TextureViewDescriptor {
+            format: Some(wgpu::TextureFormat::Rgba8Unorm),
+            ..wgpu::TextureViewDescriptor::default()
+        });
+    let error = pollster::block_on(device.pop_error_scope());
+    assert!(error.is_none(), "Unexpected error: {error:?}");
+    pixels.render().unwrap();
+}
+
+#[test]
+fn generate_mipmaps() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    drop(pixels);
+
+    let builder = |texture_format| {
+        offscreen_builder(WIDTH * 3, HEIGHT * 3)
+            .texture_format(texture_format)
+            .generate_mipmaps(true)
+            .build()
+    };
+
+    // Formats that cannot be rendered to are rejected
+    assert!(matches!(
+        builder(wgpu::TextureFormat::Rgb9e5Ufloat),
+        Err(Error::InvalidTexture(TextureError::UnsupportedFormat(
+            wgpu::TextureFormat::Rgb9e5Ufloat
+        )))
+    ));
+
+    // Magnification is unchanged
+    let mut pixels = builder(wgpu::TextureFormat::Rgba8UnormSrgb).unwrap();
+    assert_eq!(pixels.context().texture.mip_level_count(), 4);
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    assert_eq!(pixels.read_surface().unwrap(), surface);
+
+    // A checkerboard drawn at a quarter of its size is filtered to gray
+    for (x, y, pixel) in pixels.frame_chunks_mut() {
+        *pixel = if (x ^ y) & 1 == 0 {
+            [0xff; 4]
+        } else {
+            [0, 0, 0, 0xff]
+        };
+    }
+    pixels.set_scaling_matrix(Mat4::from_scale(0.25));
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let center = ((HEIGHT * 3 / 2 * WIDTH * 3 + WIDTH * 3 / 2) * 4) as usize;
+    for &channel in &surface[center..center + 3] {
+        assert!((0x80..0xf0).contains(&channel), "channel: {channel:#x}");
+    }
+
+    // Writing a region regenerates the mipmaps, too
+    let white = vec![0xff; (WIDTH * HEIGHT * 4) as usize];
+    pixels.write_region(0, 0, WIDTH, HEIGHT, &white);
+    pixels
+        .render_with_no_upload(|encoder, render_target, context| {
+            context.scaling_renderer.render(encoder, render_target);
+            Ok(())
+        })
+        .unwrap();
+    let surface = pixels.read_surface().unwrap();
+    assert_eq!(surface[center..center + 4], [0xff; 4]);
+}
+
+#[test]
+fn render_with_no_upload() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+
+    // The texture keeps the last uploaded pixel buffer
+    pixels.clear_black();
+    pixels
+        .render_with_no_upload(|encoder, render_target, context| {
+            context.scaling_renderer.render(encoder, render_target);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(pixels.read_surface().unwrap(), surface);
+
+    // Until the next upload
+    pixels.render().unwrap();
+    assert_ne!(pixels.read_surface().unwrap(), surface);
+}
+
+#[test]
+fn frame_callback() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let recording = Arc::clone(&frames);
+    pixels.set_frame_callback(Some(Box::new(move |frame, width, height| {
+        recording
+            .lock()
+            .unwrap()
+            .push((frame.to_vec(), width, height));
+    })));
+
+    // Called once for each render, with the uploaded pixel buffer
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    pixels
+        .render_with_no_upload(|encoder, render_target, context| {
+            context.scaling_renderer.render(encoder, render_target);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(
+        *frames.lock().unwrap(),
+        [(pixels.frame().to_vec(), WIDTH, HEIGHT)]
+    );
+
+    // Until it is removed
+    pixels.set_frame_callback(None);
+    pixels.render().unwrap();
+    assert_eq!(frames.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn premultiply_alpha() {
+    let Some(mut pixels) = build_offscreen_with(WIDTH, HEIGHT, |builder| {
+        builder.blend_state(wgpu::BlendState::REPLACE)
+    }) else {
+        return;
+    };
+    let white = [0xff, 0xff, 0xff, 0x80];
+    for pixel in pixels.frame_mut().chunks_exact_mut(4) {
+        pixel.copy_from_slice(&white);
+    }
+    pixels.render().unwrap();
+    assert_eq!(pixels.read_surface().unwrap()[..4], white);
+
+    // The uploaded colors are premultiplied, but the pixel buffer is unchanged
+    pixels.set_premultiply_alpha(true);
+    pixels.render().unwrap();
+    assert_eq!(
+        pixels.read_surface().unwrap()[..4],
+        [0xbc, 0xbc, 0xbc, 0x80]
+    );
+    assert_eq!(pixels.frame()[..4], white);
+}
+
+#[test]
+fn write_region() {
+    let Some(mut pixels) = build_offscreen(16, 16) else {
+        return;
+    };
+    pixels.resize_buffer(16, 16).unwrap();
+    pixels.clear([0x00, 0x00, 0x00, 0xff]);
+    pixels.render().unwrap();
+
+    // Upload a 4x4 region with a distinct value in every pixel
+    let region: Vec<u8> = (0..16u8)
+        .flat_map(|i| [i * 16, 0xff - i, i, 0xff])
+        .collect();
+    pixels.write_region(5, 9, 4, 4, &region);
+
+    let frame = pixels.read_frame().unwrap();
+    for (i, pixel) in frame.chunks_exact(4).enumerate() {
+        let (x, y) = (i % 16, i / 16);
+        if (5..9).contains(&x) && (9..13).contains(&y) {
+            let j = ((x - 5) + (y - 9) * 4) * 4;
+            assert_eq!(pixel, &region[j..j + 4], "pixel at ({x}, {y})");
+        } else {
+            assert_eq!(pixel, [0x00, 0x00, 0x00, 0xff], "pixel at ({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn viewport() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 5, HEIGHT * 4) else {
+        return;
+    };
+
+    // A 2x scale in a 20x16 viewport, with a 2px border on each side
+    pixels.set_viewport(10, 5, WIDTH * 2 + 4, HEIGHT * 2);
+    assert_eq!(pixels.scale_factor(), 2.0);
+    assert_eq!(pixels.context().clip_rect(), (12, 5, WIDTH * 2, HEIGHT * 2));
+
+    // Window positions are relative to the viewport
+    assert_eq!(pixels.window_pos_to_pixel((12.0, 5.0)), Ok((0, 0)));
+    assert_eq!(pixels.window_pos_to_pixel((27.5, 20.5)), Ok((7, 7)));
+    assert!(pixels.window_pos_to_pixel((11.0, 5.0)).is_err());
+
+    // The viewport is kept when resizing, and clamped to the surface
+    pixels.resize_buffer(WIDTH, HEIGHT).unwrap();
+    pixels.resize_surface(WIDTH * 3, HEIGHT * 2).unwrap();
+    assert_eq!(pixels.viewport(), (10, 5, WIDTH * 3 - 10, HEIGHT * 2 - 5));
+    assert_eq!(pixels.scale_factor(), 1.0);
+    pixels.render().unwrap();
+
+    pixels.reset_viewport();
+    assert_eq!(pixels.viewport(), (0, 0, WIDTH * 3, HEIGHT * 2));
+    assert_eq!(pixels.scale_factor(), 2.0);
+}
+
+#[test]
+fn source_rect() {
+    let Some(mut pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    assert_eq!(pixels.source_rect(), (0, 0, WIDTH, HEIGHT));
+
+    // The bottom-right quarter is zoomed in 2x to fill the surface
+    pixels.set_source_rect(WIDTH / 2, HEIGHT / 2, WIDTH / 2, HEIGHT / 2);
+    assert_eq!(pixels.scale_factor(), 2.0);
+    assert_eq!(pixels.context().clip_rect(), (0, 0, WIDTH, HEIGHT));
+    assert_eq!(pixels.window_pos_to_pixel((0.5, 0.5)), Ok((4, 4)));
+    assert_eq!(pixels.window_pos_to_pixel((7.5, 7.5)), Ok((7, 7)));
+
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let frame = pixels.frame();
+    for (i, pixel) in surface.chunks_exact(4).enumerate() {
+        let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+        let j = ((x / 2 + 4 + (y / 2 + 4) * WIDTH) * 4) as usize;
+        assert_eq!(pixel, &frame[j..j + 4], "pixel at ({x}, {y})");
+    }
+
+    // The source rectangle is kept while it fits within the pixel buffer
+    pixels.resize_buffer(WIDTH, HEIGHT).unwrap();
+    assert_eq!(pixels.source_rect(), (4, 4, 4, 4));
+    pixels.resize_buffer(WIDTH / 2, HEIGHT / 2).unwrap();
+    assert_eq!(pixels.source_rect(), (0, 0, WIDTH / 2, HEIGHT / 2));
+
+    pixels.set_source_rect(1, 0, 2, 4);
+    pixels.reset_source_rect();
+    assert_eq!(pixels.source_rect(), (0, 0, WIDTH / 2, HEIGHT / 2));
+    assert_eq!(pixels.window_pos_to_pixel((0.5, 0.5)), Ok((0, 0)));
+}
+
+#[test]
+fn window_pos_to_pixel_f32() {
+    // A 2x scale with a 1px border on the left and top
+    let Some(pixels) = build_offscreen(WIDTH * 2 + 2, HEIGHT * 2 + 2) else {
+        return;
+    };
+    assert_eq!(pixels.scale_factor(), 2.0);
+
+    // The center of a surface pixel is a quarter of the way into a buffer pixel
+    assert_eq!(
+        pixels.window_pos_to_pixel_f32((1.5, 1.5)),
+        Some((0.25, 0.25))
+    );
+    assert_eq!(
+        pixels.window_pos_to_pixel_f32((8.5, 5.5)),
+        Some((3.75, 2.25))
+    );
+    assert_eq!(pixels.window_pos_to_pixel((8.5, 5.5)), Ok((3, 2)));
+
+    // Positions in the border are outside of the pixel buffer
+    assert_eq!(pixels.window_pos_to_pixel_f32((0.5, 1.5)), None);
+    assert_eq!(pixels.window_pos_to_pixel_f32((17.5, 16.5)), None);
+}
+
+#[test]
+fn scaling_matrix() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 4, HEIGHT * 4) else {
+        return;
+    };
+    let default_matrix = pixels.scaling_matrix();
+
+    // Zoom in 2x on the bottom-right quarter of the pixel buffer
+    let pan = Mat4::from_translation(Vec3::new(-0.5, 0.5, 0.0));
+    pixels.set_scaling_matrix(Mat4::from_scale(2.0) * pan);
+    assert_eq!(pixels.context().clip_rect(), (0, 0, WIDTH * 4, HEIGHT * 4));
+    assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((4, 4)));
+    assert_eq!(pixels.window_pos_to_pixel((31.5, 31.5)), Ok((7, 7)));
+
+    // Every pixel in the quarter is drawn 8x8 on the surface
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let frame = pixels.frame();
+    for (i, pixel) in surface.chunks_exact(4).enumerate() {
+        let (x, y) = (i as u32 % (WIDTH * 4), i as u32 / (WIDTH * 4));
+        let j = ((x / 8 + 4 + (y / 8 + 4) * WIDTH) * 4) as usize;
+        assert_eq!(pixel, &frame[j..j + 4], "pixel at ({x}, {y})");
+    }
+
+    // The custom transform is kept when resizing, until it is reset
+    pixels.resize_surface(WIDTH * 2, HEIGHT * 2).unwrap();
+    pixels.resize_buffer(WIDTH, HEIGHT).unwrap();
+    assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((4, 4)));
+
+    pixels.resize_surface(WIDTH * 4, HEIGHT * 4).unwrap();
+    pixels.reset_scaling_matrix();
+    assert_eq!(pixels.scaling_matrix(), default_matrix);
+    assert_eq!(pixels.context().clip_rect(), (0, 0, WIDTH * 4, HEIGHT * 4));
+    assert_eq!(pixels.window_pos_to_pixel((0.0, 0.0)), Ok((0, 0)));
+}
+
+#[test]
+fn snapshot_surface() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3 + 5, HEIGHT * 3 + 7) else {
+        return;
+    };
+
+    draw_pattern(&mut pixels);
+    pixels.render().unwrap();
+
+    let frame = pixels.read_frame().unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let (surface_width, surface_height) = (WIDTH * 3 + 5, HEIGHT * 3 + 7);
+    assert_eq!(surface.len(), (surface_width * surface_height * 4) as usize);
+
+    // Each pixel is scaled up 3x, surrounded by a black border
+    let (left, top, width, height) = pixels.context().clip_rect();
+    for (i, pixel) in surface.chunks_exact(4).enumerate() {
+        let x = i as u32 % surface_width;
+        let y = i as u32 / surface_width;
+        let expected = if (left..left + width).contains(&x) && (top..top + height).contains(&y) {
+            let j = (((x - left) / 3 + (y - top) / 3 * WIDTH) * 4) as usize;
+            &frame[j..j + 4]
+        } else {
+            &[0x00, 0x00, 0x00, 0xff]
+        };
+
+        assert_eq!(pixel, expected, "surface pixel at ({x}, {y})");
+    }
+}
+
+#[test]
+fn pixel_aspect_ratio() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 4, HEIGHT * 2) else {
+        return;
+    };
+
+    // 2:1 pixels fill the surface at 2x scale
+    pixels.set_pixel_aspect_ratio(2.0);
+    assert_eq!(pixels.scale_factor(), 2.0);
+    assert_eq!(pixels.context().clip_rect(), (0, 0, WIDTH * 4, HEIGHT * 2));
+
+    // Window positions are mapped back through the pixel aspect ratio
+    assert_eq!(pixels.window_pos_to_pixel((3.5, 0.5)), Ok((0, 0)));
+    assert_eq!(pixels.window_pos_to_pixel((4.5, 2.5)), Ok((1, 1)));
+    assert_eq!(pixels.window_pos_to_pixel((31.5, 15.5)), Ok((7, 7)));
+
+    // The pixel aspect ratio is kept when resizing
+    pixels.resize_buffer(WIDTH / 2, HEIGHT / 2).unwrap();
+    assert_eq!(pixels.pixel_aspect_ratio(), 2.0);
+    assert_eq!(pixels.scale_factor(), 4.0);
+    assert_eq!(pixels.window_pos_to_pixel((8.5, 4.5)), Ok((1, 1)));
+}
+
+#[test]
+fn render_passes() {
+    let Some(mut pixels) = build_offscreen(WIDTH * 3, HEIGHT * 3) else {
+        return;
+    };
+
+    pixels.add_render_pass(
+        "scale",
+        Box::new(|encoder, render_target, context| {
+            context.scaling_renderer.render(encoder, render_target);
+            Ok(())
+        }),
+    );
+    pixels.add_render_pass(
+        "red",
+        Box::new(|encoder, render_target, _| {
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: render_target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::RED),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            Ok(())
+        }),
+    );
+    draw_pattern(&mut pixels);
+
+    // Switch between the render passes
+    pixels.render_with_pass("red").unwrap();
+    let surface = pixels.read_surface().unwrap();
+    assert!(surface
+        .chunks_exact(4)
+        .all(|p| p == [0xff, 0x00, 0x00, 0xff]));
+
+    pixels.render_with_pass("scale").unwrap();
+    let scaled = pixels.read_surface().unwrap();
+    pixels.render().unwrap();
+    assert_eq!(scaled, pixels.read_surface().unwrap());
+
+    assert!(pixels.remove_render_pass("red").is_some());
+    assert!(pixels.remove_render_pass("red").is_none());
+
+    // Unregistered render passes are an error
+    assert!(matches!(
+        pixels.render_with_pass("red"),
+        Err(Error::UnknownRenderPass(id)) if id == "red"
+    ));
+}
+
+#[cfg(feature = "crt")]
+#[test]
+fn crt_effect() {
+    let (surface_width, surface_height) = (WIDTH * 4 + 6, HEIGHT * 4);
+    let Some(mut pixels) = build_offscreen(surface_width, surface_height) else {
+        return;
+    };
+    pixels.frame_mut().fill(0xff);
+
+    let crt = pixels::effects::Crt::new(&pixels, surface_width, surface_height).unwrap();
+    pixels
+        .render_with(|encoder, render_target, context| {
+            crt.render(encoder, render_target, context);
+            Ok(())
+        })
+        .unwrap();
+    let surface = pixels.read_surface().unwrap();
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * surface_width + x) * 4) as usize;
+        surface[i..i + 4].to_vec()
+    };
+
+    // The border is untouched
+    assert_eq!(pixel(0, surface_height / 2), [0x00, 0x00, 0x00, 0xff]);
+
+    // Scanlines are darker between rows of the pixel buffer than through their centers
+    let center = pixel(surface_width / 2, surface_height / 2 + 2);
+    let edge = pixel(surface_width / 2, surface_height / 2);
+    assert!(
+        center[0] > edge[0],
+        "{center:?} should be brighter than {edge:?}"
+    );
+
+    // The corners are pushed off of the screen by the barrel distortion
+    assert_eq!(pixel(3, 0), [0x00, 0x00, 0x00, 0xff]);
+}
+
+#[cfg(feature = "render-timing")]
+#[test]
+fn last_render_time() {
+    let Some(pixels) = build_offscreen(WIDTH, HEIGHT) else {
+        return;
+    };
+    assert_eq!(pixels.last_render_time(), Duration::ZERO);
+
+    pixels.render().unwrap();
+    assert!(pixels.last_render_time() > Duration::ZERO);
+}